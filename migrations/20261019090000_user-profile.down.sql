@@ -0,0 +1,6 @@
+-- Add down migration script here
+ALTER TABLE users
+DROP CONSTRAINT users_profile_link_key,
+DROP COLUMN bio,
+DROP COLUMN prompt,
+DROP COLUMN avatar;
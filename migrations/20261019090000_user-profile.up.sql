@@ -0,0 +1,24 @@
+-- Add up migration script here
+-- profile links become unique; a link taken by more than one user has to be
+-- sorted out by an operator first, as changing either would break it
+DO $$
+DECLARE
+  taken text;
+BEGIN
+  SELECT string_agg(profile_link, ', ') INTO taken FROM (
+    SELECT profile_link FROM users
+    WHERE profile_link IS NOT NULL
+    GROUP BY profile_link HAVING count(*) > 1
+  ) duplicates;
+  IF taken IS NOT NULL THEN
+    RAISE EXCEPTION 'profile links used by more than one user: %', taken
+      USING HINT = 'Change all but one of them, then run the migration again.';
+  END IF;
+END
+$$;
+
+ALTER TABLE users
+ADD COLUMN bio VARCHAR(160) DEFAULT NULL,
+ADD COLUMN prompt VARCHAR(120) DEFAULT NULL,
+ADD COLUMN avatar VARCHAR(255) DEFAULT NULL,
+ADD CONSTRAINT users_profile_link_key UNIQUE (profile_link);
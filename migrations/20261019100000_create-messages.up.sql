@@ -0,0 +1,13 @@
+-- Add up migration script here
+CREATE TABLE "messages"
+(
+  id uuid primary key default uuid_generate_v1mc(),
+  recipient_id uuid not null references "users" (id) on delete cascade,
+  body text not null,
+  read_at timestamp,
+  created_at timestamp not null default now(),
+  updated_at timestamp
+);
+SELECT trigger_updated_at('"messages"');
+
+CREATE INDEX messages_recipient_id_created_at_idx ON "messages" (recipient_id, created_at desc);
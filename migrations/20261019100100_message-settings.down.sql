@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TRIGGER create_message_settings ON "users";
+DROP FUNCTION create_message_settings();
+DROP TABLE "message_settings";
@@ -0,0 +1,35 @@
+-- Add up migration script here
+CREATE TABLE "message_settings"
+(
+  user_id uuid primary key references "users" (id) on delete cascade,
+  paused boolean not null default false,
+  schedule_enabled boolean not null default false,
+  accept_from time not null default '09:00',
+  accept_until time not null default '21:00',
+  timezone varchar(64) not null default 'UTC',
+  min_length integer not null default 1,
+  max_length integer not null default 1000,
+  closed_message varchar(200),
+  created_at timestamp not null default now(),
+  updated_at timestamp,
+  constraint message_settings_length_check check (min_length <= max_length)
+);
+SELECT trigger_updated_at('"message_settings"');
+
+-- every user always has a settings row, so readers never need to fall back to defaults
+CREATE OR REPLACE FUNCTION create_message_settings()
+  returns trigger as
+$$
+begin
+  insert into "message_settings" (user_id) values (NEW.id);
+  return NEW;
+end;
+$$ language plpgsql;
+
+CREATE TRIGGER create_message_settings
+  AFTER INSERT
+  ON "users"
+  FOR EACH ROW
+EXECUTE FUNCTION create_message_settings();
+
+INSERT INTO "message_settings" (user_id) SELECT id FROM "users";
@@ -0,0 +1,13 @@
+-- Add down migration script here
+DROP TABLE "blocklist_entries";
+DROP FUNCTION bump_blocklist_version();
+
+ALTER TABLE "message_settings"
+DROP COLUMN blocklist_action,
+DROP COLUMN blocklist_version;
+
+DROP INDEX messages_recipient_id_folder_created_at_idx;
+CREATE INDEX messages_recipient_id_created_at_idx ON "messages" (recipient_id, created_at desc);
+
+ALTER TABLE "messages"
+DROP COLUMN folder;
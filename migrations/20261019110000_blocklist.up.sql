@@ -0,0 +1,40 @@
+-- Add up migration script here
+ALTER TABLE "messages"
+ADD COLUMN folder VARCHAR(16) NOT NULL DEFAULT 'inbox' CHECK (folder IN ('inbox', 'filtered'));
+
+DROP INDEX messages_recipient_id_created_at_idx;
+CREATE INDEX messages_recipient_id_folder_created_at_idx ON "messages" (recipient_id, folder, created_at desc);
+
+ALTER TABLE "message_settings"
+ADD COLUMN blocklist_action VARCHAR(16) NOT NULL DEFAULT 'reject' CHECK (blocklist_action IN ('reject', 'drop', 'filter')),
+ADD COLUMN blocklist_version INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE "blocklist_entries"
+(
+  id uuid primary key default uuid_generate_v1mc(),
+  user_id uuid not null references "users" (id) on delete cascade,
+  kind VARCHAR(16) not null CHECK (kind IN ('word', 'phrase', 'regex')),
+  pattern VARCHAR(200) not null,
+  created_at timestamp not null default now(),
+  updated_at timestamp
+);
+SELECT trigger_updated_at('"blocklist_entries"');
+
+CREATE INDEX blocklist_entries_user_id_idx ON "blocklist_entries" (user_id);
+
+-- compiled blocklists are cached per user and keyed by this version
+CREATE OR REPLACE FUNCTION bump_blocklist_version()
+  returns trigger as
+$$
+begin
+  update "message_settings" set blocklist_version = blocklist_version + 1
+  where user_id = coalesce(NEW.user_id, OLD.user_id);
+  return null;
+end;
+$$ language plpgsql;
+
+CREATE TRIGGER bump_blocklist_version
+  AFTER INSERT OR UPDATE OR DELETE
+  ON "blocklist_entries"
+  FOR EACH ROW
+EXECUTE FUNCTION bump_blocklist_version();
@@ -0,0 +1,7 @@
+-- Add down migration script here
+DROP TABLE "sender_blocks";
+
+ALTER TABLE "messages"
+DROP COLUMN sender_fingerprint;
+
+DROP TABLE "fingerprint_salts";
@@ -0,0 +1,21 @@
+-- Add up migration script here
+CREATE TABLE "fingerprint_salts"
+(
+  day date primary key,
+  salt bytea not null,
+  created_at timestamp not null default now()
+);
+
+ALTER TABLE "messages"
+ADD COLUMN sender_fingerprint bytea DEFAULT NULL;
+
+-- a block keeps one row per daily digest it has been seen with, grouped by block_id
+CREATE TABLE "sender_blocks"
+(
+  block_id uuid not null,
+  recipient_id uuid not null references "users" (id) on delete cascade,
+  fingerprint bytea not null,
+  created_at timestamp not null default now(),
+  primary key (recipient_id, fingerprint)
+);
+CREATE INDEX sender_blocks_block_id_idx ON "sender_blocks" (block_id);
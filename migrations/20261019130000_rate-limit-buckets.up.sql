@@ -0,0 +1,11 @@
+-- Add up migration script here
+-- buckets are cheap to lose, so skip the write-ahead log
+CREATE UNLOGGED TABLE "rate_limit_buckets"
+(
+  key varchar(255) primary key,
+  tokens double precision not null,
+  allowed boolean not null,
+  -- when the bucket last ran dry
+  limited_at timestamptz,
+  updated_at timestamptz not null default now()
+);
@@ -0,0 +1,9 @@
+-- Add up migration script here
+-- challenges that have been spent, kept until they expire
+CREATE TABLE "pow_redemptions"
+(
+  nonce bytea primary key,
+  expires_at timestamptz not null
+);
+
+CREATE INDEX pow_redemptions_expires_at_idx ON "pow_redemptions" (expires_at);
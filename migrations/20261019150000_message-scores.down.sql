@@ -0,0 +1,9 @@
+-- Add down migration script here
+ALTER TABLE "message_settings" DROP COLUMN filter_threshold;
+
+DROP INDEX messages_content_hash_created_at_idx;
+
+ALTER TABLE "messages"
+  DROP COLUMN content_hash,
+  DROP COLUMN scores,
+  DROP COLUMN score;
@@ -0,0 +1,12 @@
+-- Add up migration script here
+ALTER TABLE "messages"
+  ADD COLUMN content_hash bytea,
+  ADD COLUMN scores jsonb not null default '{}',
+  ADD COLUMN score real not null default 0;
+
+CREATE INDEX messages_content_hash_created_at_idx ON "messages" (content_hash, created_at);
+
+-- scores never exceed 1, so the default filters nothing
+ALTER TABLE "message_settings"
+  ADD COLUMN filter_threshold real not null default 1
+    constraint message_settings_filter_threshold_check check (filter_threshold between 0 and 1);
@@ -0,0 +1,13 @@
+-- Add down migration script here
+DROP TABLE "platform_blocks";
+
+DROP TABLE "moderation_log";
+DROP FUNCTION reject_moderation_log_change();
+
+DROP TABLE "reports";
+
+ALTER TABLE "messages" DROP COLUMN platform_fingerprint;
+
+ALTER TABLE "users"
+  DROP COLUMN role,
+  DROP COLUMN suspended_at;
@@ -0,0 +1,82 @@
+-- Add up migration script here
+-- moderators are appointed in the database, eg:
+-- UPDATE "users" SET role = 'moderator' WHERE email = 'mod@example.com';
+ALTER TABLE "users"
+  ADD COLUMN role varchar(16) not null default 'user'
+    constraint users_role_check check (role in ('user', 'moderator')),
+  ADD COLUMN suspended_at timestamp;
+
+-- digest of the sender for the whole platform rather than one recipient
+ALTER TABLE "messages" ADD COLUMN platform_fingerprint bytea DEFAULT NULL;
+
+CREATE TABLE "reports"
+(
+  id uuid primary key default uuid_generate_v1mc(),
+  message_id uuid unique references "messages" (id) on delete set null,
+  recipient_id uuid not null references "users" (id) on delete cascade,
+  category varchar(32) not null
+    constraint reports_category_check
+      check (category in ('harassment', 'threat', 'hate', 'spam', 'self_harm', 'other')),
+  note varchar(1000),
+  -- copied from the message so the report outlives it
+  message_body text not null,
+  platform_fingerprint bytea,
+  status varchar(16) not null default 'open'
+    constraint reports_status_check check (status in ('open', 'claimed', 'resolved')),
+  claimed_by uuid references "users" (id) on delete set null,
+  claimed_at timestamp,
+  resolved_by uuid references "users" (id) on delete set null,
+  resolved_at timestamp,
+  resolution varchar(32)
+    constraint reports_resolution_check
+      check (resolution in ('dismiss', 'delete_message', 'block_sender', 'suspend_link')),
+  created_at timestamp not null default now(),
+  updated_at timestamp
+);
+SELECT trigger_updated_at('"reports"');
+
+CREATE INDEX reports_status_created_at_idx ON "reports" (status, created_at);
+
+-- no foreign keys, entries must survive the reports and users they mention
+CREATE TABLE "moderation_log"
+(
+  id uuid primary key default uuid_generate_v1mc(),
+  report_id uuid not null,
+  moderator_id uuid not null,
+  action varchar(32) not null,
+  note varchar(1000),
+  created_at timestamp not null default now()
+);
+
+CREATE INDEX moderation_log_report_id_idx ON "moderation_log" (report_id);
+CREATE INDEX moderation_log_created_at_idx ON "moderation_log" (created_at desc);
+
+CREATE OR REPLACE FUNCTION reject_moderation_log_change()
+  returns trigger as
+$$
+begin
+  raise exception 'moderation_log is append-only';
+end;
+$$ language plpgsql;
+
+CREATE TRIGGER moderation_log_append_only
+  BEFORE UPDATE OR DELETE
+  ON "moderation_log"
+  FOR EACH ROW
+EXECUTE FUNCTION reject_moderation_log_change();
+
+CREATE TRIGGER moderation_log_no_truncate
+  BEFORE TRUNCATE
+  ON "moderation_log"
+  FOR EACH STATEMENT
+EXECUTE FUNCTION reject_moderation_log_change();
+
+-- like sender_blocks, one row per daily digest the block has been seen with
+CREATE TABLE "platform_blocks"
+(
+  block_id uuid not null,
+  fingerprint bytea primary key,
+  report_id uuid,
+  created_at timestamp not null default now()
+);
+CREATE INDEX platform_blocks_block_id_idx ON "platform_blocks" (block_id);
@@ -0,0 +1,6 @@
+-- Add down migration script here
+ALTER TABLE "messages"
+  DROP COLUMN claim_hash,
+  DROP COLUMN reply,
+  DROP COLUMN reply_visibility,
+  DROP COLUMN replied_at;
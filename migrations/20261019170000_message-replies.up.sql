@@ -0,0 +1,8 @@
+-- Add up migration script here
+-- only a digest of the claim code is kept, the code itself goes to the sender
+ALTER TABLE "messages"
+  ADD COLUMN claim_hash bytea unique,
+  ADD COLUMN reply text,
+  ADD COLUMN reply_visibility varchar(16)
+    constraint messages_reply_visibility_check check (reply_visibility in ('private', 'public')),
+  ADD COLUMN replied_at timestamp;
@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP INDEX messages_recipient_id_published_at_idx;
+
+ALTER TABLE "messages"
+  DROP CONSTRAINT messages_published_reply_check,
+  DROP COLUMN published_at;
@@ -0,0 +1,9 @@
+-- Add up migration script here
+-- only public replies can be published
+ALTER TABLE "messages"
+  ADD COLUMN published_at timestamp,
+  ADD CONSTRAINT messages_published_reply_check
+    check (published_at is null or coalesce(reply_visibility = 'public', false));
+
+CREATE INDEX messages_recipient_id_published_at_idx ON "messages" (recipient_id, published_at desc)
+  WHERE published_at is not null;
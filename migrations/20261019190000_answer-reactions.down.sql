@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE "reactions";
+
+ALTER TABLE "messages" DROP COLUMN reactions_enabled;
@@ -0,0 +1,13 @@
+-- Add up migration script here
+ALTER TABLE "messages" ADD COLUMN reactions_enabled boolean not null default true;
+
+-- one row per reaction, the sender is only known by a rotating digest
+CREATE TABLE "reactions"
+(
+  message_id uuid not null references "messages" (id) on delete cascade,
+  reaction varchar(16) not null
+    constraint reactions_reaction_check check (reaction in ('like', 'love', 'laugh', 'wow', 'sad', 'fire')),
+  fingerprint bytea not null,
+  created_at timestamp not null default now(),
+  primary key (message_id, reaction, fingerprint)
+);
@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE "messages" DROP COLUMN link_id;
+
+DROP TABLE "links";
@@ -0,0 +1,32 @@
+-- Add up migration script here
+-- named links a user shares for separate campaigns, each with its own slug and
+-- overrides of the user's message settings, a null override falls back to them
+CREATE TABLE "links"
+(
+  id uuid primary key default uuid_generate_v1mc(),
+  user_id uuid not null references "users" (id) on delete cascade,
+  slug varchar(30) not null
+    constraint links_slug_key unique,
+  name varchar(60) not null,
+  prompt varchar(120),
+  enabled boolean not null default true,
+  expires_at timestamp,
+  min_length integer
+    constraint links_min_length_check check (min_length between 1 and 1000),
+  max_length integer
+    constraint links_max_length_check check (max_length between 1 and 1000),
+  closed_message varchar(200),
+  filter_threshold real
+    constraint links_filter_threshold_check check (filter_threshold between 0 and 1),
+  created_at timestamp not null default now(),
+  updated_at timestamp,
+  constraint links_length_check check (min_length <= max_length)
+);
+SELECT trigger_updated_at('"links"');
+
+CREATE INDEX links_user_id_created_at_idx ON "links" (user_id, created_at desc);
+
+-- messages sent to the profile link itself have no link
+ALTER TABLE "messages" ADD COLUMN link_id uuid references "links" (id) on delete set null;
+
+CREATE INDEX messages_link_id_created_at_idx ON "messages" (link_id, created_at desc) WHERE link_id IS NOT NULL;
@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE "links" DROP COLUMN max_messages, DROP COLUMN message_count;
@@ -0,0 +1,8 @@
+-- Add up migration script here
+-- a link with max_messages stops accepting once message_count reaches it, 1 makes it single-use
+ALTER TABLE "links"
+ADD COLUMN max_messages integer
+  constraint links_max_messages_check check (max_messages >= 1),
+ADD COLUMN message_count integer not null default 0;
+
+UPDATE "links" l SET message_count = (select count(*) from "messages" m where m.link_id = l.id);
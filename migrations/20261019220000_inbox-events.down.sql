@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP TRIGGER record_inbox_event ON "messages";
+DROP FUNCTION record_inbox_event();
+
+DROP TABLE "inbox_events";
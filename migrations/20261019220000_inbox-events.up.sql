@@ -0,0 +1,48 @@
+-- Add up migration script here
+-- changes to a user's inbox, kept for a day so event streams can resume after a
+-- disconnect, and announced on the "inbox_events" channel once committed
+CREATE TABLE "inbox_events"
+(
+  id bigserial primary key,
+  user_id uuid not null references "users" (id) on delete cascade,
+  kind varchar(16) not null
+    constraint inbox_events_kind_check check (kind in ('message', 'read')),
+  payload jsonb not null,
+  created_at timestamp not null default now()
+);
+
+CREATE INDEX inbox_events_user_id_id_idx ON "inbox_events" (user_id, id);
+CREATE INDEX inbox_events_created_at_idx ON "inbox_events" (created_at);
+
+CREATE OR REPLACE FUNCTION record_inbox_event()
+  returns trigger as
+$$
+declare
+  event_kind varchar(16);
+  event_payload jsonb;
+  event_id bigint;
+begin
+  if TG_OP = 'INSERT' then
+    event_kind := 'message';
+    event_payload := jsonb_build_object('message_id', NEW.id, 'folder', NEW.folder, 'link_id', NEW.link_id,
+      'created_at', NEW.created_at);
+  elsif NEW.read_at is distinct from OLD.read_at then
+    event_kind := 'read';
+    event_payload := jsonb_build_object('message_id', NEW.id, 'read_at', NEW.read_at);
+  else
+    return NEW;
+  end if;
+
+  insert into "inbox_events" (user_id, kind, payload) values (NEW.recipient_id, event_kind, event_payload)
+  returning id into event_id;
+  perform pg_notify('inbox_events', jsonb_build_object('id', event_id, 'user_id', NEW.recipient_id,
+    'kind', event_kind, 'payload', event_payload)::text);
+  return NEW;
+end;
+$$ language plpgsql;
+
+CREATE TRIGGER record_inbox_event
+  AFTER INSERT OR UPDATE OF read_at
+  ON "messages"
+  FOR EACH ROW
+EXECUTE FUNCTION record_inbox_event();
@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP TABLE "ama_questions";
+DROP FUNCTION notify_ama_question();
+
+DROP TABLE "ama_sessions";
+DROP FUNCTION notify_ama_ended();
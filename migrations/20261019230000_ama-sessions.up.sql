@@ -0,0 +1,66 @@
+-- Add up migration script here
+-- live "ask me anything" sessions: viewers ask anonymously, questions wait as
+-- pending until the host approves or hides them
+CREATE TABLE "ama_sessions"
+(
+  id uuid primary key default uuid_generate_v1mc(),
+  user_id uuid not null references "users" (id) on delete cascade,
+  title varchar(120) not null,
+  started_at timestamp not null default now(),
+  ended_at timestamp
+);
+
+CREATE INDEX ama_sessions_user_id_started_at_idx ON "ama_sessions" (user_id, started_at desc);
+
+CREATE TABLE "ama_questions"
+(
+  id uuid primary key default uuid_generate_v1mc(),
+  session_id uuid not null references "ama_sessions" (id) on delete cascade,
+  body varchar(500) not null,
+  status varchar(16) not null default 'pending'
+    constraint ama_questions_status_check check (status in ('pending', 'approved', 'hidden')),
+  answer varchar(1000),
+  answered_at timestamp,
+  created_at timestamp not null default now()
+);
+
+CREATE INDEX ama_questions_session_id_created_at_idx ON "ama_questions" (session_id, created_at);
+
+-- announces every change on the "ama_events" channel, so the sockets open on
+-- any instance hear about it
+CREATE OR REPLACE FUNCTION notify_ama_question()
+  returns trigger as
+$$
+begin
+  perform pg_notify('ama_events', jsonb_build_object(
+    'kind', 'question',
+    'ama_id', NEW.session_id,
+    'previous_status', case when TG_OP = 'UPDATE' then OLD.status end,
+    'question', jsonb_build_object('question_id', NEW.id, 'body', NEW.body, 'status', NEW.status,
+      'answer', NEW.answer, 'answered_at', NEW.answered_at, 'created_at', NEW.created_at)
+  )::text);
+  return NEW;
+end;
+$$ language plpgsql;
+
+CREATE TRIGGER notify_ama_question
+  AFTER INSERT OR UPDATE
+  ON "ama_questions"
+  FOR EACH ROW
+EXECUTE FUNCTION notify_ama_question();
+
+CREATE OR REPLACE FUNCTION notify_ama_ended()
+  returns trigger as
+$$
+begin
+  perform pg_notify('ama_events', jsonb_build_object('kind', 'ended', 'ama_id', NEW.id)::text);
+  return NEW;
+end;
+$$ language plpgsql;
+
+CREATE TRIGGER notify_ama_ended
+  AFTER UPDATE OF ended_at
+  ON "ama_sessions"
+  FOR EACH ROW
+  WHEN (OLD.ended_at IS NULL AND NEW.ended_at IS NOT NULL)
+EXECUTE FUNCTION notify_ama_ended();
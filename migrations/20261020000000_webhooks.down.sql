@@ -0,0 +1,12 @@
+-- Add down migration script here
+ALTER TABLE "links" DROP COLUMN expiry_notified_at;
+
+DROP TRIGGER enqueue_report_webhook ON "reports";
+DROP FUNCTION enqueue_report_webhook();
+DROP TRIGGER enqueue_message_webhook ON "messages";
+DROP FUNCTION enqueue_message_webhook();
+DROP FUNCTION enqueue_webhook(uuid, varchar, jsonb);
+
+DROP TABLE "webhook_attempts";
+DROP TABLE "webhook_deliveries";
+DROP TABLE "webhooks";
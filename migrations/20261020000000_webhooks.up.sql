@@ -0,0 +1,107 @@
+-- Add up migration script here
+-- endpoints a user registers to hear about events, deliveries are signed with
+-- the endpoint's secret
+CREATE TABLE "webhooks"
+(
+  id uuid primary key default uuid_generate_v1mc(),
+  user_id uuid not null references "users" (id) on delete cascade,
+  url varchar(2048) not null,
+  description varchar(200),
+  secret varchar(64) not null,
+  events varchar(32)[] not null
+    constraint webhooks_events_check
+      check (events <@ array['message.received', 'message.reported', 'link.expired']::varchar(32)[]),
+  enabled boolean not null default true,
+  created_at timestamp not null default now(),
+  updated_at timestamp
+);
+SELECT trigger_updated_at('"webhooks"');
+
+CREATE INDEX webhooks_user_id_idx ON "webhooks" (user_id);
+
+-- the queue: a delivery is pending until it succeeds or runs out of attempts
+CREATE TABLE "webhook_deliveries"
+(
+  id uuid primary key default uuid_generate_v1mc(),
+  webhook_id uuid not null references "webhooks" (id) on delete cascade,
+  event varchar(32) not null,
+  payload jsonb not null,
+  status varchar(16) not null default 'pending'
+    constraint webhook_deliveries_status_check check (status in ('pending', 'succeeded', 'failed')),
+  attempts integer not null default 0,
+  next_attempt_at timestamp not null default now(),
+  last_status_code integer,
+  last_error varchar(500),
+  delivered_at timestamp,
+  created_at timestamp not null default now(),
+  updated_at timestamp
+);
+SELECT trigger_updated_at('"webhook_deliveries"');
+
+CREATE INDEX webhook_deliveries_webhook_id_created_at_idx ON "webhook_deliveries" (webhook_id, created_at desc);
+CREATE INDEX webhook_deliveries_due_idx ON "webhook_deliveries" (next_attempt_at) WHERE status = 'pending';
+
+-- one row per request made for a delivery
+CREATE TABLE "webhook_attempts"
+(
+  id bigserial primary key,
+  delivery_id uuid not null references "webhook_deliveries" (id) on delete cascade,
+  status_code integer,
+  error varchar(500),
+  response_body varchar(1000),
+  duration_ms integer not null,
+  created_at timestamp not null default now()
+);
+
+CREATE INDEX webhook_attempts_delivery_id_idx ON "webhook_attempts" (delivery_id, id);
+
+-- queues a delivery of `event` to every enabled endpoint of the user that asked for it
+CREATE OR REPLACE FUNCTION enqueue_webhook(recipient uuid, event varchar, payload jsonb)
+  returns void as
+$$
+begin
+  insert into "webhook_deliveries" (webhook_id, event, payload)
+  select id, event, payload from "webhooks"
+  where user_id = recipient and enabled and event = any(events);
+end;
+$$ language plpgsql;
+
+CREATE OR REPLACE FUNCTION enqueue_message_webhook()
+  returns trigger as
+$$
+begin
+  perform enqueue_webhook(NEW.recipient_id, 'message.received', jsonb_build_object(
+    'message_id', NEW.id, 'body', NEW.body, 'folder', NEW.folder, 'link_id', NEW.link_id,
+    'created_at', NEW.created_at));
+  return NEW;
+end;
+$$ language plpgsql;
+
+CREATE TRIGGER enqueue_message_webhook
+  AFTER INSERT
+  ON "messages"
+  FOR EACH ROW
+EXECUTE FUNCTION enqueue_message_webhook();
+
+CREATE OR REPLACE FUNCTION enqueue_report_webhook()
+  returns trigger as
+$$
+begin
+  perform enqueue_webhook(NEW.recipient_id, 'message.reported', jsonb_build_object(
+    'report_id', NEW.id, 'message_id', NEW.message_id, 'category', NEW.category, 'note', NEW.note,
+    'created_at', NEW.created_at));
+  return NEW;
+end;
+$$ language plpgsql;
+
+CREATE TRIGGER enqueue_report_webhook
+  AFTER INSERT
+  ON "reports"
+  FOR EACH ROW
+EXECUTE FUNCTION enqueue_report_webhook();
+
+-- set once a link stopped accepting messages and its owner was told, links
+-- already expired are not announced
+ALTER TABLE "links" ADD COLUMN expiry_notified_at timestamp;
+UPDATE "links" SET expiry_notified_at = now()
+WHERE expires_at <= now() OR message_count >= max_messages;
@@ -0,0 +1,42 @@
+-- Add up migration script here
+-- work done out of the request path. A job is queued until a worker claims it,
+-- goes back to the queue with a later run_at when it fails, and is dead once
+-- it failed max_attempts times
+CREATE TABLE "jobs"
+(
+  id uuid primary key default uuid_generate_v1mc(),
+  kind varchar(64) not null,
+  payload jsonb not null,
+  status varchar(16) not null default 'queued'
+    constraint jobs_status_check check (status in ('queued', 'running', 'succeeded', 'dead')),
+  attempts integer not null default 0,
+  max_attempts integer not null default 5
+    constraint jobs_max_attempts_check check (max_attempts >= 1),
+  run_at timestamp not null default now(),
+  locked_at timestamp,
+  locked_by varchar(64),
+  last_error varchar(1000),
+  finished_at timestamp,
+  created_at timestamp not null default now(),
+  updated_at timestamp
+);
+SELECT trigger_updated_at('"jobs"');
+
+CREATE INDEX jobs_due_idx ON "jobs" (run_at) WHERE status = 'queued';
+CREATE INDEX jobs_running_idx ON "jobs" (locked_at) WHERE status = 'running';
+CREATE INDEX jobs_finished_at_idx ON "jobs" (finished_at) WHERE status = 'succeeded';
+
+-- recurring jobs, kept in sync with the schedules registered in code. Whoever
+-- locks a due row queues its job and moves next_run_at on
+CREATE TABLE "job_schedules"
+(
+  name varchar(64) primary key,
+  kind varchar(64) not null,
+  cron varchar(100) not null,
+  payload jsonb not null,
+  next_run_at timestamp not null,
+  last_run_at timestamp,
+  created_at timestamp not null default now(),
+  updated_at timestamp
+);
+SELECT trigger_updated_at('"job_schedules"');
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS "digest_emails";
+DROP TABLE IF EXISTS "digest_settings";
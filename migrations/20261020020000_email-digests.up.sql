@@ -0,0 +1,31 @@
+-- Add up migration script here
+-- a user without a row gets no digest. send_hour is read in the timezone of
+-- their message settings, and weekly digests go out on Mondays
+CREATE TABLE "digest_settings"
+(
+  user_id uuid primary key references "users" (id) on delete cascade,
+  frequency varchar(16) not null default 'off'
+    constraint digest_settings_frequency_check check (frequency in ('off', 'daily', 'weekly')),
+  send_hour smallint not null default 8
+    constraint digest_settings_send_hour_check check (send_hour between 0 and 23),
+  last_digest_at timestamp,
+  created_at timestamp not null default now(),
+  updated_at timestamp
+);
+SELECT trigger_updated_at('"digest_settings"');
+
+CREATE INDEX digest_settings_frequency_idx ON "digest_settings" (frequency) WHERE frequency <> 'off';
+
+-- one row per digest sent, the unsubscribe link in it carries a token only
+-- its digest is stored of
+CREATE TABLE "digest_emails"
+(
+  id uuid primary key default uuid_generate_v1mc(),
+  user_id uuid not null references "users" (id) on delete cascade,
+  unsubscribe_hash bytea not null unique,
+  message_count integer not null,
+  sent_at timestamp not null default now(),
+  unsubscribed_at timestamp
+);
+
+CREATE INDEX digest_emails_user_id_idx ON "digest_emails" (user_id);
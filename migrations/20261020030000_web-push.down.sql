@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP TRIGGER enqueue_message_push ON "messages";
+DROP FUNCTION enqueue_message_push();
+
+DROP TABLE IF EXISTS "push_subscriptions";
+DROP TABLE IF EXISTS "vapid_keys";
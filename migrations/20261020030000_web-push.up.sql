@@ -0,0 +1,50 @@
+-- Add up migration script here
+-- the key pair push requests are signed with (VAPID), made on first use and
+-- shared by every instance, since subscriptions are bound to its public key
+CREATE TABLE "vapid_keys"
+(
+  id smallint primary key default 1
+    constraint vapid_keys_single_check check (id = 1),
+  private_key bytea not null,
+  public_key bytea not null,
+  created_at timestamp not null default now()
+);
+
+-- one row per browser or device, an endpoint belongs to whoever subscribed
+-- with it last
+CREATE TABLE "push_subscriptions"
+(
+  id uuid primary key default uuid_generate_v1mc(),
+  user_id uuid not null references "users" (id) on delete cascade,
+  endpoint varchar(2048) not null unique,
+  p256dh varchar(128) not null,
+  auth varchar(64) not null,
+  device_name varchar(100),
+  last_success_at timestamp,
+  created_at timestamp not null default now(),
+  updated_at timestamp
+);
+SELECT trigger_updated_at('"push_subscriptions"');
+
+CREATE INDEX push_subscriptions_user_id_idx ON "push_subscriptions" (user_id);
+
+-- queues a push.send job for every device of the recipient of a message that
+-- reaches their inbox, the job runner sends them with retries
+CREATE OR REPLACE FUNCTION enqueue_message_push()
+  returns trigger as
+$$
+begin
+  if NEW.folder = 'inbox' then
+    insert into "jobs" (kind, payload, max_attempts)
+    select 'push.send', jsonb_build_object('subscription_id', id, 'message_id', NEW.id), 5
+    from "push_subscriptions" where user_id = NEW.recipient_id;
+  end if;
+  return NEW;
+end;
+$$ language plpgsql;
+
+CREATE TRIGGER enqueue_message_push
+  AFTER INSERT
+  ON "messages"
+  FOR EACH ROW
+EXECUTE FUNCTION enqueue_message_push();
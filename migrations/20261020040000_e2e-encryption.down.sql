@@ -0,0 +1,20 @@
+-- Add down migration script here
+CREATE OR REPLACE FUNCTION enqueue_message_webhook()
+  returns trigger as
+$$
+begin
+  perform enqueue_webhook(NEW.recipient_id, 'message.received', jsonb_build_object(
+    'message_id', NEW.id, 'body', NEW.body, 'folder', NEW.folder, 'link_id', NEW.link_id,
+    'created_at', NEW.created_at));
+  return NEW;
+end;
+$$ language plpgsql;
+
+ALTER TABLE "links" DROP COLUMN require_encryption;
+ALTER TABLE "message_settings" DROP COLUMN require_encryption;
+
+ALTER TABLE "messages"
+  DROP CONSTRAINT messages_published_encrypted_check,
+  DROP COLUMN encryption_key_id;
+
+DROP TABLE "encryption_keys";
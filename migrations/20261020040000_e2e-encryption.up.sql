@@ -0,0 +1,36 @@
+-- Add up migration script here
+-- X25519 public keys users register to receive end-to-end encrypted messages,
+-- replaced keys are kept as revoked so older messages still name theirs
+CREATE TABLE "encryption_keys"
+(
+  id uuid primary key default uuid_generate_v1mc(),
+  user_id uuid not null references "users" (id) on delete cascade,
+  public_key bytea not null
+    constraint encryption_keys_public_key_check check (length(public_key) = 32),
+  revoked_at timestamp,
+  created_at timestamp not null default now()
+);
+
+CREATE UNIQUE INDEX encryption_keys_user_id_active_idx ON "encryption_keys" (user_id) WHERE revoked_at IS NULL;
+
+-- set when the body is an envelope only the holder of that key can open, such
+-- a message is never classified, previewed or published
+ALTER TABLE "messages"
+  ADD COLUMN encryption_key_id uuid references "encryption_keys" (id),
+  ADD CONSTRAINT messages_published_encrypted_check
+    check (published_at is null or encryption_key_id is null);
+
+-- refuse plaintext on the profile link, a link can override it either way
+ALTER TABLE "message_settings" ADD COLUMN require_encryption boolean not null default false;
+ALTER TABLE "links" ADD COLUMN require_encryption boolean;
+
+CREATE OR REPLACE FUNCTION enqueue_message_webhook()
+  returns trigger as
+$$
+begin
+  perform enqueue_webhook(NEW.recipient_id, 'message.received', jsonb_build_object(
+    'message_id', NEW.id, 'body', NEW.body, 'encryption_key_id', NEW.encryption_key_id, 'folder', NEW.folder,
+    'link_id', NEW.link_id, 'created_at', NEW.created_at));
+  return NEW;
+end;
+$$ language plpgsql;
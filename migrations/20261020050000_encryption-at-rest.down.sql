@@ -0,0 +1,12 @@
+-- Add down migration script here
+-- fails while any body is still sealed, they cannot be opened in SQL
+ALTER TABLE "reports"
+  ALTER COLUMN message_body SET NOT NULL,
+  DROP COLUMN sealed_message_body;
+
+ALTER TABLE "messages"
+  DROP CONSTRAINT messages_body_check,
+  ALTER COLUMN body SET NOT NULL,
+  DROP COLUMN sealed_body;
+
+DROP TABLE "data_keys";
@@ -0,0 +1,25 @@
+-- Add up migration script here
+-- one random key per user that message bodies are sealed with, stored wrapped
+-- by the master key named by master_key_id
+CREATE TABLE "data_keys"
+(
+  user_id uuid primary key references "users" (id) on delete cascade,
+  wrapped_key bytea not null,
+  master_key_id varchar(16) not null,
+  created_at timestamp not null default now(),
+  updated_at timestamp
+);
+SELECT trigger_updated_at('"data_keys"');
+
+CREATE INDEX data_keys_master_key_id_idx ON "data_keys" (master_key_id);
+
+-- a body is either stored as it is or sealed with the recipient's data key,
+-- end-to-end encrypted envelopes are never sealed
+ALTER TABLE "messages"
+  ALTER COLUMN body DROP NOT NULL,
+  ADD COLUMN sealed_body bytea,
+  ADD CONSTRAINT messages_body_check check ((body is null) <> (sealed_body is null));
+
+ALTER TABLE "reports"
+  ALTER COLUMN message_body DROP NOT NULL,
+  ADD COLUMN sealed_message_body bytea;
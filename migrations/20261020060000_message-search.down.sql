@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP INDEX messages_search_vector_idx;
+
+ALTER TABLE "messages" DROP COLUMN search_vector;
@@ -0,0 +1,11 @@
+-- Add up migration script here
+-- bodies are indexed as written, without stemming, whatever their language;
+-- sealed and end-to-end encrypted bodies are left out and cannot be searched
+ALTER TABLE "messages"
+  ADD COLUMN search_vector tsvector generated always as (
+    case when encryption_key_id is null and body is not null
+      then to_tsvector('simple', body)
+    end
+  ) stored;
+
+CREATE INDEX messages_search_vector_idx ON "messages" USING gin (search_vector);
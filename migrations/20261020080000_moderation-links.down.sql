@@ -0,0 +1,8 @@
+-- Add down migration script here
+ALTER TABLE "users" DROP COLUMN profile_link_suspended_at;
+ALTER TABLE "links" DROP COLUMN suspended_at;
+ALTER TABLE "reports" DROP COLUMN link_id;
+
+ALTER TABLE "messages"
+  DROP COLUMN sealed_platform_fingerprint,
+  ADD COLUMN platform_fingerprint bytea DEFAULT NULL;
@@ -0,0 +1,17 @@
+-- Add up migration script here
+-- the platform digest is sealed on the message with a key kept out of the
+-- database, so messages from the same sender cannot be linked through it; it
+-- is only opened into the report. Digests already stored cannot be sealed
+-- here and are dropped, reports keep theirs.
+ALTER TABLE "messages"
+  DROP COLUMN platform_fingerprint,
+  ADD COLUMN sealed_platform_fingerprint bytea;
+
+-- the link the reported message came through, null for the profile link; no
+-- foreign key so a deleted link is not mistaken for the profile link
+ALTER TABLE "reports" ADD COLUMN link_id uuid;
+UPDATE "reports" r SET link_id = m.link_id FROM "messages" m WHERE m.id = r.message_id;
+
+-- a suspended link stops taking messages, its owner keeps their account
+ALTER TABLE "links" ADD COLUMN suspended_at timestamp;
+ALTER TABLE "users" ADD COLUMN profile_link_suspended_at timestamp;
@@ -0,0 +1,12 @@
+-- Add up migration script here
+-- claim codes handed out for messages the recipient's blocklist dropped, so
+-- looking one up reads like a message that was never read or replied to
+CREATE TABLE "dropped_claims"
+(
+  claim_hash bytea primary key,
+  recipient_id uuid not null references "users" (id) on delete cascade,
+  body text,
+  sealed_body bytea,
+  created_at timestamp not null default now(),
+  constraint dropped_claims_body_check check ((body is null) <> (sealed_body is null))
+);
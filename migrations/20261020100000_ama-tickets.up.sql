@@ -0,0 +1,14 @@
+-- Add up migration script here
+-- single-use tickets opening a host socket, so the access token never has to
+-- go in a URL, where proxies and access logs would keep it
+CREATE TABLE "ama_tickets"
+(
+  ticket_hash bytea primary key,
+  session_id uuid not null references "ama_sessions" (id) on delete cascade,
+  user_id uuid not null references "users" (id) on delete cascade,
+  -- the socket closes when the token the ticket was taken with expires
+  token_expires_at timestamp not null,
+  expires_at timestamp not null
+);
+
+CREATE INDEX ama_tickets_expires_at_idx ON "ama_tickets" (expires_at);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP INDEX dropped_claims_created_at_idx;
+ALTER TABLE "dropped_claims" ADD COLUMN body text, ADD COLUMN sealed_body bytea;
@@ -0,0 +1,5 @@
+-- Add up migration script here
+-- a claim on a dropped message only has to read as unread, which needs no body
+ALTER TABLE "dropped_claims" DROP COLUMN body, DROP COLUMN sealed_body;
+
+CREATE INDEX dropped_claims_created_at_idx ON "dropped_claims" (created_at);
@@ -0,0 +1,21 @@
+//! Trains the naive-Bayes model `TOXICITY_MODEL` points to, from
+//! `label<TAB>text` samples.
+
+use std::{path::Path, process::ExitCode};
+
+use reminder_api::core::classifier::NaiveBayesModel;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [samples, model] = &args[..] else {
+        eprintln!("usage: reminder-train-toxicity <samples.tsv> <model.json>");
+        return ExitCode::FAILURE;
+    };
+
+    let mut toxicity = NaiveBayesModel::default();
+    toxicity
+        .train_file(Path::new(samples))
+        .and_then(|_| toxicity.save(Path::new(model)))
+        .expect("Unable to train the toxicity model");
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,10 @@
+//! Runs the background jobs without serving the API, for deployments that set
+//! `JOBS_IN_PROCESS=false` on their API processes.
+
+use reminder_api::{connect, init, run_worker};
+
+#[tokio::main]
+async fn main() {
+    let (ctx, _) = connect(init()).await;
+    run_worker(ctx).await;
+}
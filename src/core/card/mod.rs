@@ -0,0 +1,227 @@
+mod text;
+mod theme;
+
+pub use theme::*;
+
+use std::io::Cursor;
+
+use image::{ImageFormat, Rgb, RgbImage};
+
+use crate::core::models::ApiError;
+use text::{ascent, draw, drawable, line_height, truncate, width, wrap, Weight};
+
+/// What goes on a card: the message, optionally its answer, and a footer
+/// naming where more can be asked, eg: the recipient's profile address.
+pub struct CardContent<'a> {
+    pub question: &'a str,
+    pub answer: Option<&'a str>,
+    pub footer: &'a str,
+}
+
+struct Block {
+    label: &'static str,
+    lines: Vec<String>,
+    weight: Weight,
+    px: f32,
+}
+
+impl Block {
+    fn height(&self, label_px: f32) -> f32 {
+        line_height(label_px) + self.lines.len() as f32 * line_height(self.px)
+    }
+}
+
+fn mix(from: Rgb<u8>, to: Rgb<u8>, t: f32) -> Rgb<u8> {
+    Rgb(std::array::from_fn(|channel| {
+        (from.0[channel] as f32 + (to.0[channel] as f32 - from.0[channel] as f32) * t).round() as u8
+    }))
+}
+
+/// Fills a rectangle with corners of `radius`, antialiased along the curve.
+fn rounded_rect(image: &mut RgbImage, x: f32, y: f32, w: f32, h: f32, radius: f32, color: Rgb<u8>) {
+    let (left, top) = (x.max(0.0) as u32, y.max(0.0) as u32);
+    let right = ((x + w).ceil() as u32).min(image.width());
+    let bottom = ((y + h).ceil() as u32).min(image.height());
+    for py in top..bottom {
+        for px in left..right {
+            let (cx, cy) = (px as f32 + 0.5, py as f32 + 0.5);
+            let dx = (x + radius - cx).max(cx - (x + w - radius)).max(0.0);
+            let dy = (y + radius - cy).max(cy - (y + h - radius)).max(0.0);
+            let coverage = (radius + 0.5 - (dx * dx + dy * dy).sqrt()).clamp(0.0, 1.0);
+            if coverage > 0.0 {
+                let pixel = image.get_pixel_mut(px, py);
+                *pixel = mix(*pixel, color, coverage);
+            }
+        }
+    }
+}
+
+/// Lays the blocks out at the largest size that fits `available` height,
+/// shrinking down to half size and cutting lines past that.
+fn fit_blocks(content: &CardContent, inner_width: f32, available: f32, base_px: f32) -> Vec<Block> {
+    let question = drawable(content.question, Weight::Bold);
+    let answer = content
+        .answer
+        .map(|answer| drawable(answer, Weight::Regular));
+    let label_px = base_px * 0.45;
+
+    let layout = |px: f32| {
+        let mut blocks = vec![Block {
+            label: "Anonymous message",
+            lines: wrap(&question, Weight::Bold, px, inner_width),
+            weight: Weight::Bold,
+            px,
+        }];
+        if let Some(answer) = &answer {
+            let px = px * 0.85;
+            blocks.push(Block {
+                label: "Answer",
+                lines: wrap(answer, Weight::Regular, px, inner_width),
+                weight: Weight::Regular,
+                px,
+            });
+        }
+        blocks
+    };
+    let height = |blocks: &[Block]| {
+        blocks
+            .iter()
+            .map(|block| block.height(label_px))
+            .sum::<f32>()
+            + (blocks.len() - 1) as f32 * base_px
+    };
+
+    let mut px = base_px;
+    let mut blocks = layout(px);
+    while height(&blocks) > available && px > base_px * 0.5 {
+        px *= 0.92;
+        blocks = layout(px);
+    }
+
+    // still too tall at the smallest size: the answer gets up to half the room
+    // and the message whatever the answer leaves
+    if height(&blocks) > available {
+        let fit = |block: &mut Block, room: f32| {
+            let lines = (room - line_height(label_px)) / line_height(block.px);
+            truncate(
+                &mut block.lines,
+                (lines.floor() as usize).max(1),
+                block.weight,
+                block.px,
+                inner_width,
+            );
+        };
+        let mut room = available;
+        if let Some(answer) = blocks.get_mut(1) {
+            fit(answer, available / 2.0 - base_px);
+            room -= answer.height(label_px) + base_px;
+        }
+        fit(&mut blocks[0], room);
+    }
+    blocks
+}
+
+/// Renders a message card as a PNG.
+pub fn render_card(
+    content: &CardContent,
+    theme: CardTheme,
+    ratio: CardRatio,
+) -> Result<Vec<u8>, ApiError> {
+    let palette = theme.palette();
+    let (w, h) = ratio.dimensions();
+    let (wf, hf) = (w as f32, h as f32);
+
+    let mut image = RgbImage::from_fn(w, h, |_, y| {
+        mix(palette.top, palette.bottom, y as f32 / (h - 1) as f32)
+    });
+
+    let margin = wf.min(hf) * 0.07;
+    let padding = wf * 0.06;
+    let base_px = wf * 0.055;
+    let label_px = base_px * 0.45;
+    let footer_px = wf * 0.03;
+    let footer_height = line_height(footer_px) * 2.0;
+    let inner_width = wf - 2.0 * (margin + padding);
+    let available = hf - 2.0 * (margin + padding) - footer_height;
+
+    let blocks = fit_blocks(content, inner_width, available, base_px);
+    let content_height = blocks
+        .iter()
+        .map(|block| block.height(label_px))
+        .sum::<f32>()
+        + (blocks.len() - 1) as f32 * base_px;
+
+    let card_height = content_height + 2.0 * padding;
+    let card_top = ((hf - footer_height - card_height) / 2.0).max(margin);
+    rounded_rect(
+        &mut image,
+        margin,
+        card_top,
+        wf - 2.0 * margin,
+        card_height,
+        wf * 0.04,
+        palette.card,
+    );
+
+    let left = margin + padding;
+    let mut y = card_top + padding;
+    for (index, block) in blocks.iter().enumerate() {
+        if index > 0 {
+            let rule = y + base_px / 2.0;
+            rounded_rect(
+                &mut image,
+                left,
+                rule - 1.0,
+                inner_width,
+                2.0,
+                1.0,
+                palette.muted,
+            );
+            y += base_px;
+        }
+        draw(
+            &mut image,
+            block.label,
+            Weight::Bold,
+            label_px,
+            left,
+            y + ascent(label_px),
+            if index == 0 {
+                palette.accent
+            } else {
+                palette.muted
+            },
+        );
+        y += line_height(label_px);
+        for line in &block.lines {
+            draw(
+                &mut image,
+                line,
+                block.weight,
+                block.px,
+                left,
+                y + ascent(block.px),
+                palette.text,
+            );
+            y += line_height(block.px);
+        }
+    }
+
+    let footer = drawable(content.footer, Weight::Regular);
+    let footer_width = width(&footer, Weight::Regular, footer_px);
+    draw(
+        &mut image,
+        &footer,
+        Weight::Regular,
+        footer_px,
+        (wf - footer_width) / 2.0,
+        card_top + card_height + footer_height / 2.0 + ascent(footer_px) / 2.0,
+        palette.footer,
+    );
+
+    let mut png = Cursor::new(Vec::new());
+    image
+        .write_to(&mut png, ImageFormat::Png)
+        .map_err(|e| ApiError::InternalServer(e.to_string()))?;
+    Ok(png.into_inner())
+}
@@ -0,0 +1,217 @@
+use std::sync::LazyLock;
+
+use ab_glyph::{point, Font, FontRef, GlyphId, PxScale, ScaleFont};
+use image::{Rgb, RgbImage};
+
+static REGULAR: LazyLock<FontRef<'static>> = LazyLock::new(|| {
+    FontRef::try_from_slice(include_bytes!("../../../assets/fonts/DejaVuSans.ttf")).unwrap()
+});
+static BOLD: LazyLock<FontRef<'static>> = LazyLock::new(|| {
+    FontRef::try_from_slice(include_bytes!("../../../assets/fonts/DejaVuSans-Bold.ttf")).unwrap()
+});
+
+/// Drawn for characters no bundled font has a glyph for.
+const MISSING: char = '\u{25a1}';
+
+#[derive(Clone, Copy)]
+pub enum Weight {
+    Regular,
+    Bold,
+}
+
+impl Weight {
+    /// The face of this weight first, then the other one.
+    fn fonts(self) -> [&'static FontRef<'static>; 2] {
+        match self {
+            Weight::Regular => [&REGULAR, &BOLD],
+            Weight::Bold => [&BOLD, &REGULAR],
+        }
+    }
+}
+
+fn glyph(weight: Weight, c: char) -> Option<(&'static FontRef<'static>, GlyphId)> {
+    weight.fonts().into_iter().find_map(|font| {
+        let id = font.glyph_id(c);
+        (id.0 != 0).then_some((font, id))
+    })
+}
+
+/// Stand-in from the bundled fonts for emoji they lack, by meaning.
+fn emoji_fallback(c: char) -> Option<char> {
+    let fallback = match c {
+        '\u{1f600}'..='\u{1f60a}' | '\u{1f642}' | '\u{1f923}' | '\u{1f60e}' => '\u{263a}',
+        '\u{1f61e}' | '\u{1f614}' | '\u{1f622}' | '\u{1f62d}' | '\u{1f641}' => '\u{2639}',
+        '\u{1f60d}' | '\u{1f618}' | '\u{1f970}' | '\u{1f493}'..='\u{1f49f}' | '\u{1f9e1}' => {
+            '\u{2665}'
+        }
+        '\u{1f31f}' | '\u{2728}' | '\u{2b50}' => '\u{2605}',
+        '\u{2705}' | '\u{1f44d}' => '\u{2714}',
+        '\u{274c}' | '\u{1f44e}' => '\u{2718}',
+        '\u{1f3b5}' | '\u{1f3b6}' => '\u{266b}',
+        '\u{1f31e}' => '\u{2600}',
+        _ => return None,
+    };
+    Some(fallback)
+}
+
+/// Characters that only modify the emoji before them, eg: variation
+/// selectors, joiners, skin tones and flag tags.
+fn is_modifier(c: char) -> bool {
+    matches!(
+        c,
+        '\u{fe0e}' | '\u{fe0f}' | '\u{200d}' | '\u{1f3fb}'..='\u{1f3ff}' | '\u{e0020}'..='\u{e007f}'
+    )
+}
+
+/// Replaces what the bundled fonts cannot draw: modifiers are dropped, common
+/// emoji become a similar symbol and anything else a box.
+pub fn drawable(text: &str, weight: Weight) -> String {
+    text.chars()
+        .filter(|c| !is_modifier(*c))
+        .map(|c| {
+            if c.is_whitespace() || glyph(weight, c).is_some() {
+                return c;
+            }
+            emoji_fallback(c)
+                .filter(|fallback| glyph(weight, *fallback).is_some())
+                .unwrap_or(MISSING)
+        })
+        .collect()
+}
+
+pub fn width(text: &str, weight: Weight, px: f32) -> f32 {
+    let mut width = 0.0;
+    let mut previous: Option<(&FontRef, GlyphId)> = None;
+    for c in text.chars() {
+        let Some((font, id)) = glyph(weight, c) else {
+            continue;
+        };
+        let scaled = font.as_scaled(PxScale::from(px));
+        if let Some((previous_font, previous_id)) = previous {
+            if std::ptr::eq(previous_font, font) {
+                width += scaled.kern(previous_id, id);
+            }
+        }
+        width += scaled.h_advance(id);
+        previous = Some((font, id));
+    }
+    width
+}
+
+/// Distance between baselines of consecutive lines.
+pub fn line_height(px: f32) -> f32 {
+    let scaled = REGULAR.as_scaled(PxScale::from(px));
+    (scaled.height() + scaled.line_gap()) * 1.1
+}
+
+/// Breaks `text` into lines no wider than `max_width`, between words where
+/// possible and inside words longer than a line. Line breaks are kept.
+pub fn wrap(text: &str, weight: Weight, px: f32, max_width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let candidate = if line.is_empty() {
+                word.to_string()
+            } else {
+                format!("{line} {word}")
+            };
+            if width(&candidate, weight, px) <= max_width {
+                line = candidate;
+                continue;
+            }
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            for c in word.chars() {
+                line.push(c);
+                if width(&line, weight, px) > max_width && line.chars().count() > 1 {
+                    line.pop();
+                    lines.push(std::mem::replace(&mut line, c.to_string()));
+                }
+            }
+        }
+        lines.push(line);
+    }
+    while lines.last().is_some_and(String::is_empty) {
+        lines.pop();
+    }
+    lines
+}
+
+/// Cuts `lines` to `max_lines`, ending the last one kept with an ellipsis.
+pub fn truncate(
+    lines: &mut Vec<String>,
+    max_lines: usize,
+    weight: Weight,
+    px: f32,
+    max_width: f32,
+) {
+    if lines.len() <= max_lines {
+        return;
+    }
+    lines.truncate(max_lines);
+    if let Some(last) = lines.last_mut() {
+        while !last.is_empty() && width(&format!("{last}\u{2026}"), weight, px) > max_width {
+            last.pop();
+        }
+        last.push('\u{2026}');
+    }
+}
+
+fn blend(image: &mut RgbImage, x: i64, y: i64, color: Rgb<u8>, coverage: f32) {
+    if x < 0 || y < 0 || x >= image.width() as i64 || y >= image.height() as i64 {
+        return;
+    }
+    let pixel = image.get_pixel_mut(x as u32, y as u32);
+    let coverage = coverage.clamp(0.0, 1.0);
+    for channel in 0..3 {
+        pixel.0[channel] = (pixel.0[channel] as f32 * (1.0 - coverage)
+            + color.0[channel] as f32 * coverage)
+            .round() as u8;
+    }
+}
+
+/// Draws one line with its baseline at `y`.
+pub fn draw(
+    image: &mut RgbImage,
+    text: &str,
+    weight: Weight,
+    px: f32,
+    x: f32,
+    y: f32,
+    color: Rgb<u8>,
+) {
+    let mut caret = x;
+    let mut previous: Option<(&FontRef, GlyphId)> = None;
+    for c in text.chars() {
+        let Some((font, id)) = glyph(weight, c) else {
+            continue;
+        };
+        let scaled = font.as_scaled(PxScale::from(px));
+        if let Some((previous_font, previous_id)) = previous {
+            if std::ptr::eq(previous_font, font) {
+                caret += scaled.kern(previous_id, id);
+            }
+        }
+        let positioned = id.with_scale_and_position(px, point(caret, y));
+        if let Some(outline) = font.outline_glyph(positioned) {
+            let bounds = outline.px_bounds();
+            outline.draw(|gx, gy, coverage| {
+                blend(
+                    image,
+                    bounds.min.x as i64 + gx as i64,
+                    bounds.min.y as i64 + gy as i64,
+                    color,
+                    coverage,
+                );
+            });
+        }
+        caret += scaled.h_advance(id);
+        previous = Some((font, id));
+    }
+}
+
+pub fn ascent(px: f32) -> f32 {
+    REGULAR.as_scaled(PxScale::from(px)).ascent()
+}
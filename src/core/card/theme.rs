@@ -0,0 +1,90 @@
+use image::Rgb;
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CardTheme {
+    #[default]
+    Light,
+    Dark,
+    Sunset,
+    Ocean,
+}
+
+/// Canvas shapes: `square` and `post` (4:5) for feeds, `story` (9:16) for
+/// stories and `landscape` for link previews.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CardRatio {
+    Square,
+    #[default]
+    Post,
+    Story,
+    Landscape,
+}
+
+impl CardRatio {
+    pub fn dimensions(self) -> (u32, u32) {
+        match self {
+            CardRatio::Square => (1080, 1080),
+            CardRatio::Post => (1080, 1350),
+            CardRatio::Story => (1080, 1920),
+            CardRatio::Landscape => (1200, 630),
+        }
+    }
+}
+
+/// Colours of a theme. The background is a vertical gradient from `top` to
+/// `bottom`, the message sits on a `card` panel and the footer on the background.
+pub struct Palette {
+    pub top: Rgb<u8>,
+    pub bottom: Rgb<u8>,
+    pub card: Rgb<u8>,
+    pub text: Rgb<u8>,
+    pub muted: Rgb<u8>,
+    pub accent: Rgb<u8>,
+    pub footer: Rgb<u8>,
+}
+
+impl CardTheme {
+    pub fn palette(self) -> Palette {
+        match self {
+            CardTheme::Light => Palette {
+                top: Rgb([244, 241, 234]),
+                bottom: Rgb([244, 241, 234]),
+                card: Rgb([255, 255, 255]),
+                text: Rgb([28, 28, 30]),
+                muted: Rgb([107, 107, 112]),
+                accent: Rgb([255, 90, 95]),
+                footer: Rgb([107, 107, 112]),
+            },
+            CardTheme::Dark => Palette {
+                top: Rgb([14, 14, 16]),
+                bottom: Rgb([14, 14, 16]),
+                card: Rgb([28, 28, 34]),
+                text: Rgb([245, 245, 247]),
+                muted: Rgb([154, 154, 165]),
+                accent: Rgb([139, 124, 255]),
+                footer: Rgb([154, 154, 165]),
+            },
+            CardTheme::Sunset => Palette {
+                top: Rgb([255, 126, 95]),
+                bottom: Rgb([254, 180, 123]),
+                card: Rgb([255, 255, 255]),
+                text: Rgb([40, 24, 20]),
+                muted: Rgb([120, 96, 90]),
+                accent: Rgb([235, 94, 64]),
+                footer: Rgb([255, 255, 255]),
+            },
+            CardTheme::Ocean => Palette {
+                top: Rgb([33, 147, 176]),
+                bottom: Rgb([109, 213, 237]),
+                card: Rgb([255, 255, 255]),
+                text: Rgb([16, 36, 48]),
+                muted: Rgb([84, 110, 122]),
+                accent: Rgb([33, 147, 176]),
+                footer: Rgb([255, 255, 255]),
+            },
+        }
+    }
+}
@@ -0,0 +1,75 @@
+mod naive_bayes;
+mod spam_rules;
+
+pub use naive_bayes::*;
+pub use spam_rules::*;
+
+use std::{collections::HashMap, path::Path};
+
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use sqlx::PgPool;
+
+use crate::{
+    config::Config,
+    core::{models::IncomingMessage, traits::MessageClassifier},
+};
+
+/// The spam rules, plus the toxicity model when `TOXICITY_MODEL` points to one.
+pub fn message_classifiers(config: &Config, db: &PgPool) -> Vec<Box<dyn MessageClassifier>> {
+    let mut classifiers: Vec<Box<dyn MessageClassifier>> =
+        vec![Box::new(SpamRules::new(db.clone()))];
+
+    if !config.toxicity_model.is_empty() {
+        let model = NaiveBayesModel::load(Path::new(&config.toxicity_model))
+            .expect("Unable to load the toxicity model");
+        classifiers.push(Box::new(ToxicityModel::new(model)));
+    }
+    classifiers
+}
+
+/// The key message bodies are digested with, derived from `JWT_SECRET` so
+/// the digests stored next to a message cannot be used to confirm a guessed
+/// body by anyone holding only the database.
+pub fn content_key(jwt_secret: &str) -> [u8; 32] {
+    let mut key = [0; 32];
+    Hkdf::<Sha256>::new(None, jwt_secret.as_bytes())
+        .expand(b"anonymous-message content v1", &mut key)
+        .expect("HKDF output is short enough");
+    key
+}
+
+/// Digest of a message body ignoring case and whitespace, so trivially
+/// altered copies of the same text share it.
+pub fn content_hash(key: &[u8; 32], body: &str) -> Vec<u8> {
+    let normalised = body
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ");
+    Hmac::<Sha256>::new_from_slice(key)
+        .expect("HMAC accepts keys of any length")
+        .chain_update(normalised)
+        .finalize()
+        .into_bytes()
+        .to_vec()
+}
+
+/// Runs every classifier. A failing classifier is skipped rather than
+/// blocking the submission.
+pub async fn classify(
+    classifiers: &[Box<dyn MessageClassifier>],
+    message: &IncomingMessage<'_>,
+) -> HashMap<&'static str, f32> {
+    let mut scores = HashMap::new();
+    for classifier in classifiers {
+        match classifier.score(message).await {
+            Ok(score) => {
+                scores.insert(classifier.name(), score.clamp(0.0, 1.0));
+            }
+            Err(e) => tracing::warn!("Classifier {} failed: {e}", classifier.name()),
+        }
+    }
+    scores
+}
@@ -0,0 +1,211 @@
+use std::{collections::HashMap, path::Path};
+
+use anyhow::{bail, Context, Error};
+use axum::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::core::{models::IncomingMessage, traits::MessageClassifier};
+
+/// Word counts of a multinomial naive-Bayes model with two classes, stored as
+/// JSON. Train one with
+/// `reminder-train-toxicity <samples.tsv> <model.json>`.
+#[derive(Default, Serialize, Deserialize)]
+pub struct NaiveBayesModel {
+    toxic_documents: u64,
+    clean_documents: u64,
+    toxic_words: HashMap<String, u64>,
+    clean_words: HashMap<String, u64>,
+    /// Derived from the word counts by `train` and `load` rather than stored.
+    #[serde(skip)]
+    totals: Totals,
+}
+
+/// Word occurrences of each class, smoothed by the size of the vocabulary of
+/// both classes.
+#[derive(Default)]
+struct Totals {
+    vocabulary: u64,
+    toxic_words: u64,
+    clean_words: u64,
+}
+
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .map(|word| word.trim_matches('\'').to_lowercase())
+        .filter(|word| !word.is_empty())
+}
+
+impl NaiveBayesModel {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let file = std::fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+        let mut model: Self = serde_json::from_slice(&file)?;
+        if model.toxic_documents == 0 || model.clean_documents == 0 {
+            bail!("{} needs toxic and clean samples", path.display());
+        }
+        model.totals = Totals {
+            vocabulary: model
+                .toxic_words
+                .keys()
+                .chain(
+                    model
+                        .clean_words
+                        .keys()
+                        .filter(|word| !model.toxic_words.contains_key(*word)),
+                )
+                .count() as u64,
+            toxic_words: model.toxic_words.values().sum(),
+            clean_words: model.clean_words.values().sum(),
+        };
+        Ok(model)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        std::fs::write(path, serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+
+    /// Adds one labelled sample.
+    pub fn train(&mut self, text: &str, toxic: bool) {
+        let (documents, counts, total, other) = if toxic {
+            (
+                &mut self.toxic_documents,
+                &mut self.toxic_words,
+                &mut self.totals.toxic_words,
+                &self.clean_words,
+            )
+        } else {
+            (
+                &mut self.clean_documents,
+                &mut self.clean_words,
+                &mut self.totals.clean_words,
+                &self.toxic_words,
+            )
+        };
+        *documents += 1;
+        for word in words(text) {
+            if !counts.contains_key(&word) && !other.contains_key(&word) {
+                self.totals.vocabulary += 1;
+            }
+            *counts.entry(word).or_default() += 1;
+            *total += 1;
+        }
+    }
+
+    /// Reads `label<TAB>text` lines, where the label is `1`/`toxic` or `0`/`clean`.
+    pub fn train_file(&mut self, path: &Path) -> Result<(), Error> {
+        let samples =
+            std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        for (number, line) in samples.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (toxic, text) = match line.split_once('\t') {
+                Some(("1" | "toxic", text)) => (true, text),
+                Some(("0" | "clean", text)) => (false, text),
+                _ => bail!("{}:{}: expected label<TAB>text", path.display(), number + 1),
+            };
+            self.train(text, toxic);
+        }
+        Ok(())
+    }
+
+    /// Probability that `text` is toxic, with add-one smoothing over the
+    /// vocabulary of both classes.
+    pub fn probability(&self, text: &str) -> f32 {
+        let vocabulary = self.totals.vocabulary as f64;
+        let toxic_total = self.totals.toxic_words as f64 + vocabulary;
+        let clean_total = self.totals.clean_words as f64 + vocabulary;
+
+        let mut log_odds = (self.toxic_documents as f64 / self.clean_documents as f64).ln();
+        for word in words(text) {
+            let toxic = self.toxic_words.get(&word).copied();
+            let clean = self.clean_words.get(&word).copied();
+            // words never seen in training say nothing either way
+            if toxic.is_none() && clean.is_none() {
+                continue;
+            }
+            log_odds += ((toxic.unwrap_or(0) + 1) as f64 / toxic_total).ln()
+                - ((clean.unwrap_or(0) + 1) as f64 / clean_total).ln();
+        }
+        (1.0 / (1.0 + (-log_odds).exp())) as f32
+    }
+}
+
+pub struct ToxicityModel {
+    model: NaiveBayesModel,
+}
+
+impl ToxicityModel {
+    pub fn new(model: NaiveBayesModel) -> Self {
+        ToxicityModel { model }
+    }
+}
+
+#[async_trait]
+impl MessageClassifier for ToxicityModel {
+    fn name(&self) -> &'static str {
+        "toxicity"
+    }
+
+    async fn score(&self, message: &IncomingMessage<'_>) -> Result<f32, Error> {
+        Ok(self.model.probability(message.body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trained() -> NaiveBayesModel {
+        let mut model = NaiveBayesModel::default();
+        model.train("you are an idiot", true);
+        model.train("shut up, idiot!", true);
+        model.train("what a lovely question", false);
+        model.train("you are lovely", false);
+        model
+    }
+
+    #[test]
+    fn splits_words() {
+        assert_eq!(
+            words("Don't SHOUT, 'idiot'...ok?").collect::<Vec<_>>(),
+            ["don't", "shout", "idiot", "ok"]
+        );
+    }
+
+    #[test]
+    fn scores_by_the_words_seen_in_training() {
+        let model = trained();
+        assert!(model.probability("idiot") > 0.7);
+        assert!(model.probability("Lovely!") < 0.3);
+        assert!(model.probability("idiot idiot") > model.probability("idiot"));
+        // two samples of each class, and nothing known to go by
+        assert_eq!(model.probability("zebra crossing"), 0.5);
+    }
+
+    #[test]
+    fn scores_the_same_once_saved_and_loaded() {
+        let model = trained();
+        let path = std::env::temp_dir().join(format!("toxicity-{}.json", uuid::Uuid::new_v4()));
+        model.save(&path).unwrap();
+        let loaded = NaiveBayesModel::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let loaded = loaded.unwrap();
+        for text in ["you idiot", "lovely", "what are you", "zebra"] {
+            assert_eq!(loaded.probability(text), model.probability(text));
+        }
+    }
+
+    #[test]
+    fn refuses_models_missing_a_class() {
+        let mut model = NaiveBayesModel::default();
+        model.train("you are an idiot", true);
+        let path = std::env::temp_dir().join(format!("toxicity-{}.json", uuid::Uuid::new_v4()));
+        model.save(&path).unwrap();
+        let loaded = NaiveBayesModel::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(loaded.is_err());
+    }
+}
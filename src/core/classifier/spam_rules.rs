@@ -0,0 +1,124 @@
+use std::sync::LazyLock;
+
+use anyhow::Error;
+use axum::async_trait;
+use regex::Regex;
+use sqlx::PgPool;
+
+use crate::core::{models::IncomingMessage, traits::MessageClassifier};
+
+static URL_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(https?://|www\.)\S+").unwrap());
+
+/// Runs of the same character at least this long count as repeated characters.
+const REPEAT_RUN: usize = 4;
+
+/// Distinct other recipients of the same content, within a day, that make a
+/// message certain spam.
+const DUPLICATE_RECIPIENTS: i64 = 5;
+
+/// Heuristic spam rules. Each rule yields a probability-like score and the
+/// message score is the chance that at least one of them is right.
+pub struct SpamRules {
+    db: PgPool,
+}
+
+impl SpamRules {
+    pub fn new(db: PgPool) -> Self {
+        SpamRules { db }
+    }
+}
+
+/// Share of characters sitting in runs of `REPEAT_RUN` or more, doubled so a
+/// message that is half "!!!!!!" scores 1.
+fn repeated_characters(body: &str) -> f32 {
+    let mut total = 0;
+    let mut repeated = 0;
+    let mut run = 0;
+    let mut previous = None;
+
+    for c in body.chars() {
+        total += 1;
+        run = if Some(c) == previous { run + 1 } else { 1 };
+        previous = Some(c);
+        if run == REPEAT_RUN {
+            repeated += REPEAT_RUN;
+        } else if run > REPEAT_RUN {
+            repeated += 1;
+        }
+    }
+
+    if total == 0 {
+        return 0.0;
+    }
+    (2.0 * repeated as f32 / total as f32).min(1.0)
+}
+
+/// URLs per word, so a bare link scores 1 and a link in a long message little.
+fn url_density(body: &str) -> f32 {
+    let urls = URL_REGEX.find_iter(body).count();
+    let words = body.split_whitespace().count().max(1);
+    (3.0 * urls as f32 / words as f32).min(1.0)
+}
+
+#[async_trait]
+impl MessageClassifier for SpamRules {
+    fn name(&self) -> &'static str {
+        "spam"
+    }
+
+    async fn score(&self, message: &IncomingMessage<'_>) -> Result<f32, Error> {
+        let recipients = sqlx::query_scalar!(
+            r#"select count(distinct recipient_id) as "count!" from "messages"
+            where content_hash = $1 and recipient_id <> $2 and created_at > now() - interval '1 day'"#,
+            message.content_hash,
+            message.recipient_id
+        )
+        .fetch_one(&self.db)
+        .await?;
+        let duplicate = (recipients as f32 / DUPLICATE_RECIPIENTS as f32).min(1.0);
+
+        let clean = [
+            repeated_characters(message.body),
+            url_density(message.body),
+            duplicate,
+        ]
+        .iter()
+        .map(|score| 1.0 - score)
+        .product::<f32>();
+        Ok(1.0 - clean)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_repeated_characters() {
+        assert_eq!(repeated_characters(""), 0.0);
+        assert_eq!(repeated_characters("hello, good morning"), 0.0);
+        // a run one short of counting
+        assert_eq!(repeated_characters("nooo"), 0.0);
+        assert_eq!(repeated_characters("noooo"), 1.0);
+        assert_eq!(repeated_characters("hey!!!!!"), 1.0);
+        assert_eq!(
+            repeated_characters("what a lovely question!!!!"),
+            8.0 / 26.0
+        );
+    }
+
+    #[test]
+    fn scores_url_density() {
+        assert_eq!(url_density(""), 0.0);
+        assert_eq!(url_density("no links here"), 0.0);
+        assert_eq!(url_density("https://example.com"), 1.0);
+        assert_eq!(url_density("WWW.example.com"), 1.0);
+        assert_eq!(
+            url_density(
+                "I wrote about this at https://example.com/post last week, have a look please"
+            ),
+            0.25
+        );
+    }
+}
@@ -28,11 +28,27 @@ pub fn error_transformer(errors: ValidationErrors) -> Vec<String> {
                         messages.push(format!("{}: maximum length is {} characters.", field, max));
                     }
                 }
+                Cow::Borrowed("range") => {
+                    let min = error.params.get("min").and_then(|min| min.as_f64());
+                    let max = error.params.get("max").and_then(|max| max.as_f64());
+
+                    match (min, max) {
+                        (Some(min), Some(max)) => messages
+                            .push(format!("{}: must be between {} and {}.", field, min, max)),
+                        (Some(min), None) => {
+                            messages.push(format!("{}: must be at least {}.", field, min))
+                        }
+                        (None, Some(max)) => {
+                            messages.push(format!("{}: must be at most {}.", field, max))
+                        }
+                        (None, None) => messages.push(format!("{}: is out of range.", field)),
+                    }
+                }
                 Cow::Borrowed("email") => {
                     let email = error
                         .params
                         .get("value")
-                        .and_then(|min| Some(min.to_string()))
+                        .map(|value| value.to_string())
                         .unwrap();
 
                     messages.push(format!("{}: {} is not a valid email.", field, email));
@@ -27,7 +27,7 @@ where
                 let bearer_token_result = value.to_str();
 
                 if let Ok(bearer_token) = bearer_token_result {
-                    let scheme = bearer_token.splitn(2, " ").next().unwrap_or_default();
+                    let scheme = bearer_token.split(' ').next().unwrap_or_default();
                     if scheme != "Bearer" {
                         let status = StatusCode::UNAUTHORIZED;
                         let payload = ErrorResponse::new(
@@ -37,9 +37,8 @@ where
                         return Err((status, Json(payload)).into_response());
                     }
                     let token = bearer_token
-                        .splitn(2, " ")
-                        .skip(1)
-                        .next()
+                        .split_once(' ')
+                        .map(|(_, token)| token)
                         .unwrap_or_default();
 
                     let decoded = decode_jwt(token);
@@ -0,0 +1,68 @@
+use std::net::{IpAddr, SocketAddr};
+
+use axum::{
+    async_trait,
+    body::Body,
+    extract::{ConnectInfo, FromRequestParts},
+    http::{header, request::Parts, Response},
+    response::IntoResponse,
+};
+
+use crate::{core::models::ApiError, ApiContext};
+
+/// The network identity of the caller, as far as the server can tell.
+#[derive(Clone)]
+pub struct ClientInfo {
+    pub ip: IpAddr,
+    pub user_agent: String,
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ClientInfo
+where
+    S: Send + Sync,
+{
+    type Rejection = Response<Body>;
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let trusted_proxies = parts
+            .extensions
+            .get::<ApiContext>()
+            .filter(|ctx| ctx.config.trust_proxy)
+            .map_or(0, |ctx| ctx.config.trusted_proxies as usize);
+
+        // each proxy appends the address it got the request from, anything
+        // left of what our own proxies added is up to the client
+        let forwarded = parts
+            .headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .collect::<Vec<_>>()
+            .join(",");
+        let forwarded_ip = (trusted_proxies > 0)
+            .then(|| forwarded.rsplit(',').nth(trusted_proxies - 1))
+            .flatten()
+            .and_then(|ip| ip.trim().parse::<IpAddr>().ok());
+
+        let ip = match forwarded_ip {
+            Some(ip) => ip,
+            None => parts
+                .extensions
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip())
+                .ok_or_else(|| {
+                    ApiError::InternalServer("Unable to determine client address".to_string())
+                        .into_response()
+                })?,
+        };
+
+        let user_agent = parts
+            .headers
+            .get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+
+        Ok(ClientInfo { ip, user_agent })
+    }
+}
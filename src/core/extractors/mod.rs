@@ -1,7 +1,11 @@
 mod auth;
+mod client;
 mod request_body;
 mod request_param;
+mod request_query;
 
 pub use auth::*;
+pub use client::*;
 pub use request_body::*;
 pub use request_param::*;
+pub use request_query::*;
@@ -31,9 +31,7 @@ where
 
         match value_result {
             Ok(value) => {
-                let validation_result = value.validate();
-                if validation_result.is_err() {
-                    let errors = validation_result.unwrap_err();
+                if let Err(errors) = value.validate() {
                     let error_messages = transform_validation_errors.transform_errors(errors);
 
                     let error = ApiError::BadRequest {
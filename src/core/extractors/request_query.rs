@@ -0,0 +1,47 @@
+use axum::{
+    async_trait,
+    body::Body,
+    extract::{self, rejection::QueryRejection, FromRequestParts},
+    http::{request::Parts, Response, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde::de::DeserializeOwned;
+
+use crate::core::models::ErrorResponse;
+
+pub struct CustomQuery<T>(pub T);
+
+#[async_trait]
+impl<S, T> FromRequestParts<S> for CustomQuery<T>
+where
+    T: DeserializeOwned + Send,
+    S: Send + Sync,
+{
+    type Rejection = Response<Body>;
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let query = extract::Query::<T>::from_request_parts(parts, _state).await;
+        match query {
+            Ok(value) => Ok(Self(value.0)),
+            Err(rejection) => {
+                let (code, error_response) = match rejection {
+                    QueryRejection::FailedToDeserializeQueryString(inner) => {
+                        let status = StatusCode::BAD_REQUEST;
+                        (status, ErrorResponse::new(vec![inner.body_text()], status))
+                    }
+                    _ => {
+                        let code = StatusCode::INTERNAL_SERVER_ERROR;
+                        (
+                            code,
+                            ErrorResponse::new(
+                                vec![format!("Unhandled query rejection: {rejection}")],
+                                code,
+                            ),
+                        )
+                    }
+                };
+                Err((code, Json(error_response)).into_response())
+            }
+        }
+    }
+}
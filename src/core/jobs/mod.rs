@@ -0,0 +1,423 @@
+use std::{
+    collections::HashMap, future::Future, pin::Pin, str::FromStr, sync::Arc, time::Duration,
+};
+
+use anyhow::{anyhow, Error};
+use axum::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use serde_json::Value;
+use sqlx::PgExecutor;
+use tokio::{sync::watch, task::JoinSet};
+use uuid::Uuid;
+
+use crate::{
+    core::{models::ApiError, traits::JobHandler, utils::backoff_seconds},
+    ApiContext,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often due schedules are queued and abandoned jobs recovered.
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A running job whose worker has not finished it after this long is assumed
+/// lost with its worker and goes back to the queue.
+const LEASE: Duration = Duration::from_secs(15 * 60);
+
+const FIRST_RETRY_SECONDS: f64 = 10.0;
+
+const MAX_RETRY_SECONDS: f64 = 3600.0;
+
+type JobFuture = Pin<Box<dyn Future<Output = Result<(), Error>> + Send>>;
+
+struct RegisteredJob {
+    max_attempts: i32,
+    timeout: Duration,
+    run: Box<dyn Fn(ApiContext, Value) -> JobFuture + Send + Sync>,
+}
+
+struct CronSchedule {
+    name: &'static str,
+    kind: &'static str,
+    expression: &'static str,
+    schedule: cron::Schedule,
+    payload: Value,
+}
+
+impl CronSchedule {
+    fn next_run(&self) -> NaiveDateTime {
+        self.schedule
+            .after(&Utc::now())
+            .next()
+            .map(|at| at.naive_utc())
+            .unwrap_or(NaiveDateTime::MAX)
+    }
+}
+
+fn payload_value<H: JobHandler>(payload: &H::Payload) -> Result<Value, ApiError> {
+    serde_json::to_value(payload)
+        .map_err(|e| ApiError::InternalServer(format!("Invalid {} payload: {e}", H::KIND)))
+}
+
+/// Queues a job for `H`, to run as soon as a worker is free or once `run_at`
+/// has passed. Pass a transaction to queue the job only if it commits.
+pub async fn enqueue<'e, H: JobHandler>(
+    db: impl PgExecutor<'e>,
+    payload: &H::Payload,
+    run_at: Option<NaiveDateTime>,
+) -> Result<Uuid, ApiError> {
+    let id = sqlx::query_scalar!(
+        r#"insert into "jobs" (kind, payload, max_attempts, run_at)
+        values ($1, $2, $3, coalesce($4::timestamp, now()))
+        returning id"#,
+        H::KIND,
+        payload_value::<H>(payload)?,
+        H::MAX_ATTEMPTS,
+        run_at
+    )
+    .fetch_one(db)
+    .await?;
+    Ok(id)
+}
+
+struct ClaimedJob {
+    id: Uuid,
+    kind: String,
+    payload: Value,
+    attempts: i32,
+    max_attempts: i32,
+}
+
+/// Runs queued jobs with the handlers registered on it, and queues the jobs
+/// of its cron schedules when they are due. Any number of runners can share
+/// the queue, in the API processes or in separate `worker` processes.
+pub struct JobRunner {
+    ctx: ApiContext,
+    worker_id: String,
+    concurrency: usize,
+    drain_timeout: Duration,
+    jobs: HashMap<&'static str, Arc<RegisteredJob>>,
+    schedules: Vec<CronSchedule>,
+}
+
+impl JobRunner {
+    pub fn new(ctx: ApiContext) -> Self {
+        JobRunner {
+            worker_id: format!(
+                "{}-{}",
+                std::process::id(),
+                hex::encode(rand::random::<[u8; 4]>())
+            ),
+            concurrency: ctx.config.job_concurrency.max(1) as usize,
+            drain_timeout: Duration::from_secs(ctx.config.shutdown_drain_seconds.into()),
+            ctx,
+            jobs: HashMap::new(),
+            schedules: Vec::new(),
+        }
+    }
+
+    pub fn register<H: JobHandler>(mut self, handler: H) -> Self {
+        let handler = Arc::new(handler);
+        let run = move |ctx: ApiContext, payload: Value| -> JobFuture {
+            let handler = handler.clone();
+            Box::pin(async move {
+                let payload = serde_json::from_value(payload)
+                    .map_err(|e| anyhow!("Invalid {} payload: {e}", H::KIND))?;
+                handler.run(&ctx, payload).await
+            })
+        };
+        self.jobs.insert(
+            H::KIND,
+            Arc::new(RegisteredJob {
+                max_attempts: H::MAX_ATTEMPTS,
+                timeout: H::TIMEOUT,
+                run: Box::new(run),
+            }),
+        );
+        self
+    }
+
+    /// Queues a job for `H` at every time matching `expression`, a cron
+    /// expression with seconds, eg: `0 */5 * * * *` every five minutes.
+    /// Runs missed while no runner was up are not caught up on.
+    pub fn schedule<H: JobHandler>(
+        mut self,
+        name: &'static str,
+        expression: &'static str,
+        payload: H::Payload,
+    ) -> Self {
+        self.schedules.push(CronSchedule {
+            name,
+            kind: H::KIND,
+            expression,
+            schedule: cron::Schedule::from_str(expression)
+                .unwrap_or_else(|e| panic!("Invalid cron expression for {name}: {e}")),
+            payload: payload_value::<H>(&payload)
+                .unwrap_or_else(|e| panic!("Invalid payload for {name}: {e:?}")),
+        });
+        self
+    }
+
+    /// Brings the stored schedules in line with the registered ones. A
+    /// schedule keeps its next run unless its expression changed.
+    async fn sync_schedules(&self) -> Result<(), sqlx::Error> {
+        let mut tx = self.ctx.db.begin().await?;
+        for schedule in &self.schedules {
+            sqlx::query!(
+                r#"insert into "job_schedules" (name, kind, cron, payload, next_run_at)
+                values ($1, $2, $3, $4, $5)
+                on conflict (name) do update set
+                    kind = excluded.kind,
+                    payload = excluded.payload,
+                    next_run_at = case when "job_schedules".cron = excluded.cron
+                        then "job_schedules".next_run_at else excluded.next_run_at end,
+                    cron = excluded.cron"#,
+                schedule.name,
+                schedule.kind,
+                schedule.expression,
+                schedule.payload,
+                schedule.next_run()
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        let names: Vec<&str> = self
+            .schedules
+            .iter()
+            .map(|schedule| schedule.name)
+            .collect();
+        sqlx::query!(
+            r#"delete from "job_schedules" where name <> all($1)"#,
+            &names as &[&str]
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+    }
+
+    /// Queues the jobs of due schedules, each once however many runners look.
+    async fn queue_due_schedules(&self) -> Result<(), sqlx::Error> {
+        let mut tx = self.ctx.db.begin().await?;
+        let due = sqlx::query_scalar!(
+            r#"select name from "job_schedules" where next_run_at <= now()
+            for update skip locked"#
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for name in due {
+            let Some(schedule) = self.schedules.iter().find(|schedule| schedule.name == name)
+            else {
+                continue;
+            };
+            let max_attempts = self
+                .jobs
+                .get(schedule.kind)
+                .map_or(1, |job| job.max_attempts);
+            sqlx::query!(
+                r#"insert into "jobs" (kind, payload, max_attempts) values ($1, $2, $3)"#,
+                schedule.kind,
+                schedule.payload,
+                max_attempts
+            )
+            .execute(&mut *tx)
+            .await?;
+            sqlx::query!(
+                r#"update "job_schedules" set next_run_at = $2, last_run_at = now() where name = $1"#,
+                schedule.name,
+                schedule.next_run()
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    /// Returns jobs whose worker went away mid-run to the queue, or buries
+    /// them when that was their last attempt.
+    async fn recover_abandoned(&self) -> Result<(), sqlx::Error> {
+        let recovered = sqlx::query!(
+            r#"update "jobs" set
+                status = case when attempts >= max_attempts then 'dead' else 'queued' end,
+                finished_at = case when attempts >= max_attempts then now() end,
+                last_error = 'Worker stopped before the job finished',
+                locked_at = null, locked_by = null
+            where status = 'running' and locked_at < now() - make_interval(secs => $1)"#,
+            LEASE.as_secs_f64()
+        )
+        .execute(&self.ctx.db)
+        .await?;
+        if recovered.rows_affected() > 0 {
+            tracing::warn!("Recovered {} abandoned jobs", recovered.rows_affected());
+        }
+        Ok(())
+    }
+
+    /// Locks up to `limit` due jobs of the registered kinds for this worker.
+    async fn claim(&self, limit: usize) -> Result<Vec<ClaimedJob>, sqlx::Error> {
+        let kinds: Vec<&str> = self.jobs.keys().copied().collect();
+        sqlx::query_as!(
+            ClaimedJob,
+            r#"update "jobs" set status = 'running', attempts = attempts + 1, locked_at = now(), locked_by = $2
+            where id in (
+                select id from "jobs"
+                where status = 'queued' and run_at <= now() and kind = any($3)
+                order by run_at
+                limit $1
+                for update skip locked
+            )
+            returning id, kind, payload, attempts, max_attempts"#,
+            limit as i64,
+            self.worker_id,
+            &kinds as &[&str]
+        )
+        .fetch_all(&self.ctx.db)
+        .await
+    }
+
+    async fn execute(ctx: ApiContext, registered: Arc<RegisteredJob>, job: ClaimedJob) {
+        let run = (registered.run)(ctx.clone(), job.payload);
+        let outcome = match tokio::time::timeout(registered.timeout, run).await {
+            Ok(outcome) => outcome,
+            Err(_) => Err(anyhow!(
+                "Timed out after {} seconds",
+                registered.timeout.as_secs()
+            )),
+        };
+
+        let finished = match &outcome {
+            Ok(()) => sqlx::query!(
+                r#"update "jobs" set status = 'succeeded', finished_at = now(), last_error = null,
+                        locked_at = null, locked_by = null
+                    where id = $1"#,
+                job.id
+            )
+            .execute(&ctx.db)
+            .await,
+            Err(e) => {
+                let dead = job.attempts >= job.max_attempts;
+                if dead {
+                    tracing::error!("Job {} ({}) is dead: {e:#}", job.id, job.kind);
+                } else {
+                    tracing::warn!(
+                        "Job {} ({}) failed attempt {}: {e:#}",
+                        job.id,
+                        job.kind,
+                        job.attempts
+                    );
+                }
+                let error: String = format!("{e:#}").chars().take(1000).collect();
+                sqlx::query!(
+                    r#"update "jobs" set
+                        status = case when $2 then 'dead' else 'queued' end,
+                        finished_at = case when $2 then now() end,
+                        run_at = now() + make_interval(secs => $3),
+                        last_error = $4, locked_at = null, locked_by = null
+                    where id = $1"#,
+                    job.id,
+                    dead,
+                    backoff_seconds(job.attempts, FIRST_RETRY_SECONDS, MAX_RETRY_SECONDS),
+                    error
+                )
+                .execute(&ctx.db)
+                .await
+            }
+        };
+        if let Err(e) = finished {
+            tracing::warn!("Failed to record the outcome of job {}: {e}", job.id);
+        }
+    }
+
+    /// Runs until `shutdown` flips, then stops claiming and waits for the jobs
+    /// in flight. Jobs still running after the drain timeout are cut short and
+    /// queued again without the attempt counting.
+    pub async fn run(self, mut shutdown: watch::Receiver<bool>) {
+        if let Err(e) = self.sync_schedules().await {
+            tracing::warn!("Failed to sync job schedules: {e}");
+        }
+
+        let mut in_flight = JoinSet::new();
+        let mut poll = tokio::time::interval(POLL_INTERVAL);
+        let mut maintenance = tokio::time::interval(MAINTENANCE_INTERVAL);
+
+        loop {
+            tokio::select! {
+                _ = shutdown.changed() => break,
+                _ = maintenance.tick() => {
+                    if let Err(e) = self.queue_due_schedules().await {
+                        tracing::warn!("Failed to queue scheduled jobs: {e}");
+                    }
+                    if let Err(e) = self.recover_abandoned().await {
+                        tracing::warn!("Failed to recover abandoned jobs: {e}");
+                    }
+                }
+                _ = poll.tick() => {}
+                Some(_) = in_flight.join_next(), if !in_flight.is_empty() => {}
+            }
+
+            let free = self.concurrency.saturating_sub(in_flight.len());
+            if free == 0 {
+                continue;
+            }
+            let claimed = match self.claim(free).await {
+                Ok(claimed) => claimed,
+                Err(e) => {
+                    tracing::warn!("Failed to claim jobs: {e}");
+                    continue;
+                }
+            };
+            for job in claimed {
+                if let Some(registered) = self.jobs.get(job.kind.as_str()) {
+                    in_flight.spawn(Self::execute(self.ctx.clone(), registered.clone(), job));
+                }
+            }
+        }
+
+        if in_flight.is_empty() {
+            return;
+        }
+        tracing::info!("Draining {} running jobs", in_flight.len());
+        let drained = tokio::time::timeout(self.drain_timeout, async {
+            while in_flight.join_next().await.is_some() {}
+        })
+        .await;
+        if drained.is_err() {
+            in_flight.abort_all();
+            let released = sqlx::query!(
+                r#"update "jobs" set status = 'queued', attempts = attempts - 1, locked_at = null,
+                    locked_by = null
+                where status = 'running' and locked_by = $1"#,
+                self.worker_id
+            )
+            .execute(&self.ctx.db)
+            .await;
+            match released {
+                Ok(released) => {
+                    tracing::warn!("Queued {} unfinished jobs again", released.rows_affected())
+                }
+                Err(e) => tracing::warn!("Failed to release unfinished jobs: {e}"),
+            }
+        }
+    }
+}
+
+/// Forgets succeeded jobs after a week and dead ones after a month.
+pub struct PruneJobs;
+
+#[async_trait]
+impl JobHandler for PruneJobs {
+    const KIND: &'static str = "jobs.prune";
+
+    type Payload = ();
+
+    async fn run(&self, ctx: &ApiContext, _: ()) -> Result<(), Error> {
+        sqlx::query!(
+            r#"delete from "jobs"
+            where (status = 'succeeded' and finished_at < now() - interval '7 days')
+                or (status = 'dead' and finished_at < now() - interval '30 days')"#
+        )
+        .execute(&ctx.db)
+        .await?;
+        Ok(())
+    }
+}
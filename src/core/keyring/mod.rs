@@ -0,0 +1,334 @@
+use std::{collections::HashMap, sync::RwLock};
+
+use anyhow::{anyhow, Error};
+use axum::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    config::Config,
+    core::{
+        models::ApiError,
+        traits::JobHandler,
+        utils::{key_id, seal, unseal},
+    },
+    ApiContext,
+};
+
+/// Rows a maintenance job handles per transaction.
+const BATCH_SIZE: i64 = 100;
+
+/// Batches one run of a maintenance job goes through, the next run carries on.
+const MAX_BATCHES: usize = 20;
+
+/// A key wrapping the users' data keys, named by its digest so no id has to
+/// be configured along with it.
+struct MasterKey {
+    id: String,
+    key: [u8; 32],
+}
+
+impl MasterKey {
+    fn parse(name: &str, value: &str) -> Self {
+        let key: [u8; 32] = STANDARD
+            .decode(value.trim())
+            .ok()
+            .and_then(|key| key.try_into().ok())
+            .unwrap_or_else(|| panic!("Invalid {name} env variable, expected 32 bytes in base64"));
+        MasterKey {
+            id: key_id(&key),
+            key,
+        }
+    }
+}
+
+/// Encryption at rest of message bodies. Each user has a random data key the
+/// bodies are sealed with, stored wrapped by the master key. Rotating the
+/// master key only rewraps data keys, sealed bodies are left as they are.
+pub struct Keyring {
+    master_key: Option<MasterKey>,
+    previous_master_keys: Vec<MasterKey>,
+    data_keys: RwLock<HashMap<Uuid, [u8; 32]>>,
+}
+
+impl Keyring {
+    pub fn new(config: &Config) -> Self {
+        Keyring {
+            master_key: (!config.master_key.is_empty())
+                .then(|| MasterKey::parse("MASTER_KEY", &config.master_key)),
+            previous_master_keys: config
+                .previous_master_keys
+                .split(',')
+                .filter(|key| !key.trim().is_empty())
+                .map(|key| MasterKey::parse("PREVIOUS_MASTER_KEYS", key))
+                .collect(),
+            data_keys: RwLock::default(),
+        }
+    }
+
+    /// Whether new bodies are sealed. Sealed bodies can be opened either way
+    /// while their master key is configured.
+    pub fn is_sealing(&self) -> bool {
+        self.master_key.is_some()
+    }
+
+    fn master_key(&self, id: &str) -> Result<&MasterKey, Error> {
+        self.master_key
+            .iter()
+            .chain(&self.previous_master_keys)
+            .find(|master_key| master_key.id == id)
+            .ok_or_else(|| anyhow!("Master key {id} is not configured"))
+    }
+
+    /// The user's data key, made on first use.
+    async fn data_key(&self, db: &PgPool, user_id: Uuid) -> Result<[u8; 32], ApiError> {
+        if let Some(key) = self.data_keys.read().unwrap().get(&user_id) {
+            return Ok(*key);
+        }
+
+        let key = loop {
+            let wrapped = sqlx::query!(
+                r#"select wrapped_key, master_key_id from "data_keys" where user_id = $1"#,
+                user_id
+            )
+            .fetch_optional(db)
+            .await?;
+            if let Some(wrapped) = wrapped {
+                let key = self
+                    .master_key(&wrapped.master_key_id)
+                    .and_then(|master_key| {
+                        unseal(&master_key.key, user_id.as_bytes(), &wrapped.wrapped_key)
+                    })
+                    .and_then(|key| {
+                        key.try_into()
+                            .map_err(|_| anyhow!("A data key is 32 bytes"))
+                    })
+                    .map_err(|e| ApiError::InternalServer(format!("Data key of {user_id}: {e}")))?;
+                break key;
+            }
+
+            let master_key = self
+                .master_key
+                .as_ref()
+                .ok_or_else(|| ApiError::InternalServer("MASTER_KEY is not set".to_string()))?;
+            let key: [u8; 32] = rand::random();
+            let wrapped = seal(&master_key.key, user_id.as_bytes(), &key)
+                .map_err(|e| ApiError::InternalServer(e.to_string()))?;
+            // another instance may have made one meanwhile, then read theirs
+            let inserted = sqlx::query!(
+                r#"insert into "data_keys" (user_id, wrapped_key, master_key_id) values ($1, $2, $3)
+                on conflict (user_id) do nothing"#,
+                user_id,
+                wrapped,
+                master_key.id
+            )
+            .execute(db)
+            .await?;
+            if inserted.rows_affected() == 1 {
+                break key;
+            }
+        };
+
+        self.data_keys.write().unwrap().insert(user_id, key);
+        Ok(key)
+    }
+
+    /// Encrypts a body of the user's, or returns `None` when bodies are
+    /// stored as they are.
+    pub async fn seal(
+        &self,
+        db: &PgPool,
+        user_id: Uuid,
+        body: &str,
+    ) -> Result<Option<Vec<u8>>, ApiError> {
+        if !self.is_sealing() {
+            return Ok(None);
+        }
+        let key = self.data_key(db, user_id).await?;
+        seal(&key, user_id.as_bytes(), body.as_bytes())
+            .map(Some)
+            .map_err(|e| ApiError::InternalServer(e.to_string()))
+    }
+
+    /// Decrypts a body sealed with `seal` for the same user.
+    pub async fn open(
+        &self,
+        db: &PgPool,
+        user_id: Uuid,
+        sealed: &[u8],
+    ) -> Result<String, ApiError> {
+        let key = self.data_key(db, user_id).await?;
+        unseal(&key, user_id.as_bytes(), sealed)
+            .and_then(|body| Ok(String::from_utf8(body)?))
+            .map_err(|e| ApiError::InternalServer(format!("Sealed body of {user_id}: {e}")))
+    }
+
+    /// Rewraps up to `BATCH_SIZE` data keys still wrapped by a previous master
+    /// key with the current one, returning how many it did.
+    async fn rewrap(&self, db: &PgPool) -> Result<usize, Error> {
+        let Some(master_key) = &self.master_key else {
+            return Ok(0);
+        };
+
+        let mut tx = db.begin().await?;
+        let stale = sqlx::query!(
+            r#"select user_id, wrapped_key, master_key_id from "data_keys"
+            where master_key_id <> $1
+            limit $2
+            for update skip locked"#,
+            master_key.id,
+            BATCH_SIZE
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for data_key in &stale {
+            let previous = self.master_key(&data_key.master_key_id)?;
+            let key = unseal(
+                &previous.key,
+                data_key.user_id.as_bytes(),
+                &data_key.wrapped_key,
+            )?;
+            sqlx::query!(
+                r#"update "data_keys" set wrapped_key = $2, master_key_id = $3 where user_id = $1"#,
+                data_key.user_id,
+                seal(&master_key.key, data_key.user_id.as_bytes(), &key)?,
+                master_key.id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(stale.len())
+    }
+
+    /// Seals up to `BATCH_SIZE` message bodies, and copies of them in
+    /// reports, stored before sealing was turned on, and clears the copies
+    /// queued in webhook payloads. Returns how many it did.
+    async fn seal_stored(&self, db: &PgPool) -> Result<usize, Error> {
+        let mut tx = db.begin().await?;
+        let messages = sqlx::query!(
+            r#"select id, recipient_id, body as "body!" from "messages"
+            where body is not null and encryption_key_id is null
+            limit $1
+            for update skip locked"#,
+            BATCH_SIZE
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        // nulling body also nulls the generated search_vector, and content_hash
+        // is keyed with a server secret, so no plaintext is left derived from it
+        for message in &messages {
+            let sealed = self.seal(db, message.recipient_id, &message.body).await?;
+            sqlx::query!(
+                r#"update "messages" set body = null, sealed_body = $2 where id = $1"#,
+                message.id,
+                sealed
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let reports = sqlx::query!(
+            r#"select id, recipient_id, message_body as "message_body!" from "reports"
+            where message_body is not null
+            limit $1
+            for update skip locked"#,
+            BATCH_SIZE
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        for report in &reports {
+            let sealed = self
+                .seal(db, report.recipient_id, &report.message_body)
+                .await?;
+            sqlx::query!(
+                r#"update "reports" set message_body = null, sealed_message_body = $2 where id = $1"#,
+                report.id,
+                sealed
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        // `open_payload` fills the body back in from `sealed_body` when sending,
+        // deliveries of messages deleted since go out without one
+        let deliveries = sqlx::query!(
+            r#"update "webhook_deliveries" set payload = jsonb_set(payload, '{body}', 'null')
+            where id in (
+                select d.id from "webhook_deliveries" d
+                where d.event = 'message.received' and jsonb_typeof(d.payload->'body') = 'string'
+                    and not exists (
+                        select 1 from "messages" m
+                        where m.id = (d.payload->>'message_id')::uuid and m.body is not null
+                    )
+                limit $1
+                for update skip locked
+            )"#,
+            BATCH_SIZE
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(messages.len() + reports.len() + deliveries.rows_affected() as usize)
+    }
+}
+
+/// Finishes a master key rotation: once `MASTER_KEY` is replaced and the old
+/// key moved to `PREVIOUS_MASTER_KEYS`, rewraps every data key with the new
+/// one. The old key can be dropped when no data key is left on it.
+pub struct RewrapDataKeys;
+
+#[async_trait]
+impl JobHandler for RewrapDataKeys {
+    const KIND: &'static str = "keys.rewrap";
+
+    const MAX_ATTEMPTS: i32 = 1;
+
+    type Payload = ();
+
+    async fn run(&self, ctx: &ApiContext, _: ()) -> Result<(), Error> {
+        let mut rewrapped = 0;
+        for _ in 0..MAX_BATCHES {
+            match ctx.keyring.rewrap(&ctx.db).await? {
+                0 => break,
+                count => rewrapped += count,
+            }
+        }
+        if rewrapped > 0 {
+            tracing::info!("Rewrapped {rewrapped} data keys");
+        }
+        Ok(())
+    }
+}
+
+/// Seals the bodies stored in plaintext before `MASTER_KEY` was set.
+pub struct SealStoredBodies;
+
+#[async_trait]
+impl JobHandler for SealStoredBodies {
+    const KIND: &'static str = "messages.seal_stored";
+
+    const MAX_ATTEMPTS: i32 = 1;
+
+    type Payload = ();
+
+    async fn run(&self, ctx: &ApiContext, _: ()) -> Result<(), Error> {
+        if !ctx.keyring.is_sealing() {
+            return Ok(());
+        }
+        let mut sealed = 0;
+        for _ in 0..MAX_BATCHES {
+            match ctx.keyring.seal_stored(&ctx.db).await? {
+                0 => break,
+                count => sealed += count,
+            }
+        }
+        if sealed > 0 {
+            tracing::info!("Sealed {sealed} stored message bodies");
+        }
+        Ok(())
+    }
+}
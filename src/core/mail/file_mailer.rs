@@ -0,0 +1,30 @@
+use anyhow::Error;
+use axum::async_trait;
+use lettre::{message::Mailbox, AsyncFileTransport, AsyncTransport, Tokio1Executor};
+
+use crate::core::{mail::message, models::Email, traits::Mailer};
+
+/// Writes every email to an `.eml` file under a directory instead of sending
+/// it, for development.
+pub struct FileMailer {
+    from: Mailbox,
+    transport: AsyncFileTransport<Tokio1Executor>,
+}
+
+impl FileMailer {
+    pub fn new(dir: &str, from: Mailbox) -> Self {
+        std::fs::create_dir_all(dir).expect("Unable to create the mail directory");
+        FileMailer {
+            from,
+            transport: AsyncFileTransport::new(dir),
+        }
+    }
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, email: Email) -> Result<(), Error> {
+        self.transport.send(message(&self.from, email)?).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+mod file_mailer;
+mod smtp_mailer;
+
+pub use file_mailer::*;
+pub use smtp_mailer::*;
+
+use std::sync::Arc;
+
+use anyhow::Error;
+use lettre::{
+    message::{
+        header::{HeaderName, HeaderValue},
+        Mailbox, MultiPart,
+    },
+    Message,
+};
+
+use crate::{
+    config::Config,
+    core::{models::Email, traits::Mailer},
+};
+
+pub fn mailer(config: &Config) -> Arc<dyn Mailer> {
+    let from: Mailbox = config
+        .mail_from
+        .parse()
+        .unwrap_or_else(|e| panic!("Invalid MAIL_FROM env variable: {e}"));
+    match config.mail_backend.as_str() {
+        "smtp" => Arc::new(SmtpMailer::new(&config.smtp_url, from)),
+        _ => Arc::new(FileMailer::new(&config.mail_dir, from)),
+    }
+}
+
+fn message(from: &Mailbox, email: Email) -> Result<Message, Error> {
+    let mut builder = Message::builder()
+        .from(from.clone())
+        .to(email.to.parse()?)
+        .subject(email.subject);
+    if let Some(url) = email.unsubscribe_url {
+        builder = builder
+            .raw_header(HeaderValue::new(
+                HeaderName::new_from_ascii_str("List-Unsubscribe"),
+                format!("<{url}>"),
+            ))
+            .raw_header(HeaderValue::new(
+                HeaderName::new_from_ascii_str("List-Unsubscribe-Post"),
+                "List-Unsubscribe=One-Click".to_string(),
+            ));
+    }
+    Ok(builder.multipart(MultiPart::alternative_plain_html(email.text, email.html))?)
+}
@@ -1,5 +1,13 @@
+pub mod card;
+pub mod classifier;
 pub mod extractors;
+pub mod jobs;
+pub mod keyring;
+pub mod mail;
 pub mod models;
+pub mod push;
+pub mod rate_limit;
+pub mod storage;
 pub mod traits;
 pub mod utils;
 
@@ -1,4 +1,7 @@
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::ApiError;
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Claims {
@@ -7,6 +10,13 @@ pub struct Claims {
     pub exp: i64,
 }
 
+impl Claims {
+    pub fn user_id(&self) -> Result<Uuid, ApiError> {
+        Uuid::parse_str(&self.sub)
+            .map_err(|_| ApiError::Unauthorized("Invalid token subject".to_string()))
+    }
+}
+
 pub struct JwtUser {
     pub email: String,
     pub id: String,
@@ -0,0 +1,9 @@
+use uuid::Uuid;
+
+/// A message being submitted, as seen by a `MessageClassifier`.
+pub struct IncomingMessage<'a> {
+    pub recipient_id: Uuid,
+    pub body: &'a str,
+    /// Digest of the normalised body, shared by messages with the same content.
+    pub content_hash: &'a [u8],
+}
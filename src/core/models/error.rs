@@ -23,6 +23,8 @@ impl ErrorResponse {
                 StatusCode::BAD_REQUEST => "Bad Request".to_string(),
                 StatusCode::UNPROCESSABLE_ENTITY => "Unprocessable Entity".to_string(),
                 StatusCode::CONFLICT => "Conflict".to_string(),
+                StatusCode::GONE => "Gone".to_string(),
+                StatusCode::TOO_MANY_REQUESTS => "Too Many Requests".to_string(),
                 _ => "Internal Server Error".to_string(),
             },
         }
@@ -32,7 +34,7 @@ impl ErrorResponse {
 #[derive(thiserror::Error, Debug)]
 pub enum ApiError {
     #[error("user may not perform this action")]
-    Forbidden,
+    Forbidden(String),
 
     #[error("request path or resource not found")]
     NotFound(String),
@@ -51,69 +53,76 @@ pub enum ApiError {
 
     #[error("invalid password or authorization token")]
     Unauthorized(String),
+
+    #[error("resource existed but is no longer available")]
+    Gone(String),
+
+    #[error("too many requests or connections")]
+    TooManyRequests(String),
 }
 
 impl ApiError {
     fn status_code(&self) -> StatusCode {
         match self {
-            Self::Forbidden => StatusCode::FORBIDDEN,
+            Self::Forbidden(_) => StatusCode::FORBIDDEN,
             Self::NotFound(..) => StatusCode::NOT_FOUND,
             Self::BadRequest { .. } => StatusCode::BAD_REQUEST,
             Self::Database(_) | Self::InternalServer(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Self::Conflict(_) => StatusCode::CONFLICT,
             Self::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Self::Gone(_) => StatusCode::GONE,
+            Self::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
         }
     }
 }
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response<Body> {
+        let code = self.status_code();
         match &self {
             Self::BadRequest { errors } => {
-                let code = self.status_code();
-                return (code, Json(ErrorResponse::new(errors.to_vec(), code))).into_response();
-            }
-            Self::NotFound(ref error) => {
-                let code = self.status_code();
-                return (
-                    code,
-                    Json(ErrorResponse::new(vec![error.to_string()], code)),
-                )
-                    .into_response();
-            }
-            Self::Unauthorized(ref error) => {
-                let code = self.status_code();
-                return (
-                    code,
-                    Json(ErrorResponse::new(vec![error.to_string()], code)),
-                )
-                    .into_response();
+                (code, Json(ErrorResponse::new(errors.to_vec(), code))).into_response()
             }
+            Self::NotFound(ref error) => (
+                code,
+                Json(ErrorResponse::new(vec![error.to_string()], code)),
+            )
+                .into_response(),
+            Self::Unauthorized(ref error) => (
+                code,
+                Json(ErrorResponse::new(vec![error.to_string()], code)),
+            )
+                .into_response(),
+            Self::Forbidden(ref error) => (
+                code,
+                Json(ErrorResponse::new(vec![error.to_string()], code)),
+            )
+                .into_response(),
             Self::Database(ref e) => {
                 // TODO: we probably want to use `tracing` instead
                 // so that this gets linked to the HTTP request by `TraceLayer`.
                 // log::error!("SQLx error: {:?}", e);
-                let code = self.status_code();
-                return (code, Json(ErrorResponse::new(vec![e.to_string()], code))).into_response();
+                (code, Json(ErrorResponse::new(vec![e.to_string()], code))).into_response()
             }
 
             Self::InternalServer(ref e) => {
                 // TODO: we probably want to use `tracing` instead
                 // so that this gets linked to the HTTP request by `TraceLayer`.
                 // log::error!("Generic error: {:?}", e);
-                let code = self.status_code();
-                return (code, Json(ErrorResponse::new(vec![e.to_string()], code))).into_response();
+                (code, Json(ErrorResponse::new(vec![e.to_string()], code))).into_response()
             }
 
             Self::Conflict(ref e) => {
-                let code = self.status_code();
-                return (code, Json(ErrorResponse::new(vec![e.to_string()], code))).into_response();
+                (code, Json(ErrorResponse::new(vec![e.to_string()], code))).into_response()
             }
 
-            // Other errors get mapped normally.
-            _ => (),
-        }
+            Self::Gone(ref e) => {
+                (code, Json(ErrorResponse::new(vec![e.to_string()], code))).into_response()
+            }
 
-        (self.status_code(), self.to_string()).into_response()
+            Self::TooManyRequests(ref e) => {
+                (code, Json(ErrorResponse::new(vec![e.to_string()], code))).into_response()
+            }
+        }
     }
 }
@@ -0,0 +1,9 @@
+/// An email with HTML and plain text alternatives.
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub html: String,
+    pub text: String,
+    /// Sent as `List-Unsubscribe`, with one-click unsubscribe by `POST`.
+    pub unsubscribe_url: Option<String>,
+}
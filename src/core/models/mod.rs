@@ -1,5 +1,15 @@
 mod auth;
+mod classifier;
 mod error;
+mod mail;
+mod push;
+mod rate_limit;
+mod storage;
 
 pub use auth::*;
+pub use classifier::*;
 pub use error::*;
+pub use mail::*;
+pub use push::*;
+pub use rate_limit::*;
+pub use storage::*;
@@ -0,0 +1,12 @@
+/// An encrypted push message for one subscription.
+pub struct PushMessage {
+    pub endpoint: String,
+    /// The VAPID `Authorization` header.
+    pub authorization: String,
+    /// Seconds the push service keeps the message for an offline device.
+    pub ttl: u32,
+    /// `very-low`, `low`, `normal` or `high`.
+    pub urgency: &'static str,
+    /// An `aes128gcm` encoded body.
+    pub body: Vec<u8>,
+}
@@ -0,0 +1,12 @@
+use std::time::Duration;
+
+/// Outcome of taking one token from a bucket.
+pub struct RateLimitDecision {
+    pub allowed: bool,
+    pub limit: u32,
+    pub remaining: u32,
+    /// Time until the bucket is full again.
+    pub reset_after: Duration,
+    /// Time until the next token is available, zero when `allowed`.
+    pub retry_after: Duration,
+}
@@ -0,0 +1,4 @@
+pub struct Blob {
+    pub bytes: Vec<u8>,
+    pub content_type: String,
+}
@@ -0,0 +1,18 @@
+mod relay_push_sender;
+mod web_push_sender;
+
+pub use relay_push_sender::*;
+pub use web_push_sender::*;
+
+use std::{sync::Arc, time::Duration};
+
+use crate::{config::Config, core::traits::PushSender};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub fn push_sender(config: &Config) -> Arc<dyn PushSender> {
+    match config.push_backend.as_str() {
+        "relay" => Arc::new(RelayPushSender::new(&config.push_relay_url)),
+        _ => Arc::new(WebPushSender),
+    }
+}
@@ -0,0 +1,44 @@
+use anyhow::Error;
+use axum::async_trait;
+use reqwest::Client;
+
+use crate::core::{models::PushMessage, push::REQUEST_TIMEOUT, traits::PushSender};
+
+/// Posts every push message to one fixed URL, eg: a local stand-in push
+/// service in development and tests, with the subscription's endpoint in
+/// `x-push-endpoint`. Its status code is taken as the push service's.
+pub struct RelayPushSender {
+    client: Client,
+    url: String,
+}
+
+impl RelayPushSender {
+    pub fn new(url: &str) -> Self {
+        RelayPushSender {
+            client: Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .expect("Unable to build the push relay client"),
+            url: url.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl PushSender for RelayPushSender {
+    async fn send(&self, message: PushMessage) -> Result<u16, Error> {
+        let response = self
+            .client
+            .post(&self.url)
+            .header("x-push-endpoint", message.endpoint)
+            .header("authorization", message.authorization)
+            .header("content-encoding", "aes128gcm")
+            .header("content-type", "application/octet-stream")
+            .header("ttl", message.ttl)
+            .header("urgency", message.urgency)
+            .body(message.body)
+            .send()
+            .await?;
+        Ok(response.status().as_u16())
+    }
+}
@@ -0,0 +1,37 @@
+use anyhow::{anyhow, Error};
+use axum::async_trait;
+use reqwest::Url;
+
+use crate::core::{
+    models::PushMessage, push::REQUEST_TIMEOUT, traits::PushSender, utils::pinned_client,
+};
+
+/// Posts to the subscription's endpoint, which must be an https URL of a
+/// public address since browsers hand out endpoints of their vendor's push
+/// service.
+pub struct WebPushSender;
+
+#[async_trait]
+impl PushSender for WebPushSender {
+    async fn send(&self, message: PushMessage) -> Result<u16, Error> {
+        let url = Url::parse(&message.endpoint)?;
+        if url.scheme() != "https" {
+            return Err(anyhow!("Push endpoints must use https"));
+        }
+        let client = pinned_client(&url, false, REQUEST_TIMEOUT)
+            .await
+            .map_err(|e| anyhow!(e))?;
+
+        let response = client
+            .post(url)
+            .header("authorization", message.authorization)
+            .header("content-encoding", "aes128gcm")
+            .header("content-type", "application/octet-stream")
+            .header("ttl", message.ttl)
+            .header("urgency", message.urgency)
+            .body(message.body)
+            .send()
+            .await?;
+        Ok(response.status().as_u16())
+    }
+}
@@ -0,0 +1,177 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use anyhow::Error;
+use axum::async_trait;
+
+use crate::core::{models::RateLimitDecision, rate_limit::decide, traits::RateLimitStore};
+
+/// Full buckets that refused a request are kept this long for `recently_limited`.
+const LIMITED_RETENTION: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// How often `acquire` prunes on its own, as the `PruneRateLimits` job may
+/// run in a worker process that doesn't share these buckets.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+struct Bucket {
+    tokens: f64,
+    capacity: f64,
+    refill_per_second: f64,
+    updated_at: Instant,
+    limited_at: Option<Instant>,
+}
+
+impl Bucket {
+    fn refilled(&self, now: Instant) -> f64 {
+        let elapsed = now.duration_since(self.updated_at).as_secs_f64();
+        (self.tokens + elapsed * self.refill_per_second).min(self.capacity)
+    }
+}
+
+fn prune_buckets(buckets: &mut HashMap<String, Bucket>, now: Instant) {
+    buckets.retain(|_, bucket| {
+        bucket.refilled(now) < bucket.capacity
+            || bucket
+                .limited_at
+                .is_some_and(|limited_at| now.duration_since(limited_at) < LIMITED_RETENTION)
+    });
+}
+
+/// Buckets kept in process memory. Limits are per instance, so use
+/// `PostgresRateLimitStore` when running several instances.
+#[derive(Default)]
+pub struct MemoryRateLimitStore {
+    buckets: Mutex<HashMap<String, Bucket>>,
+    pruned_at: Mutex<Option<Instant>>,
+}
+
+#[async_trait]
+impl RateLimitStore for MemoryRateLimitStore {
+    async fn acquire(
+        &self,
+        key: &str,
+        capacity: u32,
+        refill_per_second: f64,
+    ) -> Result<RateLimitDecision, Error> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        let mut pruned_at = self.pruned_at.lock().unwrap();
+        if !pruned_at.is_some_and(|pruned_at| now.duration_since(pruned_at) < PRUNE_INTERVAL) {
+            prune_buckets(&mut buckets, now);
+            *pruned_at = Some(now);
+        }
+        drop(pruned_at);
+
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: capacity as f64,
+            capacity: capacity as f64,
+            refill_per_second,
+            updated_at: now,
+            limited_at: None,
+        });
+
+        let tokens = bucket.refilled(now);
+        let allowed = tokens >= 1.0;
+        bucket.tokens = if allowed { tokens - 1.0 } else { tokens };
+        bucket.capacity = capacity as f64;
+        bucket.refill_per_second = refill_per_second;
+        bucket.updated_at = now;
+        if !allowed {
+            bucket.limited_at = Some(now);
+        }
+
+        Ok(decide(allowed, bucket.tokens, capacity, refill_per_second))
+    }
+
+    async fn recently_limited(&self, key: &str, within: Duration) -> Result<bool, Error> {
+        let buckets = self.buckets.lock().unwrap();
+        Ok(buckets
+            .get(key)
+            .and_then(|bucket| bucket.limited_at)
+            .is_some_and(|limited_at| limited_at.elapsed() < within))
+    }
+
+    async fn prune(&self) -> Result<(), Error> {
+        let now = Instant::now();
+        prune_buckets(&mut self.buckets.lock().unwrap(), now);
+        *self.pruned_at.lock().unwrap() = Some(now);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn take(store: &MemoryRateLimitStore, key: &str, n: usize) -> Vec<bool> {
+        let mut allowed = vec![];
+        for _ in 0..n {
+            allowed.push(store.acquire(key, 3, 20.0).await.unwrap().allowed);
+        }
+        allowed
+    }
+
+    #[tokio::test]
+    async fn allows_up_to_capacity() {
+        let store = MemoryRateLimitStore::default();
+        let first = store.acquire("ip:1", 3, 0.01).await.unwrap();
+        assert!(first.allowed);
+        assert_eq!((first.limit, first.remaining), (3, 2));
+        assert_eq!(first.retry_after, Duration::ZERO);
+        store.acquire("ip:1", 3, 0.01).await.unwrap();
+        store.acquire("ip:1", 3, 0.01).await.unwrap();
+
+        let refused = store.acquire("ip:1", 3, 0.01).await.unwrap();
+        assert!(!refused.allowed);
+        assert_eq!(refused.remaining, 0);
+        assert!(refused.retry_after > Duration::from_secs(90));
+        assert!(store
+            .recently_limited("ip:1", Duration::from_secs(60))
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn keeps_keys_apart() {
+        let store = MemoryRateLimitStore::default();
+        assert_eq!(take(&store, "ip:1", 4).await, [true, true, true, false]);
+        assert_eq!(take(&store, "ip:2", 1).await, [true]);
+        assert!(!store
+            .recently_limited("ip:2", Duration::from_secs(60))
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn refills_up_to_capacity() {
+        let store = MemoryRateLimitStore::default();
+        assert_eq!(take(&store, "ip:1", 4).await, [true, true, true, false]);
+
+        // 20 tokens a second, 2 back after 100ms
+        tokio::time::sleep(Duration::from_millis(110)).await;
+        assert_eq!(take(&store, "ip:1", 3).await, [true, true, false]);
+
+        // never more than the capacity, however long it waits
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(take(&store, "ip:1", 4).await, [true, true, true, false]);
+    }
+
+    #[tokio::test]
+    async fn prunes_full_buckets_only() {
+        let store = MemoryRateLimitStore::default();
+        take(&store, "ip:1", 1).await;
+        take(&store, "ip:2", 4).await;
+        store.acquire("ip:3", 3, 0.01).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        store.prune().await.unwrap();
+        let buckets = store.buckets.lock().unwrap();
+        // ip:1 refilled, ip:2 refilled but was limited, ip:3 is still short
+        assert!(!buckets.contains_key("ip:1"));
+        assert!(buckets.contains_key("ip:2"));
+        assert!(buckets.contains_key("ip:3"));
+    }
+}
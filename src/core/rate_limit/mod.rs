@@ -0,0 +1,310 @@
+mod memory_store;
+mod postgres_store;
+
+pub use memory_store::*;
+pub use postgres_store::*;
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use anyhow::Error;
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, RawPathParams, Request},
+    http::{request::Parts, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use sqlx::PgPool;
+use tower::{Layer, Service};
+
+use crate::{
+    config::{Config, RateLimitQuota},
+    core::{
+        extractors::{Authorized, ClientInfo},
+        models::{Claims, ErrorResponse, RateLimitDecision},
+        traits::{JobHandler, RateLimitStore},
+    },
+    ApiContext,
+};
+
+pub fn rate_limit_store(config: &Config, db: &PgPool) -> Arc<dyn RateLimitStore> {
+    match config.rate_limit_store.as_str() {
+        "postgres" => Arc::new(PostgresRateLimitStore::new(db.clone())),
+        _ => Arc::new(MemoryRateLimitStore::default()),
+    }
+}
+
+/// Forgets the buckets that refilled and weren't limited lately.
+pub struct PruneRateLimits;
+
+#[async_trait]
+impl JobHandler for PruneRateLimits {
+    const KIND: &'static str = "rate_limits.prune";
+
+    const MAX_ATTEMPTS: i32 = 1;
+
+    type Payload = ();
+
+    async fn run(&self, ctx: &ApiContext, _: ()) -> Result<(), Error> {
+        ctx.rate_limit_store.prune().await
+    }
+}
+
+/// Builds the decision for a bucket left holding `tokens` after a take.
+pub fn decide(
+    allowed: bool,
+    tokens: f64,
+    capacity: u32,
+    refill_per_second: f64,
+) -> RateLimitDecision {
+    let tokens = tokens.max(0.0);
+    let seconds_until =
+        |target: f64| Duration::from_secs_f64(((target - tokens) / refill_per_second).max(0.0));
+    RateLimitDecision {
+        allowed,
+        limit: capacity,
+        remaining: tokens.floor() as u32,
+        reset_after: seconds_until(capacity as f64),
+        retry_after: if allowed {
+            Duration::ZERO
+        } else {
+            seconds_until(1.0)
+        },
+    }
+}
+
+/// What a bucket is keyed by.
+#[derive(Clone, Copy)]
+pub enum RateLimitKey {
+    /// The client address, see `ClientInfo`.
+    Ip,
+    /// The authenticated `claims.sub`, or the client address for anonymous requests.
+    User,
+    /// The `profile_link` or link `slug` path parameter. Requests without one
+    /// are not limited.
+    ProfileLink,
+}
+
+impl RateLimitKey {
+    async fn resolve(&self, parts: &mut Parts) -> Option<String> {
+        match self {
+            Self::Ip => ClientInfo::from_request_parts(parts, &())
+                .await
+                .ok()
+                .map(|client| format!("ip:{}", client.ip)),
+            Self::User => match Authorized::<Claims>::from_request_parts(parts, &()).await {
+                Ok(Authorized(claims)) => Some(format!("user:{}", claims.sub)),
+                Err(_) => Box::pin(Self::Ip.resolve(parts)).await,
+            },
+            Self::ProfileLink => RawPathParams::from_request_parts(parts, &())
+                .await
+                .ok()?
+                .iter()
+                .find(|(name, _)| matches!(*name, "profile_link" | "slug"))
+                .map(|(name, value)| match name {
+                    "slug" => format!("link:l/{value}"),
+                    _ => format!("link:{value}"),
+                }),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RateLimitRule {
+    name: &'static str,
+    key: RateLimitKey,
+    quota: RateLimitQuota,
+}
+
+impl RateLimitRule {
+    pub fn new(name: &'static str, key: RateLimitKey, quota: RateLimitQuota) -> Self {
+        RateLimitRule { name, key, quota }
+    }
+}
+
+/// Token-bucket rate limiting for a group of routes. Every rule takes a token
+/// from its own bucket, the first empty bucket rejects the request with
+/// `429 Too Many Requests`, and allowed responses report the tightest bucket in
+/// `RateLimit-*` headers.
+#[derive(Clone)]
+pub struct RateLimitLayer {
+    store: Arc<dyn RateLimitStore>,
+    rules: Arc<Vec<RateLimitRule>>,
+}
+
+impl RateLimitLayer {
+    pub fn new(store: Arc<dyn RateLimitStore>, rules: Vec<RateLimitRule>) -> Self {
+        RateLimitLayer {
+            store,
+            rules: Arc::new(rules),
+        }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService {
+            inner,
+            store: self.store.clone(),
+            rules: self.rules.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RateLimitService<S> {
+    inner: S,
+    store: Arc<dyn RateLimitStore>,
+    rules: Arc<Vec<RateLimitRule>>,
+}
+
+impl<S> Service<Request> for RateLimitService<S>
+where
+    S: Service<Request, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        // the clone may not be ready, so keep the one `poll_ready` was called on
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let store = self.store.clone();
+        let rules = self.rules.clone();
+
+        Box::pin(async move {
+            let (mut parts, body) = request.into_parts();
+            let mut tightest: Option<RateLimitDecision> = None;
+
+            for rule in rules.iter() {
+                let Some(key) = rule.key.resolve(&mut parts).await else {
+                    continue;
+                };
+
+                let bucket = format!("{}:{key}", rule.name);
+                let refill_per_second =
+                    rule.quota.capacity as f64 / rule.quota.period_seconds as f64;
+                let decision = match store
+                    .acquire(&bucket, rule.quota.capacity, refill_per_second)
+                    .await
+                {
+                    Ok(decision) => decision,
+                    Err(e) => {
+                        // fail open, an unavailable store should not take the API down
+                        tracing::warn!("Rate limit store failed for {bucket}: {e}");
+                        continue;
+                    }
+                };
+
+                if !decision.allowed {
+                    return Ok(too_many_requests(&decision));
+                }
+                if tightest
+                    .as_ref()
+                    .is_none_or(|tightest| decision.remaining < tightest.remaining)
+                {
+                    tightest = Some(decision);
+                }
+            }
+
+            let mut response = inner.call(Request::from_parts(parts, body)).await?;
+            if let Some(decision) = tightest {
+                rate_limit_headers(response.headers_mut(), &decision);
+            }
+            Ok(response)
+        })
+    }
+}
+
+fn rate_limit_headers(headers: &mut HeaderMap, decision: &RateLimitDecision) {
+    headers.insert("ratelimit-limit", HeaderValue::from(decision.limit));
+    headers.insert("ratelimit-remaining", HeaderValue::from(decision.remaining));
+    headers.insert(
+        "ratelimit-reset",
+        HeaderValue::from(decision.reset_after.as_secs_f64().ceil() as u64),
+    );
+}
+
+/// `429 Too Many Requests` with `Retry-After` and `RateLimit-*` headers.
+pub fn too_many_requests(decision: &RateLimitDecision) -> Response {
+    let status = StatusCode::TOO_MANY_REQUESTS;
+    let retry_after = decision.retry_after.as_secs_f64().ceil().max(1.0) as u64;
+    let payload = ErrorResponse::new(
+        vec![format!("Too many requests, retry in {retry_after} seconds")],
+        status,
+    );
+
+    let mut response = (status, Json(payload)).into_response();
+    rate_limit_headers(response.headers_mut(), decision);
+    response
+        .headers_mut()
+        .insert("retry-after", HeaderValue::from(retry_after));
+    response
+}
+
+/// The rate limits applied to each route group, built from `Config`.
+pub struct RateLimits {
+    pub store: Arc<dyn RateLimitStore>,
+    /// Signup and login, per client address.
+    pub auth: RateLimitLayer,
+    /// Public profile pages and avatars, per client address.
+    pub public: RateLimitLayer,
+    /// Message submission only, per client address and per target profile
+    /// link, on top of `public`.
+    pub submit: RateLimitLayer,
+    /// Authenticated routes, per user.
+    pub api: RateLimitLayer,
+}
+
+impl RateLimits {
+    pub fn new(config: &Config, store: Arc<dyn RateLimitStore>) -> Self {
+        RateLimits {
+            auth: RateLimitLayer::new(
+                store.clone(),
+                vec![RateLimitRule::new(
+                    "auth",
+                    RateLimitKey::Ip,
+                    config.rate_limit_auth,
+                )],
+            ),
+            public: RateLimitLayer::new(
+                store.clone(),
+                vec![RateLimitRule::new(
+                    "public",
+                    RateLimitKey::Ip,
+                    config.rate_limit_public,
+                )],
+            ),
+            submit: RateLimitLayer::new(
+                store.clone(),
+                vec![
+                    RateLimitRule::new("submit", RateLimitKey::Ip, config.rate_limit_submit),
+                    RateLimitRule::new("inbox", RateLimitKey::ProfileLink, config.rate_limit_inbox),
+                ],
+            ),
+            api: RateLimitLayer::new(
+                store.clone(),
+                vec![RateLimitRule::new(
+                    "api",
+                    RateLimitKey::User,
+                    config.rate_limit_api,
+                )],
+            ),
+            store,
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use std::time::Duration;
+
+use anyhow::Error;
+use axum::async_trait;
+use sqlx::PgPool;
+
+use crate::core::{models::RateLimitDecision, rate_limit::decide, traits::RateLimitStore};
+
+/// Buckets kept in the `rate_limit_buckets` table so that every API instance
+/// draws from the same buckets. Refill and take happen in a single statement.
+pub struct PostgresRateLimitStore {
+    db: PgPool,
+}
+
+impl PostgresRateLimitStore {
+    pub fn new(db: PgPool) -> Self {
+        PostgresRateLimitStore { db }
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for PostgresRateLimitStore {
+    async fn acquire(
+        &self,
+        key: &str,
+        capacity: u32,
+        refill_per_second: f64,
+    ) -> Result<RateLimitDecision, Error> {
+        let bucket = sqlx::query!(
+            r#"insert into "rate_limit_buckets" as b (key, tokens, allowed, updated_at)
+            values ($1, $2 - 1, true, now())
+            on conflict (key) do update set
+                tokens = least($2, b.tokens + extract(epoch from now() - b.updated_at)::float8 * $3)
+                    - case when least($2, b.tokens + extract(epoch from now() - b.updated_at)::float8 * $3) >= 1
+                        then 1 else 0 end,
+                allowed = least($2, b.tokens + extract(epoch from now() - b.updated_at)::float8 * $3) >= 1,
+                limited_at = case when least($2, b.tokens + extract(epoch from now() - b.updated_at)::float8 * $3) >= 1
+                    then b.limited_at else now() end,
+                updated_at = now()
+            returning tokens, allowed"#,
+            key,
+            capacity as f64,
+            refill_per_second
+        )
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(decide(
+            bucket.allowed,
+            bucket.tokens,
+            capacity,
+            refill_per_second,
+        ))
+    }
+
+    async fn recently_limited(&self, key: &str, within: Duration) -> Result<bool, Error> {
+        let limited = sqlx::query_scalar!(
+            r#"select exists(select 1 from "rate_limit_buckets"
+                where key = $1 and limited_at > now() - make_interval(secs => $2)) as "limited!""#,
+            key,
+            within.as_secs_f64()
+        )
+        .fetch_one(&self.db)
+        .await?;
+        Ok(limited)
+    }
+
+    async fn prune(&self) -> Result<(), Error> {
+        sqlx::query!(
+            r#"delete from "rate_limit_buckets" where updated_at < now() - interval '1 day'"#
+        )
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,16 @@
+mod local_store;
+mod s3_store;
+
+pub use local_store::*;
+pub use s3_store::*;
+
+use std::sync::Arc;
+
+use crate::{config::Config, core::traits::BlobStore};
+
+pub fn blob_store(config: &Config) -> Arc<dyn BlobStore> {
+    match config.storage_backend.as_str() {
+        "s3" => Arc::new(S3BlobStore::new(config)),
+        _ => Arc::new(LocalBlobStore::new(&config.upload_dir)),
+    }
+}
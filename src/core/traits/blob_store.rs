@@ -0,0 +1,17 @@
+use anyhow::Error;
+use axum::async_trait;
+
+use crate::core::models::Blob;
+
+/// Storage for binary objects such as uploaded images, addressed by a
+/// `/`-separated key.
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    async fn put(&self, key: &str, bytes: Vec<u8>, content_type: &str) -> Result<(), Error>;
+
+    /// Returns `None` when no object exists under `key`.
+    async fn get(&self, key: &str) -> Result<Option<Blob>, Error>;
+
+    /// Deleting a missing key is not an error.
+    async fn delete(&self, key: &str) -> Result<(), Error>;
+}
@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+use anyhow::Error;
+use axum::async_trait;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::ApiContext;
+
+/// Work done out of the request path by the job runner. Jobs are stored under
+/// `KIND` with their payload as JSON, and a failed run is retried with backoff
+/// until the job has been tried `MAX_ATTEMPTS` times.
+#[async_trait]
+pub trait JobHandler: Send + Sync + 'static {
+    const KIND: &'static str;
+
+    const MAX_ATTEMPTS: i32 = 5;
+
+    /// A run taking longer fails. Must stay well under the runner's lease.
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    type Payload: Serialize + DeserializeOwned + Send;
+
+    async fn run(&self, ctx: &ApiContext, payload: Self::Payload) -> Result<(), Error>;
+}
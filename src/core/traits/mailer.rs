@@ -0,0 +1,10 @@
+use anyhow::Error;
+use axum::async_trait;
+
+use crate::core::models::Email;
+
+/// Sends emails from the configured sender address.
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, email: Email) -> Result<(), Error>;
+}
@@ -0,0 +1,13 @@
+use anyhow::Error;
+use axum::async_trait;
+
+use crate::core::models::IncomingMessage;
+
+/// Scores incoming messages between 0 (clean) and 1. Every classifier runs at
+/// submission time and its score is stored on the message under `name`.
+#[async_trait]
+pub trait MessageClassifier: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn score(&self, message: &IncomingMessage<'_>) -> Result<f32, Error>;
+}
@@ -1,3 +1,15 @@
+mod blob_store;
+mod job_handler;
+mod mailer;
+mod message_classifier;
+mod push_sender;
+mod rate_limit_store;
 mod tve;
 
+pub use blob_store::*;
+pub use job_handler::*;
+pub use mailer::*;
+pub use message_classifier::*;
+pub use push_sender::*;
+pub use rate_limit_store::*;
 pub use tve::*;
@@ -0,0 +1,12 @@
+use anyhow::Error;
+use axum::async_trait;
+
+use crate::core::models::PushMessage;
+
+/// Hands push messages to the push service of their subscription.
+#[async_trait]
+pub trait PushSender: Send + Sync {
+    /// Returns the status code the push service responded with, a 404 or 410
+    /// meaning the subscription is gone for good.
+    async fn send(&self, message: PushMessage) -> Result<u16, Error>;
+}
@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+use anyhow::Error;
+use axum::async_trait;
+
+use crate::core::models::RateLimitDecision;
+
+/// Token buckets shared by every request handled through a `RateLimitLayer`.
+/// A bucket holds up to `capacity` tokens and refills at `refill_per_second`.
+#[async_trait]
+pub trait RateLimitStore: Send + Sync {
+    async fn acquire(
+        &self,
+        key: &str,
+        capacity: u32,
+        refill_per_second: f64,
+    ) -> Result<RateLimitDecision, Error>;
+
+    /// Whether a request was refused from the bucket within the last `within`.
+    async fn recently_limited(&self, key: &str, within: Duration) -> Result<bool, Error>;
+
+    /// Forgets buckets that have been idle long enough to be full again.
+    async fn prune(&self) -> Result<(), Error>;
+}
@@ -0,0 +1,7 @@
+/// Seconds to wait after the `attempts`th failure: `first` doubled after every
+/// further failure up to `max`, give or take a tenth so that retries of things
+/// that failed together spread out.
+pub fn backoff_seconds(attempts: i32, first: f64, max: f64) -> f64 {
+    let seconds = (first * 2f64.powi(attempts.max(1) - 1)).min(max);
+    seconds * rand::random::<f64>().mul_add(0.2, 0.9)
+}
@@ -0,0 +1,174 @@
+use aes_gcm::{aead::Aead, Aes256Gcm, KeyInit, Nonce};
+use anyhow::{anyhow, Error};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hkdf::Hkdf;
+use rand::rngs::OsRng;
+use sha2::Sha256;
+use x25519_dalek::{EphemeralSecret, PublicKey, StaticSecret};
+
+/// How end-to-end encrypted message bodies are made, returned with a
+/// recipient's public key so clients can tell what they have to implement.
+pub const E2E_ALGORITHM: &str = "x25519-hkdf-sha256-aes256gcm";
+
+const ENVELOPE_VERSION: u8 = 1;
+
+/// The version byte and the sender's ephemeral public key.
+const HEADER_LENGTH: usize = 1 + 32;
+
+const TAG_LENGTH: usize = 16;
+
+/// Room for the longest message, 1000 characters of up to 4 bytes each.
+pub const MAX_E2E_PLAINTEXT: usize = 4000;
+
+/// Characters of the base64url envelope holding `MAX_E2E_PLAINTEXT` bytes.
+pub const MAX_E2E_ENVELOPE: usize =
+    ((HEADER_LENGTH + MAX_E2E_PLAINTEXT + TAG_LENGTH) * 4).div_ceil(3);
+
+/// A new X25519 key pair as base64url: the secret key, which never leaves the
+/// recipient's device, and the public key they register.
+pub fn generate_e2e_keys() -> (String, String) {
+    let secret = StaticSecret::random_from_rng(OsRng);
+    let public = PublicKey::from(&secret);
+    (
+        URL_SAFE_NO_PAD.encode(secret.as_bytes()),
+        URL_SAFE_NO_PAD.encode(public.as_bytes()),
+    )
+}
+
+/// Decodes a base64url X25519 key, padded or not.
+pub fn decode_e2e_key(value: &str) -> Result<[u8; 32], Error> {
+    URL_SAFE_NO_PAD
+        .decode(value.trim_end_matches('='))?
+        .try_into()
+        .map_err(|_| anyhow!("An X25519 key is 32 bytes"))
+}
+
+/// The AES-256-GCM key and nonce of one message. Every message has its own
+/// ephemeral key, so deriving the nonce along with the key is safe.
+fn message_key(shared: &[u8], ephemeral: &[u8; 32], recipient: &[u8; 32]) -> ([u8; 32], [u8; 12]) {
+    let mut salt = ephemeral.to_vec();
+    salt.extend_from_slice(recipient);
+    let mut output = [0; 44];
+    Hkdf::<Sha256>::new(Some(&salt), shared)
+        .expand(b"anonymous-message e2e v1", &mut output)
+        .expect("HKDF output is short enough");
+
+    let mut key = [0; 32];
+    let mut nonce = [0; 12];
+    key.copy_from_slice(&output[..32]);
+    nonce.copy_from_slice(&output[32..]);
+    (key, nonce)
+}
+
+/// Encrypts a message body for the holder of `recipient_key`, as senders do
+/// before submitting it. The envelope is the base64url encoding of a version
+/// byte, the ephemeral public key and the AES-256-GCM ciphertext.
+pub fn encrypt_e2e_message(recipient_key: &[u8; 32], plaintext: &str) -> Result<String, Error> {
+    if plaintext.len() > MAX_E2E_PLAINTEXT {
+        return Err(anyhow!("Message over {MAX_E2E_PLAINTEXT} bytes"));
+    }
+    let recipient = PublicKey::from(*recipient_key);
+    let ephemeral = EphemeralSecret::random_from_rng(OsRng);
+    let ephemeral_key = PublicKey::from(&ephemeral);
+    let shared = ephemeral.diffie_hellman(&recipient);
+    if !shared.was_contributory() {
+        return Err(anyhow!("Invalid recipient key"));
+    }
+
+    let (key, nonce) = message_key(shared.as_bytes(), ephemeral_key.as_bytes(), recipient_key);
+    let ciphertext = Aes256Gcm::new_from_slice(&key)?
+        .encrypt(Nonce::from_slice(&nonce), plaintext.as_bytes())
+        .map_err(|_| anyhow!("Unable to encrypt the message"))?;
+
+    let mut envelope = vec![ENVELOPE_VERSION];
+    envelope.extend_from_slice(ephemeral_key.as_bytes());
+    envelope.extend_from_slice(&ciphertext);
+    Ok(URL_SAFE_NO_PAD.encode(envelope))
+}
+
+/// Decodes an envelope into the ephemeral public key and the ciphertext.
+fn open_envelope(envelope: &str) -> Result<([u8; 32], Vec<u8>), Error> {
+    let bytes = URL_SAFE_NO_PAD
+        .decode(envelope.trim())
+        .map_err(|_| anyhow!("An encrypted message is base64url encoded"))?;
+    if bytes.len() <= HEADER_LENGTH + TAG_LENGTH || bytes[0] != ENVELOPE_VERSION {
+        return Err(anyhow!(
+            "Not a version {ENVELOPE_VERSION} encrypted message"
+        ));
+    }
+    let mut ephemeral = [0; 32];
+    ephemeral.copy_from_slice(&bytes[1..HEADER_LENGTH]);
+    Ok((ephemeral, bytes[HEADER_LENGTH..].to_vec()))
+}
+
+/// Bytes of the message sealed in an envelope. This is all the server can
+/// check: anything shaped like an envelope is accepted, plaintext never is.
+pub fn e2e_plaintext_length(envelope: &str) -> Result<usize, Error> {
+    let (_, ciphertext) = open_envelope(envelope)?;
+    Ok(ciphertext.len() - TAG_LENGTH)
+}
+
+/// Decrypts an envelope with the recipient's secret key, as their client does
+/// when showing the message.
+pub fn decrypt_e2e_message(secret_key: &[u8; 32], envelope: &str) -> Result<String, Error> {
+    let (ephemeral_key, ciphertext) = open_envelope(envelope)?;
+    let secret = StaticSecret::from(*secret_key);
+    let shared = secret.diffie_hellman(&PublicKey::from(ephemeral_key));
+    if !shared.was_contributory() {
+        return Err(anyhow!("Invalid ephemeral key"));
+    }
+
+    let recipient_key = PublicKey::from(&secret);
+    let (key, nonce) = message_key(shared.as_bytes(), &ephemeral_key, recipient_key.as_bytes());
+    let plaintext = Aes256Gcm::new_from_slice(&key)?
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+        .map_err(|_| anyhow!("Unable to decrypt the message, wrong key or tampered envelope"))?;
+    Ok(String::from_utf8(plaintext)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decrypts_what_it_encrypts() {
+        let (secret_key, public_key) = generate_e2e_keys();
+        let secret_key = decode_e2e_key(&secret_key).unwrap();
+        let public_key = decode_e2e_key(&public_key).unwrap();
+
+        let envelope =
+            encrypt_e2e_message(&public_key, "Hello, is it me you're looking for?").unwrap();
+        assert_eq!(e2e_plaintext_length(&envelope).unwrap(), 35);
+        assert_eq!(
+            decrypt_e2e_message(&secret_key, &envelope).unwrap(),
+            "Hello, is it me you're looking for?"
+        );
+
+        // a fresh ephemeral key every time
+        assert_ne!(
+            envelope,
+            encrypt_e2e_message(&public_key, "Hello, is it me you're looking for?").unwrap()
+        );
+    }
+
+    #[test]
+    fn refuses_other_keys_and_tampered_envelopes() {
+        let (secret_key, public_key) = generate_e2e_keys();
+        let (other_secret_key, _) = generate_e2e_keys();
+        let secret_key = decode_e2e_key(&secret_key).unwrap();
+        let envelope = encrypt_e2e_message(&decode_e2e_key(&public_key).unwrap(), "Hello").unwrap();
+        let other_secret_key = decode_e2e_key(&other_secret_key).unwrap();
+        assert!(decrypt_e2e_message(&other_secret_key, &envelope).is_err());
+
+        let mut tampered = URL_SAFE_NO_PAD.decode(&envelope).unwrap();
+        *tampered.last_mut().unwrap() ^= 1;
+        let tampered = URL_SAFE_NO_PAD.encode(tampered);
+        assert!(decrypt_e2e_message(&secret_key, &tampered).is_err());
+    }
+
+    #[test]
+    fn refuses_plaintext_as_an_envelope() {
+        assert!(e2e_plaintext_length("Hello there, this is a plain message").is_err());
+        assert!(e2e_plaintext_length(&URL_SAFE_NO_PAD.encode([1; 40])).is_err());
+    }
+}
@@ -0,0 +1,28 @@
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::core::extractors::ClientInfo;
+
+/// Keyed digest of a sender for one recipient. The recipient id is part of the
+/// input, so the same sender yields unrelated digests for different recipients,
+/// and the daily salt makes digests from different days unrelated as well.
+pub fn sender_fingerprint(salt: &[u8], recipient_id: Uuid, client: &ClientInfo) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(salt).expect("HMAC accepts keys of any length");
+    mac.update(recipient_id.as_bytes());
+    mac.update(client.ip.to_string().as_bytes());
+    mac.update(b"\n");
+    mac.update(client.user_agent.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// The key platform digests are sealed with while they sit on a message,
+/// derived from `JWT_SECRET` so it never lives in the database next to them.
+pub fn report_key(jwt_secret: &str) -> [u8; 32] {
+    let mut key = [0; 32];
+    Hkdf::<Sha256>::new(None, jwt_secret.as_bytes())
+        .expand(b"anonymous-message report v1", &mut key)
+        .expect("HKDF output is short enough");
+    key
+}
@@ -0,0 +1,52 @@
+use std::io::Cursor;
+
+use image::{imageops::FilterType, DynamicImage, ImageDecoder, ImageFormat, ImageReader, Limits};
+
+use crate::core::models::ApiError;
+
+/// Square edge lengths, in pixels, that every avatar is rendered at.
+pub const AVATAR_SIZES: [u32; 3] = [64, 256, 512];
+
+/// Sniffs the image format from its magic bytes, decodes it and renders one PNG
+/// per entry of `AVATAR_SIZES`. Re-encoding from raw pixels drops EXIF and any
+/// other embedded metadata, after the EXIF orientation has been applied.
+pub fn render_avatar(bytes: &[u8]) -> Result<Vec<(u32, Vec<u8>)>, ApiError> {
+    let format = image::guess_format(bytes)
+        .ok()
+        .filter(|format| {
+            matches!(
+                format,
+                ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::Gif | ImageFormat::WebP
+            )
+        })
+        .ok_or_else(|| ApiError::BadRequest {
+            errors: vec!["avatar: must be a PNG, JPEG, GIF or WebP image".to_string()],
+        })?;
+
+    let invalid_image = |e: image::ImageError| ApiError::BadRequest {
+        errors: vec![format!("avatar: {e}")],
+    };
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(4096);
+    limits.max_image_height = Some(4096);
+    let mut reader = ImageReader::with_format(Cursor::new(bytes), format);
+    reader.limits(limits);
+
+    let mut decoder = reader.into_decoder().map_err(invalid_image)?;
+    let orientation = decoder.orientation().map_err(invalid_image)?;
+    let mut avatar = DynamicImage::from_decoder(decoder).map_err(invalid_image)?;
+    avatar.apply_orientation(orientation);
+
+    AVATAR_SIZES
+        .iter()
+        .map(|&size| {
+            let mut png = Cursor::new(Vec::new());
+            avatar
+                .resize_to_fill(size, size, FilterType::Lanczos3)
+                .write_to(&mut png, ImageFormat::Png)
+                .map_err(|e| ApiError::InternalServer(e.to_string()))?;
+            Ok((size, png.into_inner()))
+        })
+        .collect()
+}
@@ -1,3 +1,27 @@
 mod auth_util;
+mod backoff_util;
+mod e2e_util;
+mod fingerprint_util;
+mod image_util;
+mod net_util;
+mod pow_util;
+mod qr_util;
+mod seal_util;
+mod serde_util;
+mod template_util;
+mod text_util;
+mod web_push_util;
 
 pub use auth_util::*;
+pub use backoff_util::*;
+pub use e2e_util::*;
+pub use fingerprint_util::*;
+pub use image_util::*;
+pub use net_util::*;
+pub use pow_util::*;
+pub use qr_util::*;
+pub use seal_util::*;
+pub use serde_util::*;
+pub use template_util::*;
+pub use text_util::*;
+pub use web_push_util::*;
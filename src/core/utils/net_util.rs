@@ -0,0 +1,65 @@
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+
+use reqwest::{redirect, Client, Url};
+
+/// Whether `ip` is reachable on the public internet, rather than this machine,
+/// a private network or a reserved range.
+pub fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                // shared address space used by carrier-grade NAT
+                || (a == 100 && b & 0xc0 == 64))
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_address(ip.into()),
+            None => {
+                let first = ip.segments()[0];
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || first & 0xfe00 == 0xfc00
+                    || first & 0xffc0 == 0xfe80)
+            }
+        },
+    }
+}
+
+/// A client for requests to a URL a user gave. It resolves the host and
+/// refuses private addresses unless allowed, then stays pinned to the address
+/// checked, so a name cannot resolve somewhere else by the time the request is
+/// made. Redirects are not followed.
+pub async fn pinned_client(
+    url: &Url,
+    allow_private: bool,
+    timeout: Duration,
+) -> Result<Client, String> {
+    let host = url.host_str().ok_or("URL has no host")?;
+    let port = url.port_or_known_default().unwrap_or(443);
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let addresses: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| format!("Unable to resolve {host}: {e}"))?
+        .collect();
+    let address = addresses
+        .iter()
+        .find(|address| allow_private || is_public_address(address.ip()))
+        .ok_or_else(|| format!("{host} does not resolve to a public address"))?;
+
+    Client::builder()
+        .timeout(timeout)
+        .redirect(redirect::Policy::none())
+        .resolve(host, *address)
+        .build()
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,113 @@
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+/// The key challenges are signed with, derived from `JWT_SECRET` under its own
+/// label so a challenge signature can never pass for a token's, or the other
+/// way round.
+pub fn challenge_key(jwt_secret: &str) -> [u8; 32] {
+    let mut key = [0; 32];
+    Hkdf::<Sha256>::new(None, jwt_secret.as_bytes())
+        .expand(b"anonymous-message pow v1", &mut key)
+        .expect("HKDF output is short enough");
+    key
+}
+
+/// Signs the parameters of a proof-of-work challenge. The sender fingerprint
+/// is signed but never sent, so a challenge only verifies for the client and
+/// recipient it was issued to.
+pub fn challenge_mac(
+    key: &[u8; 32],
+    profile_link: &str,
+    expires: i64,
+    difficulty: u32,
+    nonce: &[u8],
+    fingerprint: &[u8],
+) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(b"pow\n");
+    mac.update(profile_link.as_bytes());
+    mac.update(format!("\n{expires}\n{difficulty}\n").as_bytes());
+    mac.update(nonce);
+    mac.update(fingerprint);
+    mac
+}
+
+/// Whether `sha256("{challenge}:{counter}")` starts with `difficulty` zero bits.
+pub fn solves(challenge: &str, counter: &str, difficulty: u32) -> bool {
+    let digest = Sha256::new()
+        .chain_update(challenge)
+        .chain_update(":")
+        .chain_update(counter)
+        .finalize();
+
+    let mut zeros = 0;
+    for byte in digest {
+        zeros += byte.leading_zeros();
+        if byte != 0 {
+            break;
+        }
+    }
+    zeros >= difficulty
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solve(challenge: &str, difficulty: u32) -> String {
+        (0u64..)
+            .map(|counter| counter.to_string())
+            .find(|counter| solves(challenge, counter, difficulty))
+            .unwrap()
+    }
+
+    #[test]
+    fn counts_leading_zero_bits() {
+        assert!(solves("challenge", "anything", 0));
+
+        let counter = solve("challenge", 10);
+        let digest = Sha256::digest(format!("challenge:{counter}"));
+        let zero_bytes = digest.iter().take_while(|byte| **byte == 0).count();
+        let zeros = zero_bytes as u32 * 8 + digest[zero_bytes].leading_zeros();
+        assert!(zeros >= 10);
+        assert!(solves("challenge", &counter, zeros));
+        assert!(!solves("challenge", &counter, zeros + 1));
+    }
+
+    #[test]
+    fn signs_every_parameter() {
+        let key = challenge_key("secret");
+        let signature = challenge_mac(&key, "u:alice", 100, 16, b"nonce", b"sender")
+            .finalize()
+            .into_bytes();
+        let verifies = |mac: Hmac<Sha256>| mac.verify_slice(&signature).is_ok();
+
+        assert!(verifies(challenge_mac(
+            &key, "u:alice", 100, 16, b"nonce", b"sender"
+        )));
+        assert!(!verifies(challenge_mac(
+            &challenge_key("other secret"),
+            "u:alice",
+            100,
+            16,
+            b"nonce",
+            b"sender"
+        )));
+        assert!(!verifies(challenge_mac(
+            &key, "u:bob", 100, 16, b"nonce", b"sender"
+        )));
+        assert!(!verifies(challenge_mac(
+            &key, "u:alice", 200, 16, b"nonce", b"sender"
+        )));
+        assert!(!verifies(challenge_mac(
+            &key, "u:alice", 100, 8, b"nonce", b"sender"
+        )));
+        assert!(!verifies(challenge_mac(
+            &key, "u:alice", 100, 16, b"other", b"sender"
+        )));
+        assert!(!verifies(challenge_mac(
+            &key, "u:alice", 100, 16, b"nonce", b"other"
+        )));
+    }
+}
@@ -0,0 +1,39 @@
+use std::io::Cursor;
+
+use image::{ImageFormat, Luma};
+use qrcode::{render::svg, EcLevel, QrCode};
+
+use crate::core::models::ApiError;
+
+fn qr_code(data: &str, level: EcLevel) -> Result<QrCode, ApiError> {
+    QrCode::with_error_correction_level(data, level).map_err(|e| ApiError::BadRequest {
+        errors: vec![format!("qr: {e}")],
+    })
+}
+
+/// Renders `data` as a QR code with `quiet_zone` light modules around it, at
+/// no less than `size` pixels a side.
+pub fn qr_svg(data: &str, level: EcLevel, size: u32, quiet_zone: u32) -> Result<String, ApiError> {
+    let code = qr_code(data, level)?;
+    let colors = code.to_colors();
+    Ok(
+        qrcode::render::Renderer::<svg::Color>::new(&colors, code.width(), quiet_zone)
+            .min_dimensions(size, size)
+            .build(),
+    )
+}
+
+/// Same as `qr_svg`, as a grayscale PNG.
+pub fn qr_png(data: &str, level: EcLevel, size: u32, quiet_zone: u32) -> Result<Vec<u8>, ApiError> {
+    let code = qr_code(data, level)?;
+    let colors = code.to_colors();
+    let image = qrcode::render::Renderer::<Luma<u8>>::new(&colors, code.width(), quiet_zone)
+        .min_dimensions(size, size)
+        .build();
+
+    let mut png = Cursor::new(Vec::new());
+    image
+        .write_to(&mut png, ImageFormat::Png)
+        .map_err(|e| ApiError::InternalServer(e.to_string()))?;
+    Ok(png.into_inner())
+}
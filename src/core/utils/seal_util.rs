@@ -0,0 +1,53 @@
+use aes_gcm::{
+    aead::{Aead, Payload},
+    Aes256Gcm, KeyInit, Nonce,
+};
+use anyhow::{anyhow, Error};
+use sha2::{Digest, Sha256};
+
+const SEAL_VERSION: u8 = 1;
+
+const NONCE_LENGTH: usize = 12;
+
+/// Encrypts with AES-256-GCM under a random nonce, binding `context` so the
+/// result cannot be passed off as another's. Holds a version byte, the nonce
+/// and the ciphertext.
+pub fn seal(key: &[u8; 32], context: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, Error> {
+    let nonce: [u8; NONCE_LENGTH] = rand::random();
+    let ciphertext = Aes256Gcm::new_from_slice(key)?
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad: context,
+            },
+        )
+        .map_err(|_| anyhow!("Unable to seal"))?;
+
+    let mut sealed = vec![SEAL_VERSION];
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+/// Reverses `seal`, given the same key and context.
+pub fn unseal(key: &[u8; 32], context: &[u8], sealed: &[u8]) -> Result<Vec<u8>, Error> {
+    if sealed.len() <= 1 + NONCE_LENGTH || sealed[0] != SEAL_VERSION {
+        return Err(anyhow!("Not a version {SEAL_VERSION} sealed value"));
+    }
+    let (nonce, ciphertext) = sealed[1..].split_at(NONCE_LENGTH);
+    Aes256Gcm::new_from_slice(key)?
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: context,
+            },
+        )
+        .map_err(|_| anyhow!("Unable to unseal, wrong key or tampered value"))
+}
+
+/// Names a key without revealing it: the start of its SHA-256 digest in hex.
+pub fn key_id(key: &[u8; 32]) -> String {
+    hex::encode(&Sha256::digest(key)[..4])
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Deserializer};
+
+/// Tells an explicit `null` apart from an omitted field in partial updates:
+/// used with `#[serde(default)]`, an omitted field stays `None` and `null`
+/// becomes `Some(None)`.
+pub fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
@@ -0,0 +1,17 @@
+use std::path::Path;
+
+use minijinja::{path_loader, Environment};
+use serde::Serialize;
+
+/// Renders `name`, a path under `dir`, with `context`. Templates are read on
+/// every call, so copy can change without a rebuild or restart. Names ending
+/// in `.html` are HTML-escaped.
+pub fn render_template(
+    dir: impl AsRef<Path>,
+    name: &str,
+    context: impl Serialize,
+) -> Result<String, minijinja::Error> {
+    let mut env = Environment::new();
+    env.set_loader(path_loader(dir.as_ref()));
+    env.get_template(name)?.render(context)
+}
@@ -0,0 +1,11 @@
+/// The first `max_chars` characters of `text`, with an ellipsis when cut.
+pub fn excerpt(text: &str, max_chars: usize) -> String {
+    let mut chars = text.chars();
+    let excerpt: String = chars.by_ref().take(max_chars).collect();
+    let excerpt = excerpt.trim_end();
+    if chars.next().is_some() {
+        format!("{excerpt}…")
+    } else {
+        excerpt.to_string()
+    }
+}
@@ -0,0 +1,170 @@
+use aes_gcm::{aead::Aead, Aes128Gcm, KeyInit, Nonce};
+use anyhow::{anyhow, Error};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::Utc;
+use hkdf::Hkdf;
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use p256::{
+    ecdh::diffie_hellman, elliptic_curve::sec1::ToEncodedPoint, pkcs8::EncodePrivateKey, PublicKey,
+    SecretKey,
+};
+use rand::rngs::OsRng;
+use reqwest::Url;
+use serde::Serialize;
+use sha2::Sha256;
+
+/// Record size announced in the `aes128gcm` header. A push message is a single
+/// record, and push services take at most 4096 bytes.
+const RECORD_SIZE: u32 = 4096;
+
+/// Plaintext bytes a push message can carry: 4096 less the 86 byte header,
+/// the padding delimiter and the 16 byte tag.
+pub const MAX_PUSH_PAYLOAD: usize = 3993;
+
+/// How long a VAPID token is valid, push services reject more than a day.
+const VAPID_TOKEN_SECONDS: i64 = 12 * 3600;
+
+/// Decodes the base64url keys of a push subscription, padded or not.
+pub fn decode_base64_url(value: &str) -> Result<Vec<u8>, Error> {
+    Ok(URL_SAFE_NO_PAD.decode(value.trim_end_matches('='))?)
+}
+
+/// A new VAPID key pair: the private scalar and the uncompressed public point,
+/// which clients pass to `pushManager.subscribe` as `applicationServerKey`.
+pub fn generate_vapid_keys() -> (Vec<u8>, Vec<u8>) {
+    let secret = SecretKey::random(&mut OsRng);
+    let public = secret.public_key().to_encoded_point(false);
+    (secret.to_bytes().to_vec(), public.as_bytes().to_vec())
+}
+
+#[derive(Serialize)]
+struct VapidClaims<'a> {
+    aud: String,
+    exp: i64,
+    sub: &'a str,
+}
+
+/// The `Authorization` header identifying this server to the push service of
+/// `endpoint` (RFC 8292): a short-lived ES256 token and the public key.
+pub fn vapid_authorization(
+    private_key: &[u8],
+    public_key: &[u8],
+    endpoint: &Url,
+    subject: &str,
+) -> Result<String, Error> {
+    let secret = SecretKey::from_slice(private_key)?;
+    let der = secret.to_pkcs8_der()?;
+    let claims = VapidClaims {
+        aud: endpoint.origin().ascii_serialization(),
+        exp: Utc::now().timestamp() + VAPID_TOKEN_SECONDS,
+        sub: subject,
+    };
+    let token = encode(
+        &Header::new(Algorithm::ES256),
+        &claims,
+        &EncodingKey::from_ec_der(der.as_bytes()),
+    )?;
+    Ok(format!(
+        "vapid t={token}, k={}",
+        URL_SAFE_NO_PAD.encode(public_key)
+    ))
+}
+
+fn hkdf_expand<const N: usize>(hkdf: &Hkdf<Sha256>, info: &[u8]) -> [u8; N] {
+    let mut output = [0; N];
+    hkdf.expand(info, &mut output)
+        .expect("HKDF output is short enough");
+    output
+}
+
+/// Encrypts `plaintext` for one subscription as an `aes128gcm` body (RFC 8291
+/// and RFC 8188), from its `p256dh` public key and `auth` secret. Every call
+/// uses a fresh key pair and salt.
+pub fn encrypt_push_payload(
+    p256dh: &[u8],
+    auth: &[u8],
+    plaintext: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let sender = SecretKey::random(&mut OsRng);
+    encrypt_push_payload_with(p256dh, auth, &sender, rand::random(), plaintext)
+}
+
+fn encrypt_push_payload_with(
+    p256dh: &[u8],
+    auth: &[u8],
+    sender: &SecretKey,
+    salt: [u8; 16],
+    plaintext: &[u8],
+) -> Result<Vec<u8>, Error> {
+    if plaintext.len() > MAX_PUSH_PAYLOAD {
+        return Err(anyhow!("Push payload over {MAX_PUSH_PAYLOAD} bytes"));
+    }
+    let receiver = PublicKey::from_sec1_bytes(p256dh).map_err(|_| anyhow!("Invalid p256dh key"))?;
+    let receiver_key = receiver.to_encoded_point(false);
+    let sender_key = sender.public_key().to_encoded_point(false);
+    let shared = diffie_hellman(sender.to_nonzero_scalar(), receiver.as_affine());
+
+    // combine the shared secret with the subscription's auth secret
+    let mut key_info = b"WebPush: info\0".to_vec();
+    key_info.extend_from_slice(receiver_key.as_bytes());
+    key_info.extend_from_slice(sender_key.as_bytes());
+    let ikm: [u8; 32] = hkdf_expand(
+        &Hkdf::<Sha256>::new(Some(auth), shared.raw_secret_bytes()),
+        &key_info,
+    );
+
+    let hkdf = Hkdf::<Sha256>::new(Some(&salt), &ikm);
+    let key: [u8; 16] = hkdf_expand(&hkdf, b"Content-Encoding: aes128gcm\0");
+    let nonce: [u8; 12] = hkdf_expand(&hkdf, b"Content-Encoding: nonce\0");
+
+    // a single record, ended by the last-record padding delimiter
+    let mut record = plaintext.to_vec();
+    record.push(2);
+    let ciphertext = Aes128Gcm::new_from_slice(&key)?
+        .encrypt(Nonce::from_slice(&nonce), record.as_slice())
+        .map_err(|_| anyhow!("Unable to encrypt the push payload"))?;
+
+    let mut body = salt.to_vec();
+    body.extend_from_slice(&RECORD_SIZE.to_be_bytes());
+    body.push(sender_key.as_bytes().len() as u8);
+    body.extend_from_slice(sender_key.as_bytes());
+    body.extend_from_slice(&ciphertext);
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the example of RFC 8291 section 5
+    #[test]
+    fn encrypts_the_rfc_8291_example() {
+        let decode = |value| decode_base64_url(value).unwrap();
+        let sender =
+            SecretKey::from_slice(&decode("yfWPiYE-n46HLnH0KqZOF1fJJU3MYrct3AELtAQ-oRw")).unwrap();
+        let body = encrypt_push_payload_with(
+            &decode("BCVxsr7N_eNgVRqvHtD0zTZsEc6-VV-JvLexhqUzORcxaOzi6-AYWXvTBHm4bjyPjs7Vd8pZGH6SRpkNtoIAiw4"),
+            &decode("BTBZMqHH6r4Tts7J_aSIgg"),
+            &sender,
+            decode("DGv6ra1nlYgDCS1FRnbzlw").try_into().unwrap(),
+            b"When I grow up, I want to be a watermelon",
+        )
+        .unwrap();
+
+        assert_eq!(
+            URL_SAFE_NO_PAD.encode(body),
+            "DGv6ra1nlYgDCS1FRnbzlwAAEABBBP4z9KsN6nGRTbVYI_c7VJSPQTBtkgcy27mlmlMoZIIgDll6e3vCYLocInmYWAmS6TlzAC8wEqKK6PBru3jl7A_yl95bQpu6cVPTpK4Mqgkf1CXztLVBSt2Ks3oZwbuwXPXLWyouBWLVWGNWQexSgSxsj_Qulcy4a-fN"
+        );
+    }
+
+    #[test]
+    fn refuses_payloads_over_one_record() {
+        let receiver = SecretKey::random(&mut OsRng)
+            .public_key()
+            .to_encoded_point(false);
+        let plaintext = vec![0; MAX_PUSH_PAYLOAD + 1];
+        assert!(encrypt_push_payload(receiver.as_bytes(), &[0; 16], &plaintext).is_err());
+        let body = encrypt_push_payload(receiver.as_bytes(), &[0; 16], &plaintext[1..]).unwrap();
+        assert_eq!(body.len(), RECORD_SIZE as usize);
+    }
+}
@@ -0,0 +1,262 @@
+use std::sync::Arc;
+
+use axum::Router;
+use sqlx::{migrate, postgres::PgPoolOptions, PgPool};
+use tokio::{sync::watch, task::JoinHandle};
+use tower_http::{
+    add_extension::AddExtensionLayer,
+    cors::{Any, CorsLayer},
+};
+use tracing_subscriber::{prelude::__tracing_subscriber_SubscriberExt, util::SubscriberInitExt};
+
+pub mod config;
+pub mod core;
+pub mod modules;
+use crate::{
+    config::Config,
+    core::{
+        classifier::message_classifiers,
+        jobs::{JobRunner, PruneJobs},
+        keyring::{Keyring, RewrapDataKeys, SealStoredBodies},
+        mail::mailer,
+        push::push_sender,
+        rate_limit::{rate_limit_store, PruneRateLimits, RateLimits},
+        storage::blob_store,
+        traits::{BlobStore, Mailer, MessageClassifier, PushSender, RateLimitStore},
+    },
+    modules::{
+        ama::{ama_routes, live_ama_routes, service::AmaEvents},
+        auth::auth_routes,
+        blocklist::{blocklist_routes, service::BlocklistCache},
+        digest::{
+            digest_routes,
+            service::{QueueDigests, SendDigest},
+            unsubscribe_routes,
+        },
+        encryption::encryption_routes,
+        inbox::{
+            inbox_routes,
+            service::{InboxEvents, PruneInboxEvents},
+        },
+        links::{links_routes, service::QrCache},
+        moderation::moderation_routes,
+        profile::{link_message_routes, link_routes, profile_message_routes, profile_routes},
+        push::{push_routes, service::SendPush},
+        replies::{replies_routes, service::PruneDroppedClaims},
+        user::{avatar_routes, service::DeleteAvatarBlobs, user_routes},
+        webhooks::{
+            service::{DeliverWebhooks, SweepExpiredLinks},
+            webhooks_routes,
+        },
+    },
+};
+
+#[derive(Clone)]
+pub struct ApiContext {
+    pub db: PgPool,
+    pub config: Arc<Config>,
+    pub blob_store: Arc<dyn BlobStore>,
+    pub blocklists: Arc<BlocklistCache>,
+    pub qr_codes: Arc<QrCache>,
+    pub inbox_events: Arc<InboxEvents>,
+    pub ama_events: Arc<AmaEvents>,
+    pub rate_limit_store: Arc<dyn RateLimitStore>,
+    pub mailer: Arc<dyn Mailer>,
+    pub push_sender: Arc<dyn PushSender>,
+    pub keyring: Arc<Keyring>,
+    pub classifiers: Arc<Vec<Box<dyn MessageClassifier>>>,
+}
+
+impl ApiContext {
+    pub fn new(config: Config, db: PgPool, rate_limit_store: Arc<dyn RateLimitStore>) -> Self {
+        ApiContext {
+            classifiers: Arc::new(message_classifiers(&config, &db)),
+            db,
+            blob_store: blob_store(&config),
+            blocklists: Arc::new(BlocklistCache::default()),
+            qr_codes: Arc::new(QrCache::default()),
+            inbox_events: Arc::new(InboxEvents::new(config.sse_max_connections)),
+            ama_events: Arc::new(AmaEvents::default()),
+            rate_limit_store,
+            mailer: mailer(&config),
+            push_sender: push_sender(&config),
+            keyring: Arc::new(Keyring::new(&config)),
+            config: Arc::new(config),
+        }
+    }
+}
+
+/// Parses the config from the environment and `.env`, and sets up logging.
+pub fn init() -> Config {
+    dotenv::dotenv().ok();
+    let config = Config::parse();
+
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::EnvFilter::new(&config.rust_log))
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+
+    config
+}
+
+/// Connects to the database, migrates it and builds the context shared by the
+/// API and worker processes.
+pub async fn connect(config: Config) -> (ApiContext, RateLimits) {
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&config.database_url)
+        .await
+        .expect("Unable to connect to database");
+
+    migrate!()
+        .run(&pool)
+        .await
+        .expect("Failed to run auto-migration");
+
+    let rate_limits = RateLimits::new(&config, rate_limit_store(&config, &pool));
+    let ctx = ApiContext::new(config, pool, rate_limits.store.clone());
+    (ctx, rate_limits)
+}
+
+/// Every route, each behind the rate limits of its kind.
+pub fn app(ctx: ApiContext, rate_limits: &RateLimits) -> Router {
+    let cors = CorsLayer::new().allow_origin(Any);
+    Router::new()
+        .nest("/auth", auth_routes().route_layer(rate_limits.auth.clone()))
+        .nest("/me", user_routes().route_layer(rate_limits.api.clone()))
+        .nest(
+            "/me/blocklist",
+            blocklist_routes().route_layer(rate_limits.api.clone()),
+        )
+        .nest(
+            "/me/links",
+            links_routes().route_layer(rate_limits.api.clone()),
+        )
+        .nest(
+            "/me/webhooks",
+            webhooks_routes().route_layer(rate_limits.api.clone()),
+        )
+        .nest(
+            "/me/amas",
+            ama_routes().route_layer(rate_limits.api.clone()),
+        )
+        .nest(
+            "/ama",
+            live_ama_routes().route_layer(rate_limits.public.clone()),
+        )
+        .nest(
+            "/me/digest",
+            digest_routes().route_layer(rate_limits.api.clone()),
+        )
+        .nest(
+            "/me/push",
+            push_routes().route_layer(rate_limits.api.clone()),
+        )
+        .nest(
+            "/me/encryption-keys",
+            encryption_routes().route_layer(rate_limits.api.clone()),
+        )
+        .nest(
+            "/digest",
+            unsubscribe_routes().route_layer(rate_limits.public.clone()),
+        )
+        .nest(
+            "/avatars",
+            avatar_routes().route_layer(rate_limits.public.clone()),
+        )
+        .nest(
+            "/u",
+            profile_routes()
+                .merge(profile_message_routes().route_layer(rate_limits.submit.clone()))
+                .route_layer(rate_limits.public.clone()),
+        )
+        .nest(
+            "/l",
+            link_routes()
+                .merge(link_message_routes().route_layer(rate_limits.submit.clone()))
+                .route_layer(rate_limits.public.clone()),
+        )
+        .nest(
+            "/inbox",
+            inbox_routes().route_layer(rate_limits.api.clone()),
+        )
+        .nest(
+            "/moderation",
+            moderation_routes().route_layer(rate_limits.api.clone()),
+        )
+        .nest(
+            "/replies",
+            replies_routes().route_layer(rate_limits.public.clone()),
+        )
+        .layer(cors)
+        .layer(tower_http::trace::TraceLayer::new_for_http())
+        .layer(AddExtensionLayer::new(ctx))
+}
+
+/// Every job handler and cron schedule, for the runner of the API process or
+/// of a worker process.
+fn job_runner(ctx: ApiContext) -> JobRunner {
+    JobRunner::new(ctx)
+        .register(PruneJobs)
+        .register(PruneRateLimits)
+        .register(PruneInboxEvents)
+        .register(PruneDroppedClaims)
+        .register(DeliverWebhooks)
+        .register(SweepExpiredLinks)
+        .register(DeleteAvatarBlobs)
+        .register(QueueDigests)
+        .register(SendDigest)
+        .register(SendPush)
+        .register(RewrapDataKeys)
+        .register(SealStoredBodies)
+        .schedule::<PruneJobs>("jobs.prune", "0 0 3 * * *", ())
+        .schedule::<PruneRateLimits>("rate_limits.prune", "0 */10 * * * *", ())
+        .schedule::<PruneInboxEvents>("inbox_events.prune", "0 */10 * * * *", ())
+        .schedule::<PruneDroppedClaims>("dropped_claims.prune", "0 0 4 * * *", ())
+        .schedule::<DeliverWebhooks>("webhooks.deliver", "*/10 * * * * *", ())
+        .schedule::<SweepExpiredLinks>("links.sweep_expired", "0 * * * * *", ())
+        .schedule::<QueueDigests>("digests.queue", "0 */15 * * * *", ())
+        .schedule::<RewrapDataKeys>("keys.rewrap", "0 */5 * * * *", ())
+        .schedule::<SealStoredBodies>("messages.seal_stored", "0 30 * * * *", ())
+}
+
+/// Starts the job runner, which stops once `shutdown` flips and the jobs in
+/// flight finished or gave up.
+pub fn spawn_background_work(ctx: &ApiContext, shutdown: &watch::Receiver<bool>) -> JoinHandle<()> {
+    tokio::spawn(job_runner(ctx.clone()).run(shutdown.clone()))
+}
+
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to listen for Ctrl+C");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to listen for SIGTERM")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
+/// Runs background jobs only, until Ctrl+C or SIGTERM.
+pub async fn run_worker(ctx: ApiContext) {
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    tracing::debug!("Worker started");
+    let background = spawn_background_work(&ctx, &shutdown_rx);
+    shutdown_signal().await;
+    tracing::info!("Shutting down, draining background work");
+    let _ = shutdown_tx.send(true);
+    let _ = background.await;
+}
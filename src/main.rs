@@ -1,60 +1,56 @@
-use axum::Router;
-use dotenv::dotenv;
-use sqlx::{migrate, postgres::PgPoolOptions, PgPool};
-use std::{net::SocketAddr, sync::Arc};
-use tower_http::{
-    add_extension::AddExtensionLayer,
-    cors::{Any, CorsLayer},
-};
-use tracing_subscriber::{prelude::__tracing_subscriber_SubscriberExt, util::SubscriberInitExt};
-
-mod config;
-mod core;
-mod modules;
-use crate::{config::Config, modules::auth::auth_routes};
+use std::{net::SocketAddr, time::Duration};
+use tokio::sync::watch;
 
-#[derive(Clone)]
-pub struct ApiContext {
-    db: PgPool,
-    config: Arc<Config>,
-}
+use reminder_api::{
+    app, connect, init,
+    modules::{ama::service::listen_ama_events, inbox::service::listen_inbox_events},
+    shutdown_signal, spawn_background_work,
+};
 
 #[tokio::main]
 async fn main() {
-    dotenv().ok();
-    let config = Config::parse();
-
-    tracing_subscriber::registry()
-        .with(tracing_subscriber::EnvFilter::new(&config.rust_log))
-        .with(tracing_subscriber::fmt::layer())
-        .init();
-
-    let cors = CorsLayer::new().allow_origin(Any);
-    let pool = PgPoolOptions::new()
-        .max_connections(5)
-        .connect(&config.database_url)
-        .await
-        .expect("Unable to connect to database");
-
-    migrate!()
-        .run(&pool)
-        .await
-        .expect("Failed to run auto-migration");
-
-    let app = Router::new()
-        .nest("/auth", auth_routes())
-        .layer(cors)
-        .layer(tower_http::trace::TraceLayer::new_for_http())
-        .layer(AddExtensionLayer::new(ApiContext {
-            db: pool,
-            config: Arc::new(config),
-        }));
+    let (ctx, rate_limits) = connect(init()).await;
+
+    let drain_timeout = Duration::from_secs(ctx.config.shutdown_drain_seconds.into());
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+
+    listen_inbox_events(
+        ctx.config.database_url.clone(),
+        ctx.db.clone(),
+        ctx.inbox_events.clone(),
+    );
+    listen_ama_events(ctx.config.database_url.clone(), ctx.ama_events.clone());
+    let background = ctx
+        .config
+        .jobs_in_process
+        .then(|| spawn_background_work(&ctx, &shutdown_rx));
+
+    let app = app(ctx, &rate_limits);
 
     let addr = SocketAddr::from(([127, 0, 0, 1], 4040));
     tracing::debug!("Server started, listening on {addr}");
 
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app.into_make_service())
-        .await
-        .expect("Failed to start server");
+    let mut server_shutdown = shutdown_rx.clone();
+    let server = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(async move {
+        let _ = server_shutdown.changed().await;
+    });
+    let server = tokio::spawn(async move { server.await.expect("Failed to start server") });
+
+    shutdown_signal().await;
+    tracing::info!("Shutting down, draining requests and background work");
+    let _ = shutdown_tx.send(true);
+
+    // event streams and sockets stay open until their client leaves, so they
+    // only get as long as jobs do
+    if tokio::time::timeout(drain_timeout, server).await.is_err() {
+        tracing::warn!("Closing connections still open after the drain timeout");
+    }
+    if let Some(background) = background {
+        let _ = background.await;
+    }
 }
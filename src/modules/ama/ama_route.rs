@@ -0,0 +1,24 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use super::controllers::{
+    find_ama, find_amas, get_host_socket, get_public_ama, get_viewer_socket, handle_create_ama,
+    handle_create_ticket, handle_end_ama,
+};
+
+pub fn ama_routes() -> Router {
+    Router::new()
+        .route("/", get(find_amas).post(handle_create_ama))
+        .route("/:ama_id", get(find_ama))
+        .route("/:ama_id/end", post(handle_end_ama))
+        .route("/:ama_id/ticket", post(handle_create_ticket))
+}
+
+pub fn live_ama_routes() -> Router {
+    Router::new()
+        .route("/:ama_id", get(get_public_ama))
+        .route("/:ama_id/host", get(get_host_socket))
+        .route("/:ama_id/live", get(get_viewer_socket))
+}
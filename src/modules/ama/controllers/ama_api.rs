@@ -0,0 +1,93 @@
+use crate::{
+    core::{
+        extractors::{Authorized, ClientInfo, CustomPath, CustomQuery, ValidatedBody},
+        models::Claims,
+    },
+    modules::ama::{
+        models::{AmaSession, AmaSnapshot, AmaTicket, CreateAmaBody, HostSocketQuery},
+        service::{
+            create_ama, create_ticket, end_ama, find_public_ama, host_socket, list_amas,
+            single_ama, viewer_socket,
+        },
+        validation_errors::CreateAmaValidationError,
+    },
+    ApiContext,
+};
+use axum::{
+    body::Body,
+    extract::WebSocketUpgrade,
+    http::StatusCode,
+    response::{Json, Response},
+    Extension,
+};
+use uuid::Uuid;
+
+pub async fn find_amas(
+    ctx: Extension<ApiContext>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<Json<Vec<AmaSession>>, Response<Body>> {
+    let sessions = list_amas(ctx, claims).await?;
+    Ok(sessions)
+}
+
+pub async fn find_ama(
+    ctx: Extension<ApiContext>,
+    CustomPath(ama_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<Json<AmaSession>, Response<Body>> {
+    let session = single_ama(ctx, claims, ama_id).await?;
+    Ok(session)
+}
+
+pub async fn handle_create_ama(
+    ctx: Extension<ApiContext>,
+    Authorized(claims): Authorized<Claims>,
+    ValidatedBody(body, _): ValidatedBody<CreateAmaBody, CreateAmaValidationError>,
+) -> Result<(StatusCode, Json<AmaSession>), Response<Body>> {
+    let session = create_ama(ctx, claims, Json(body)).await?;
+    Ok((StatusCode::CREATED, session))
+}
+
+pub async fn handle_end_ama(
+    ctx: Extension<ApiContext>,
+    CustomPath(ama_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<Json<AmaSession>, Response<Body>> {
+    let session = end_ama(ctx, claims, ama_id).await?;
+    Ok(session)
+}
+
+pub async fn handle_create_ticket(
+    ctx: Extension<ApiContext>,
+    CustomPath(ama_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<(StatusCode, Json<AmaTicket>), Response<Body>> {
+    let ticket = create_ticket(ctx, claims, ama_id).await?;
+    Ok((StatusCode::CREATED, ticket))
+}
+
+pub async fn get_public_ama(
+    ctx: Extension<ApiContext>,
+    CustomPath(ama_id): CustomPath<Uuid>,
+) -> Result<Json<AmaSnapshot>, Response<Body>> {
+    let snapshot = find_public_ama(ctx, ama_id).await?;
+    Ok(snapshot)
+}
+
+pub async fn get_host_socket(
+    ctx: Extension<ApiContext>,
+    CustomPath(ama_id): CustomPath<Uuid>,
+    CustomQuery(query): CustomQuery<HostSocketQuery>,
+    ws: WebSocketUpgrade,
+) -> Result<Response<Body>, Response<Body>> {
+    host_socket(ctx, query.ticket, ama_id, ws).await
+}
+
+pub async fn get_viewer_socket(
+    ctx: Extension<ApiContext>,
+    CustomPath(ama_id): CustomPath<Uuid>,
+    client: ClientInfo,
+    ws: WebSocketUpgrade,
+) -> Result<Response<Body>, Response<Body>> {
+    viewer_socket(ctx, client, ama_id, ws).await
+}
@@ -0,0 +1,3 @@
+mod ama_api;
+
+pub use ama_api::*;
@@ -0,0 +1,7 @@
+mod ama_route;
+pub use ama_route::*;
+
+pub mod controllers;
+pub mod models;
+pub mod service;
+pub mod validation_errors;
@@ -0,0 +1,137 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+pub const AMA_QUESTION_MAX_LENGTH: usize = 500;
+pub const AMA_ANSWER_MAX_LENGTH: usize = 1000;
+
+/// A live "ask me anything" session, open to questions until `ended_at`.
+#[derive(Serialize, Clone)]
+pub struct AmaSession {
+    pub ama_id: String,
+    pub title: String,
+    pub started_at: NaiveDateTime,
+    pub ended_at: Option<NaiveDateTime>,
+}
+
+/// A session as viewers see it, with the host's public profile.
+#[derive(Serialize, Clone)]
+pub struct PublicAmaSession {
+    pub ama_id: String,
+    pub title: String,
+    pub host_name: String,
+    pub host_avatar: Option<String>,
+    pub started_at: NaiveDateTime,
+    pub ended_at: Option<NaiveDateTime>,
+}
+
+/// A question asked during a session. `status` is `pending` until the host
+/// approves or hides it, viewers only ever see approved questions.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AmaQuestion {
+    pub question_id: String,
+    pub body: String,
+    pub status: String,
+    pub answer: Option<String>,
+    pub answered_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+/// A session and every question the receiver may see: all of them for the
+/// host, the approved ones for viewers.
+#[derive(Serialize)]
+pub struct AmaSnapshot {
+    pub session: PublicAmaSession,
+    pub questions: Vec<AmaQuestion>,
+}
+
+/// Opens the host socket of one session, once and only until `expires_at`.
+#[derive(Serialize)]
+pub struct AmaTicket {
+    pub ticket: String,
+    pub expires_at: NaiveDateTime,
+}
+
+#[derive(Deserialize)]
+pub struct HostSocketQuery {
+    pub ticket: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct CreateAmaBody {
+    #[validate(length(min = 1, max = 120))]
+    pub title: String,
+}
+
+/// A change to a session, as announced on the `ama_events` channel.
+#[derive(Deserialize, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AmaEvent {
+    Question {
+        ama_id: Uuid,
+        question: AmaQuestion,
+        /// `None` for a new question.
+        previous_status: Option<String>,
+    },
+    Ended {
+        ama_id: Uuid,
+    },
+}
+
+impl AmaEvent {
+    pub fn ama_id(&self) -> Uuid {
+        match self {
+            Self::Question { ama_id, .. } | Self::Ended { ama_id } => *ama_id,
+        }
+    }
+}
+
+/// What the host sends over the socket.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum HostCommand {
+    Approve {
+        question_id: Uuid,
+    },
+    Hide {
+        question_id: Uuid,
+    },
+    /// Answering a question also approves it.
+    Answer {
+        question_id: Uuid,
+        answer: String,
+    },
+    End,
+}
+
+/// What a viewer sends over the socket.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ViewerCommand {
+    Ask { body: String },
+}
+
+/// What the server sends over either socket.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GatewayMessage {
+    /// Sent on connect, and again if the socket fell behind.
+    Snapshot(AmaSnapshot),
+    Question {
+        question: AmaQuestion,
+    },
+    /// A question viewers saw was hidden.
+    Removed {
+        question_id: String,
+    },
+    Ended,
+    /// The viewer's question was received and waits for the host.
+    Asked {
+        question_id: String,
+    },
+    Error {
+        message: String,
+    },
+}
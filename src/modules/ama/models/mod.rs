@@ -0,0 +1,3 @@
+mod ama_model;
+
+pub use ama_model::*;
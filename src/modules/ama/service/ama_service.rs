@@ -0,0 +1,221 @@
+use crate::{
+    core::models::{ApiError, Claims},
+    modules::ama::models::{
+        AmaQuestion, AmaSession, AmaSnapshot, AmaTicket, CreateAmaBody, PublicAmaSession,
+    },
+    ApiContext,
+};
+use axum::{body::Body, http::Response, response::IntoResponse};
+use axum::{Extension, Json};
+use chrono::DateTime;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Sessions a user may have open at once.
+const MAX_OPEN_SESSIONS: i64 = 5;
+
+/// Seconds a host socket ticket can be used for after it is taken.
+const TICKET_LIFETIME_SECONDS: f64 = 30.0;
+
+/// Digest stored in place of a ticket. Tickets are 256 random bits, so an
+/// unsalted digest is enough.
+pub fn ticket_hash(ticket: &str) -> Vec<u8> {
+    Sha256::digest(ticket).to_vec()
+}
+
+pub async fn list_amas(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+) -> Result<Json<Vec<AmaSession>>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let result = sqlx::query_as!(
+        AmaSession,
+        r#"select id::text as "ama_id!", title, started_at, ended_at
+        from "ama_sessions" where user_id = $1 order by started_at desc"#,
+        user_id
+    )
+    .fetch_all(&ctx.db)
+    .await;
+
+    match result {
+        Ok(sessions) => Ok(Json(sessions)),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+pub async fn single_ama(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    ama_id: Uuid,
+) -> Result<Json<AmaSession>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let result = sqlx::query_as!(
+        AmaSession,
+        r#"select id::text as "ama_id!", title, started_at, ended_at
+        from "ama_sessions" where id = $1 and user_id = $2"#,
+        ama_id,
+        user_id
+    )
+    .fetch_optional(&ctx.db)
+    .await;
+
+    match result {
+        Ok(Some(session)) => Ok(Json(session)),
+        Ok(None) => Err(ApiError::NotFound("Session not found".to_string()).into_response()),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+pub async fn create_ama(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    Json(body): Json<CreateAmaBody>,
+) -> Result<Json<AmaSession>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let open = sqlx::query_scalar!(
+        r#"select count(*) as "count!" from "ama_sessions" where user_id = $1 and ended_at is null"#,
+        user_id
+    )
+    .fetch_one(&ctx.db)
+    .await
+    .map_err(|e| ApiError::Database(e).into_response())?;
+    if open >= MAX_OPEN_SESSIONS {
+        return Err(ApiError::BadRequest {
+            errors: vec![format!(
+                "sessions: at most {MAX_OPEN_SESSIONS} sessions can be open at once."
+            )],
+        }
+        .into_response());
+    }
+
+    let result = sqlx::query_as!(
+        AmaSession,
+        r#"insert into "ama_sessions" (user_id, title) values ($1, $2)
+        returning id::text as "ama_id!", title, started_at, ended_at"#,
+        user_id,
+        body.title.trim()
+    )
+    .fetch_one(&ctx.db)
+    .await;
+
+    match result {
+        Ok(session) => Ok(Json(session)),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+/// Closes a session to new questions. Open sockets are told it ended.
+pub async fn end_session(db: &PgPool, user_id: Uuid, ama_id: Uuid) -> Result<AmaSession, ApiError> {
+    sqlx::query_as!(
+        AmaSession,
+        r#"update "ama_sessions" set ended_at = coalesce(ended_at, now())
+        where id = $1 and user_id = $2
+        returning id::text as "ama_id!", title, started_at, ended_at"#,
+        ama_id,
+        user_id
+    )
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Session not found".to_string()))
+}
+
+pub async fn end_ama(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    ama_id: Uuid,
+) -> Result<Json<AmaSession>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+    let session = end_session(&ctx.db, user_id, ama_id)
+        .await
+        .map_err(|e| e.into_response())?;
+    Ok(Json(session))
+}
+
+/// Hands the host a ticket for the socket of one of their sessions. Browsers
+/// cannot send the `Authorization` header on a WebSocket handshake, so the
+/// socket takes this ticket in its URL instead of the access token.
+pub async fn create_ticket(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    ama_id: Uuid,
+) -> Result<Json<AmaTicket>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+    let token_expires_at = DateTime::from_timestamp(claims.exp, 0)
+        .ok_or_else(|| ApiError::Unauthorized("Invalid token expiry".to_string()).into_response())?
+        .naive_utc();
+
+    sqlx::query!(r#"delete from "ama_tickets" where expires_at < now()"#)
+        .execute(&ctx.db)
+        .await
+        .map_err(|e| ApiError::Database(e).into_response())?;
+
+    let ticket = hex::encode(rand::random::<[u8; 32]>());
+    let result = sqlx::query_scalar!(
+        r#"insert into "ama_tickets" (ticket_hash, session_id, user_id, token_expires_at, expires_at)
+        select $1, id, user_id, $4, now() + make_interval(secs => $5)
+        from "ama_sessions" where id = $2 and user_id = $3
+        returning expires_at"#,
+        ticket_hash(&ticket),
+        ama_id,
+        user_id,
+        token_expires_at,
+        TICKET_LIFETIME_SECONDS
+    )
+    .fetch_optional(&ctx.db)
+    .await;
+
+    match result {
+        Ok(Some(expires_at)) => Ok(Json(AmaTicket { ticket, expires_at })),
+        Ok(None) => Err(ApiError::NotFound("Session not found".to_string()).into_response()),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+/// The session and its questions, every one of them for the host and only
+/// the approved ones otherwise. Sessions of suspended hosts are not found.
+pub async fn ama_snapshot(
+    db: &PgPool,
+    ama_id: Uuid,
+    host_id: Option<Uuid>,
+) -> Result<AmaSnapshot, ApiError> {
+    let session = sqlx::query_as!(
+        PublicAmaSession,
+        r#"select s.id::text as "ama_id!", s.title, u.name as host_name, u.avatar as host_avatar,
+            s.started_at, s.ended_at
+        from "ama_sessions" s join "users" u on u.id = s.user_id
+        where s.id = $1 and u.suspended_at is null and ($2::uuid is null or s.user_id = $2)"#,
+        ama_id,
+        host_id
+    )
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Session not found".to_string()))?;
+
+    let questions = sqlx::query_as!(
+        AmaQuestion,
+        r#"select id::text as "question_id!", body, status, answer, answered_at, created_at
+        from "ama_questions"
+        where session_id = $1 and ($2 or status = 'approved')
+        order by created_at"#,
+        ama_id,
+        host_id.is_some()
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(AmaSnapshot { session, questions })
+}
+
+pub async fn find_public_ama(
+    ctx: Extension<ApiContext>,
+    ama_id: Uuid,
+) -> Result<Json<AmaSnapshot>, Response<Body>> {
+    let snapshot = ama_snapshot(&ctx.db, ama_id, None)
+        .await
+        .map_err(|e| e.into_response())?;
+    Ok(Json(snapshot))
+}
@@ -0,0 +1,521 @@
+use std::{net::IpAddr, sync::Arc, time::Duration};
+
+use crate::{
+    core::{
+        classifier::{classify, content_hash, content_key},
+        extractors::ClientInfo,
+        models::{ApiError, IncomingMessage},
+    },
+    modules::{
+        ama::{
+            models::{
+                AmaEvent, GatewayMessage, HostCommand, ViewerCommand, AMA_ANSWER_MAX_LENGTH,
+                AMA_QUESTION_MAX_LENGTH,
+            },
+            service::{ama_snapshot, end_session, ticket_hash},
+        },
+        profile::service::{is_blocked, platform_fingerprints, sender_fingerprints},
+        user::service::message_settings,
+    },
+    ApiContext,
+};
+use axum::{
+    body::Body,
+    extract::ws::{Message, WebSocket, WebSocketUpgrade},
+    http::Response,
+    response::IntoResponse,
+    Extension,
+};
+use sqlx::postgres::PgListener;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+const CHANNEL: &str = "ama_events";
+
+/// Largest frame a client may send, questions and answers are far smaller.
+const MAX_FRAME_SIZE: usize = 16 * 1024;
+
+/// Keeps idle sockets from being dropped by proxies in between.
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Who is on the other end of a socket.
+#[derive(Clone)]
+enum Role {
+    /// The session's owner, until their token expires at `expires_at`.
+    Host { user_id: Uuid, expires_at: i64 },
+    /// Anyone watching, asking `host_id` questions from `client`.
+    Viewer { host_id: Uuid, client: ClientInfo },
+}
+
+/// What the listener passes on to the sockets of this instance.
+enum Broadcast {
+    Event(AmaEvent),
+    /// Events may have been missed while the listener reconnected.
+    Resync,
+}
+
+/// Fans the events this instance hears on the `ama_events` channel out to
+/// the open sockets, each keeping those of its own session.
+pub struct AmaEvents {
+    sender: broadcast::Sender<Arc<Broadcast>>,
+}
+
+impl Default for AmaEvents {
+    fn default() -> Self {
+        AmaEvents {
+            sender: broadcast::channel(1024).0,
+        }
+    }
+}
+
+/// Listens on the `ama_events` channel for as long as the server runs, so
+/// changes made through any instance reach the sockets open on this one.
+pub fn listen_ama_events(database_url: String, hub: Arc<AmaEvents>) {
+    tokio::spawn(async move {
+        let mut first = true;
+        loop {
+            let mut listener = match PgListener::connect(&database_url).await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    tracing::warn!("Failed to connect the AMA event listener: {e}");
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    continue;
+                }
+            };
+            if let Err(e) = listener.listen(CHANNEL).await {
+                tracing::warn!("Failed to listen for AMA events: {e}");
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                continue;
+            }
+            if !std::mem::take(&mut first) {
+                let _ = hub.sender.send(Arc::new(Broadcast::Resync));
+            }
+
+            loop {
+                match listener.try_recv().await {
+                    Ok(Some(notification)) => {
+                        match serde_json::from_str::<AmaEvent>(notification.payload()) {
+                            Ok(event) => {
+                                let _ = hub.sender.send(Arc::new(Broadcast::Event(event)));
+                            }
+                            Err(e) => tracing::warn!("Invalid AMA event: {e}"),
+                        }
+                    }
+                    Ok(None) => {
+                        let _ = hub.sender.send(Arc::new(Broadcast::Resync));
+                    }
+                    Err(e) => {
+                        tracing::warn!("Lost the AMA event listener: {e}");
+                        break;
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// The message `event` makes for a socket of `role`, if any: the host sees
+/// every question, viewers see approved ones and learn when one is hidden.
+fn relay(event: &AmaEvent, role: &Role) -> Option<GatewayMessage> {
+    match (event, role) {
+        (AmaEvent::Ended { .. }, _) => Some(GatewayMessage::Ended),
+        (AmaEvent::Question { question, .. }, Role::Host { .. }) => {
+            Some(GatewayMessage::Question {
+                question: question.clone(),
+            })
+        }
+        (
+            AmaEvent::Question {
+                question,
+                previous_status,
+                ..
+            },
+            Role::Viewer { .. },
+        ) => {
+            if question.status == "approved" {
+                Some(GatewayMessage::Question {
+                    question: question.clone(),
+                })
+            } else if previous_status.as_deref() == Some("approved") {
+                Some(GatewayMessage::Removed {
+                    question_id: question.question_id.clone(),
+                })
+            } else {
+                None
+            }
+        }
+    }
+}
+
+fn error_message(message: impl Into<String>) -> Option<GatewayMessage> {
+    Some(GatewayMessage::Error {
+        message: message.into(),
+    })
+}
+
+/// Trims `text` and checks it is between 1 and `max` characters long.
+fn checked_text(field: &str, text: &str, max: usize) -> Result<String, String> {
+    let text = text.trim();
+    if text.is_empty() || text.chars().count() > max {
+        return Err(format!("{field}: must be 1 to {max} characters long"));
+    }
+    Ok(text.to_string())
+}
+
+async fn set_status(
+    ctx: &ApiContext,
+    ama_id: Uuid,
+    question_id: Uuid,
+    status: &str,
+) -> Result<bool, ApiError> {
+    let updated = sqlx::query!(
+        r#"update "ama_questions" set status = $3 where id = $1 and session_id = $2"#,
+        question_id,
+        ama_id,
+        status
+    )
+    .execute(&ctx.db)
+    .await?;
+    Ok(updated.rows_affected() > 0)
+}
+
+async fn answer_question(
+    ctx: &ApiContext,
+    ama_id: Uuid,
+    question_id: Uuid,
+    answer: &str,
+) -> Result<bool, ApiError> {
+    let updated = sqlx::query!(
+        r#"update "ama_questions" set answer = $3, answered_at = now(), status = 'approved'
+        where id = $1 and session_id = $2"#,
+        question_id,
+        ama_id,
+        answer
+    )
+    .execute(&ctx.db)
+    .await?;
+    Ok(updated.rows_affected() > 0)
+}
+
+/// Applies a host command. Its effect comes back through the event channel,
+/// so only failures are answered directly.
+async fn host_command(
+    ctx: &ApiContext,
+    ama_id: Uuid,
+    user_id: Uuid,
+    text: &str,
+) -> Result<Option<GatewayMessage>, ApiError> {
+    let command = match serde_json::from_str::<HostCommand>(text) {
+        Ok(command) => command,
+        Err(e) => return Ok(error_message(format!("Invalid command: {e}"))),
+    };
+
+    let found = match command {
+        HostCommand::Approve { question_id } => {
+            set_status(ctx, ama_id, question_id, "approved").await?
+        }
+        HostCommand::Hide { question_id } => set_status(ctx, ama_id, question_id, "hidden").await?,
+        HostCommand::Answer {
+            question_id,
+            answer,
+        } => match checked_text("answer", &answer, AMA_ANSWER_MAX_LENGTH) {
+            Ok(answer) => answer_question(ctx, ama_id, question_id, &answer).await?,
+            Err(message) => return Ok(error_message(message)),
+        },
+        HostCommand::End => {
+            end_session(&ctx.db, user_id, ama_id).await?;
+            true
+        }
+    };
+    Ok((!found).then(|| GatewayMessage::Error {
+        message: "Question not found".to_string(),
+    }))
+}
+
+/// Takes a token from the viewer's bucket, the same quota as messages sent
+/// through a profile.
+async fn limit_viewer(ctx: &ApiContext, ip: IpAddr) -> Result<(), String> {
+    let quota = ctx.config.rate_limit_submit;
+    let bucket = format!("ama:ip:{ip}");
+    match ctx
+        .rate_limit_store
+        .acquire(
+            &bucket,
+            quota.capacity,
+            quota.capacity as f64 / quota.period_seconds as f64,
+        )
+        .await
+    {
+        Ok(decision) if !decision.allowed => {
+            let retry_after = decision.retry_after.as_secs_f64().ceil().max(1.0) as u64;
+            Err(format!(
+                "Too many questions, retry in {retry_after} seconds"
+            ))
+        }
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::warn!("Rate limit store failed for {bucket}: {e}");
+            Ok(())
+        }
+    }
+}
+
+/// Records a viewer's question after the checks a message sent to the host
+/// goes through: their blocks, blocklist and classifiers. A question the
+/// blocklist filters or the classifiers flag is hidden until the host
+/// approves it, one it drops is answered as if it had been asked.
+async fn viewer_command(
+    ctx: &ApiContext,
+    ama_id: Uuid,
+    host_id: Uuid,
+    client: &ClientInfo,
+    text: &str,
+) -> Result<Option<GatewayMessage>, ApiError> {
+    let ViewerCommand::Ask { body } = match serde_json::from_str::<ViewerCommand>(text) {
+        Ok(command) => command,
+        Err(e) => return Ok(error_message(format!("Invalid command: {e}"))),
+    };
+    let body = match checked_text("body", &body, AMA_QUESTION_MAX_LENGTH) {
+        Ok(body) => body,
+        Err(message) => return Ok(error_message(message)),
+    };
+
+    let fingerprints = sender_fingerprints(&ctx.db, host_id, client).await?;
+    let platform_fingerprints = platform_fingerprints(&ctx.db, client).await?;
+    if is_blocked(&ctx.db, host_id, &fingerprints, &platform_fingerprints).await? {
+        return Ok(error_message("You cannot ask questions in this session"));
+    }
+    if let Err(message) = limit_viewer(ctx, client.ip).await {
+        return Ok(error_message(message));
+    }
+
+    let settings = message_settings(&ctx.db, host_id).await?;
+    let blocklist = ctx
+        .blocklists
+        .blocklist(&ctx.db, host_id, settings.blocklist_version)
+        .await?;
+    let status = if blocklist.is_match(&body) {
+        match settings.blocklist_action.as_str() {
+            "drop" => {
+                let question_id =
+                    sqlx::query_scalar!(r#"select uuid_generate_v1mc()::text as "id!""#)
+                        .fetch_one(&ctx.db)
+                        .await?;
+                return Ok(Some(GatewayMessage::Asked { question_id }));
+            }
+            "filter" => "hidden",
+            _ => {
+                return Ok(error_message(
+                    "body: contains words the host does not accept.",
+                ))
+            }
+        }
+    } else {
+        "pending"
+    };
+
+    let scores = classify(
+        &ctx.classifiers,
+        &IncomingMessage {
+            recipient_id: host_id,
+            body: &body,
+            content_hash: &content_hash(&content_key(&ctx.config.jwt_secret), &body),
+        },
+    )
+    .await;
+    let score = scores.values().copied().fold(0.0, f32::max);
+    let status = if score > settings.filter_threshold {
+        "hidden"
+    } else {
+        status
+    };
+
+    let question_id = sqlx::query_scalar!(
+        r#"insert into "ama_questions" (session_id, body, status)
+        select id, $2, $3 from "ama_sessions" where id = $1 and ended_at is null
+        returning id::text as "question_id!""#,
+        ama_id,
+        body,
+        status
+    )
+    .fetch_optional(&ctx.db)
+    .await?;
+
+    Ok(Some(match question_id {
+        Some(question_id) => GatewayMessage::Asked { question_id },
+        None => GatewayMessage::Error {
+            message: "This session has ended".to_string(),
+        },
+    }))
+}
+
+async fn send(socket: &mut WebSocket, message: &GatewayMessage) -> Result<(), axum::Error> {
+    let text = serde_json::to_string(message).unwrap_or_default();
+    socket.send(Message::Text(text)).await
+}
+
+async fn send_snapshot(
+    ctx: &ApiContext,
+    socket: &mut WebSocket,
+    ama_id: Uuid,
+    role: &Role,
+) -> Result<(), axum::Error> {
+    let host_id = match role {
+        Role::Host { user_id, .. } => Some(*user_id),
+        Role::Viewer { .. } => None,
+    };
+    let message = match ama_snapshot(&ctx.db, ama_id, host_id).await {
+        Ok(snapshot) => GatewayMessage::Snapshot(snapshot),
+        Err(e) => GatewayMessage::Error {
+            message: e.to_string(),
+        },
+    };
+    send(socket, &message).await
+}
+
+async fn run_socket(ctx: ApiContext, mut socket: WebSocket, ama_id: Uuid, role: Role) {
+    // subscribe before the snapshot, so nothing committed in between is missed
+    let mut live = ctx.ama_events.sender.subscribe();
+    if send_snapshot(&ctx, &mut socket, ama_id, &role)
+        .await
+        .is_err()
+    {
+        return;
+    }
+
+    let mut ping = tokio::time::interval(PING_INTERVAL);
+    ping.tick().await;
+    let (is_host, expires_in) = match &role {
+        Role::Host { expires_at, .. } => (
+            true,
+            (expires_at - chrono::Utc::now().timestamp()).max(0) as u64,
+        ),
+        Role::Viewer { .. } => (false, 0),
+    };
+    let expiry = tokio::time::sleep(Duration::from_secs(expires_in));
+    tokio::pin!(expiry);
+
+    loop {
+        let sent = tokio::select! {
+            message = socket.recv() => {
+                let text = match message {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Binary(_))) => {
+                        let reply = GatewayMessage::Error {
+                            message: "Only text messages are accepted".to_string(),
+                        };
+                        if send(&mut socket, &reply).await.is_err() {
+                            return;
+                        }
+                        continue;
+                    }
+                    Some(Ok(Message::Ping(_) | Message::Pong(_))) => continue,
+                    Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                };
+                let reply = match &role {
+                    Role::Host { user_id, .. } => host_command(&ctx, ama_id, *user_id, &text).await,
+                    Role::Viewer { host_id, client } => {
+                        viewer_command(&ctx, ama_id, *host_id, client, &text).await
+                    }
+                };
+                match reply {
+                    Ok(Some(reply)) => send(&mut socket, &reply).await,
+                    Ok(None) => Ok(()),
+                    Err(e) => {
+                        tracing::warn!("AMA command failed: {e:?}");
+                        send(&mut socket, &GatewayMessage::Error { message: e.to_string() }).await
+                    }
+                }
+            }
+            event = live.recv() => match event.as_deref() {
+                Ok(Broadcast::Event(event)) if event.ama_id() == ama_id => match relay(event, &role) {
+                    Some(message) => send(&mut socket, &message).await,
+                    None => Ok(()),
+                },
+                Ok(Broadcast::Event(_)) => Ok(()),
+                Ok(Broadcast::Resync) | Err(broadcast::error::RecvError::Lagged(_)) => {
+                    send_snapshot(&ctx, &mut socket, ama_id, &role).await
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            _ = ping.tick() => socket.send(Message::Ping(Vec::new())).await,
+            _ = &mut expiry, if is_host => {
+                let _ = send(&mut socket, &GatewayMessage::Error {
+                    message: "Authorization token expired".to_string(),
+                })
+                .await;
+                break;
+            }
+        };
+        if sent.is_err() {
+            return;
+        }
+    }
+    let _ = socket.send(Message::Close(None)).await;
+}
+
+/// Opens the host's socket on one of their sessions, which shows every
+/// question as it comes in and takes `approve`, `hide`, `answer` and `end`
+/// commands. The ticket is used up whether or not it is still valid.
+pub async fn host_socket(
+    ctx: Extension<ApiContext>,
+    ticket: String,
+    ama_id: Uuid,
+    ws: WebSocketUpgrade,
+) -> Result<Response<Body>, Response<Body>> {
+    let redeemed = sqlx::query!(
+        r#"delete from "ama_tickets" where ticket_hash = $1 and session_id = $2
+        returning user_id, token_expires_at, expires_at > now() as "valid!""#,
+        ticket_hash(&ticket),
+        ama_id
+    )
+    .fetch_optional(&ctx.db)
+    .await
+    .map_err(|e| ApiError::Database(e).into_response())?;
+    let (user_id, token_expires_at) = match redeemed {
+        Some(ticket) if ticket.valid => (ticket.user_id, ticket.token_expires_at),
+        _ => {
+            return Err(
+                ApiError::Unauthorized("Invalid or expired ticket".to_string()).into_response(),
+            )
+        }
+    };
+    ama_snapshot(&ctx.db, ama_id, Some(user_id))
+        .await
+        .map_err(|e| e.into_response())?;
+
+    let role = Role::Host {
+        user_id,
+        expires_at: token_expires_at.and_utc().timestamp(),
+    };
+    let Extension(ctx) = ctx;
+    Ok(ws
+        .max_message_size(MAX_FRAME_SIZE)
+        .on_upgrade(move |socket| run_socket(ctx, socket, ama_id, role)))
+}
+
+/// Opens an anonymous viewer's socket on a session, which shows approved
+/// questions and their answers as they come and takes `ask` commands.
+pub async fn viewer_socket(
+    ctx: Extension<ApiContext>,
+    client: ClientInfo,
+    ama_id: Uuid,
+    ws: WebSocketUpgrade,
+) -> Result<Response<Body>, Response<Body>> {
+    ama_snapshot(&ctx.db, ama_id, None)
+        .await
+        .map_err(|e| e.into_response())?;
+    let host_id = sqlx::query_scalar!(
+        r#"select user_id from "ama_sessions" where id = $1"#,
+        ama_id
+    )
+    .fetch_one(&ctx.db)
+    .await
+    .map_err(|e| ApiError::Database(e).into_response())?;
+
+    let role = Role::Viewer { host_id, client };
+    let Extension(ctx) = ctx;
+    Ok(ws
+        .max_message_size(MAX_FRAME_SIZE)
+        .on_upgrade(move |socket| run_socket(ctx, socket, ama_id, role)))
+}
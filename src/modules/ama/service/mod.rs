@@ -0,0 +1,5 @@
+mod ama_service;
+mod gateway_service;
+
+pub use ama_service::*;
+pub use gateway_service::*;
@@ -0,0 +1,12 @@
+use crate::core::{error_transformer::error_transformer, traits::TransformValidationErrors};
+use validator::ValidationErrors;
+
+pub struct CreateAmaValidationError;
+impl TransformValidationErrors for CreateAmaValidationError {
+    fn new() -> Self {
+        CreateAmaValidationError
+    }
+    fn transform_errors(&self, errors: ValidationErrors) -> Vec<String> {
+        error_transformer(errors)
+    }
+}
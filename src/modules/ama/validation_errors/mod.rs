@@ -0,0 +1,3 @@
+mod ama_error;
+
+pub use ama_error::*;
@@ -20,6 +20,5 @@ fn get_auth() -> Router {
 }
 
 pub fn auth_routes() -> Router {
-    let auth_api = Router::new().merge(get_auth()).merge(post_auth());
-    auth_api
+    Router::new().merge(get_auth()).merge(post_auth())
 }
@@ -44,10 +44,7 @@ pub async fn find_user(
 }
 
 pub async fn hello_world() -> Json<Message> {
-    let message = Message {
-        message: "Welcome to Auth API".to_string(),
-    };
-    Json(message.new())
+    Json(Message::new("Welcome to Auth API".to_string()))
 }
 
 pub async fn handle_login(
@@ -4,9 +4,7 @@ pub struct Message {
 }
 
 impl Message {
-    pub fn new(self) -> Self {
-        Message {
-            message: self.message,
-        }
+    pub fn new(message: String) -> Self {
+        Message { message }
     }
 }
@@ -1,5 +1,5 @@
-mod user_model;
 mod default_model;
+mod user_model;
 
-pub use user_model::*;
 pub use default_model::*;
+pub use user_model::*;
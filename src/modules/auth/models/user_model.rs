@@ -44,7 +44,7 @@ pub struct UserResponse {
 pub struct LoginUser {
     pub user_id: Option<String>,
     pub name: Option<String>,
-    pub password: Option<String>
+    pub password: Option<String>,
 }
 
 #[derive(Serialize)]
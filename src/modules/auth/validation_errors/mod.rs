@@ -1,5 +1,5 @@
-mod signup_error;
 mod login_error;
+mod signup_error;
 
-pub use signup_error::*;
 pub use login_error::*;
+pub use signup_error::*;
@@ -0,0 +1,17 @@
+use axum::{
+    routing::{get, patch},
+    Router,
+};
+
+use super::controllers::{
+    find_entries, handle_create_entry, handle_delete_entry, handle_update_entry,
+};
+
+pub fn blocklist_routes() -> Router {
+    Router::new()
+        .route("/", get(find_entries).post(handle_create_entry))
+        .route(
+            "/:entry_id",
+            patch(handle_update_entry).delete(handle_delete_entry),
+        )
+}
@@ -0,0 +1,54 @@
+use crate::{
+    core::{
+        extractors::{Authorized, CustomPath, ValidatedBody},
+        models::Claims,
+    },
+    modules::blocklist::{
+        models::{BlocklistEntry, CreateEntryBody, UpdateEntryBody},
+        service::{create_entry, delete_entry, list_entries, update_entry},
+        validation_errors::{CreateEntryValidationError, UpdateEntryValidationError},
+    },
+    ApiContext,
+};
+use axum::{
+    body::Body,
+    http::StatusCode,
+    response::{Json, Response},
+    Extension,
+};
+use uuid::Uuid;
+
+pub async fn find_entries(
+    ctx: Extension<ApiContext>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<Json<Vec<BlocklistEntry>>, Response<Body>> {
+    let entries = list_entries(ctx, claims).await?;
+    Ok(entries)
+}
+
+pub async fn handle_create_entry(
+    ctx: Extension<ApiContext>,
+    Authorized(claims): Authorized<Claims>,
+    ValidatedBody(body, _): ValidatedBody<CreateEntryBody, CreateEntryValidationError>,
+) -> Result<(StatusCode, Json<BlocklistEntry>), Response<Body>> {
+    let entry = create_entry(ctx, claims, Json(body)).await?;
+    Ok((StatusCode::CREATED, entry))
+}
+
+pub async fn handle_update_entry(
+    ctx: Extension<ApiContext>,
+    CustomPath(entry_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+    ValidatedBody(body, _): ValidatedBody<UpdateEntryBody, UpdateEntryValidationError>,
+) -> Result<Json<BlocklistEntry>, Response<Body>> {
+    let entry = update_entry(ctx, claims, entry_id, Json(body)).await?;
+    Ok(entry)
+}
+
+pub async fn handle_delete_entry(
+    ctx: Extension<ApiContext>,
+    CustomPath(entry_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<StatusCode, Response<Body>> {
+    delete_entry(ctx, claims, entry_id).await
+}
@@ -0,0 +1,3 @@
+mod blocklist_api;
+
+pub use blocklist_api::*;
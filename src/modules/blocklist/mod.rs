@@ -0,0 +1,7 @@
+mod blocklist_route;
+pub use blocklist_route::*;
+
+pub mod controllers;
+pub mod models;
+pub mod service;
+pub mod validation_errors;
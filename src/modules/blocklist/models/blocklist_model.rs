@@ -0,0 +1,49 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+#[derive(Serialize)]
+pub struct BlocklistEntry {
+    pub entry_id: String,
+    pub kind: String,
+    pub pattern: String,
+    pub created_at: NaiveDateTime,
+}
+
+pub struct BlocklistPattern {
+    pub kind: String,
+    pub pattern: String,
+}
+
+fn validate_kind(kind: &str) -> Result<(), ValidationError> {
+    match kind {
+        "word" | "phrase" | "regex" => Ok(()),
+        _ => Err(ValidationError::new("kind")),
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct CreateEntryBody {
+    #[validate(custom(
+        function = "validate_kind",
+        message = "must be one of word, phrase or regex"
+    ))]
+    pub kind: String,
+
+    #[validate(length(min = 1, max = 200))]
+    pub pattern: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateEntryBody {
+    #[validate(custom(
+        function = "validate_kind",
+        message = "must be one of word, phrase or regex"
+    ))]
+    pub kind: Option<String>,
+
+    #[validate(length(min = 1, max = 200))]
+    pub pattern: Option<String>,
+}
@@ -0,0 +1,3 @@
+mod blocklist_model;
+
+pub use blocklist_model::*;
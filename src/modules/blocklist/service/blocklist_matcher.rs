@@ -0,0 +1,235 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use regex::{RegexBuilder, RegexSet, RegexSetBuilder};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{core::models::ApiError, modules::blocklist::models::BlocklistPattern};
+
+/// Compiled program size limits. The regex engine runs in linear time, so
+/// bounding the compiled size is enough to keep user patterns cheap.
+const PATTERN_SIZE_LIMIT: usize = 64 * 1024;
+const SET_SIZE_LIMIT: usize = 2 * 1024 * 1024;
+
+/// Translates a blocklist entry into regex source. Words only match whole
+/// words, phrases tolerate any run of whitespace between their words.
+fn pattern_source(kind: &str, pattern: &str) -> String {
+    match kind {
+        "regex" => pattern.to_string(),
+        "phrase" => pattern
+            .split_whitespace()
+            .map(regex::escape)
+            .collect::<Vec<_>>()
+            .join(r"\s+"),
+        _ => {
+            let word = pattern.trim();
+            let boundary = |c: Option<char>| match c {
+                Some(c) if c.is_alphanumeric() || c == '_' => r"\b",
+                _ => "",
+            };
+            format!(
+                "{}{}{}",
+                boundary(word.chars().next()),
+                regex::escape(word),
+                boundary(word.chars().last())
+            )
+        }
+    }
+}
+
+/// Trims a word or phrase and checks that the entry compiles on its own
+/// within the size limits, returning the pattern to store.
+pub fn normalize_pattern(kind: &str, pattern: &str) -> Result<String, ApiError> {
+    let pattern = match kind {
+        "regex" => pattern,
+        _ => pattern.trim(),
+    };
+    if pattern.is_empty() {
+        return Err(ApiError::BadRequest {
+            errors: vec!["pattern: must not be blank.".to_string()],
+        });
+    }
+    RegexBuilder::new(&pattern_source(kind, pattern))
+        .case_insensitive(true)
+        .size_limit(PATTERN_SIZE_LIMIT)
+        .build()
+        .map_err(|e| ApiError::BadRequest {
+            errors: vec![format!("pattern: {e}")],
+        })?;
+    Ok(pattern.to_string())
+}
+
+/// Compiles entries into one set. Blank words and phrases, which would
+/// match every message, are left out.
+fn compile_set<'a>(
+    entries: impl Iterator<Item = (&'a str, &'a str)>,
+) -> Result<Option<RegexSet>, regex::Error> {
+    let sources = entries
+        .filter(|(kind, pattern)| *kind == "regex" || !pattern.trim().is_empty())
+        .map(|(kind, pattern)| pattern_source(kind, pattern))
+        .collect::<Vec<_>>();
+    if sources.is_empty() {
+        return Ok(None);
+    }
+    RegexSetBuilder::new(sources)
+        .case_insensitive(true)
+        .size_limit(SET_SIZE_LIMIT)
+        .build()
+        .map(Some)
+}
+
+/// Checks that the user's other entries and the one being saved still
+/// compile together, so the entry pushing the set over its limit is refused
+/// rather than breaking every submission afterwards.
+pub fn validate_blocklist(
+    others: &[BlocklistPattern],
+    kind: &str,
+    pattern: &str,
+) -> Result<(), ApiError> {
+    let entries = others
+        .iter()
+        .map(|entry| (entry.kind.as_str(), entry.pattern.as_str()))
+        .chain([(kind, pattern)]);
+    compile_set(entries)
+        .map(|_| ())
+        .map_err(|_| ApiError::BadRequest {
+            errors: vec![
+                "pattern: makes your blocklist too large, simplify or remove other entries."
+                    .to_string(),
+            ],
+        })
+}
+
+pub struct Blocklist {
+    version: i32,
+    patterns: Option<RegexSet>,
+}
+
+impl Blocklist {
+    pub fn is_match(&self, text: &str) -> bool {
+        self.patterns
+            .as_ref()
+            .is_some_and(|patterns| patterns.is_match(text))
+    }
+}
+
+/// Per-user compiled blocklists. An entry is reused for as long as the
+/// `blocklist_version` stored in the user's settings has not changed, which the
+/// database bumps on every change to the user's entries.
+#[derive(Default)]
+pub struct BlocklistCache {
+    blocklists: RwLock<HashMap<Uuid, Arc<Blocklist>>>,
+}
+
+impl BlocklistCache {
+    pub async fn blocklist(
+        &self,
+        db: &PgPool,
+        user_id: Uuid,
+        version: i32,
+    ) -> Result<Arc<Blocklist>, ApiError> {
+        if let Some(blocklist) = self.blocklists.read().unwrap().get(&user_id) {
+            if blocklist.version == version {
+                return Ok(blocklist.clone());
+            }
+        }
+
+        let entries = sqlx::query_as!(
+            BlocklistPattern,
+            r#"select kind, pattern from "blocklist_entries" where user_id = $1"#,
+            user_id
+        )
+        .fetch_all(db)
+        .await?;
+
+        let patterns = compile_set(
+            entries
+                .iter()
+                .map(|entry| (entry.kind.as_str(), entry.pattern.as_str())),
+        )
+        .map_err(|e| ApiError::InternalServer(format!("Failed to compile blocklist: {e}")))?;
+
+        let blocklist = Arc::new(Blocklist { version, patterns });
+        self.blocklists
+            .write()
+            .unwrap()
+            .insert(user_id, blocklist.clone());
+        Ok(blocklist)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blocklist(entries: &[(&str, &str)]) -> Blocklist {
+        Blocklist {
+            version: 0,
+            patterns: compile_set(entries.iter().copied()).unwrap(),
+        }
+    }
+
+    #[test]
+    fn matches_words_whole() {
+        let blocklist = blocklist(&[("word", "ass")]);
+        assert!(blocklist.is_match("you ass!"));
+        assert!(blocklist.is_match("ass"));
+        assert!(!blocklist.is_match("first class"));
+        assert!(!blocklist.is_match("assassin"));
+        assert!(!blocklist.is_match("ass_hat"));
+    }
+
+    #[test]
+    fn folds_case() {
+        let blocklist = blocklist(&[("word", "Spam"), ("phrase", "ÉTÉ indien")]);
+        assert!(blocklist.is_match("SPAM everywhere"));
+        assert!(blocklist.is_match("more spam"));
+        assert!(blocklist.is_match("un été Indien"));
+    }
+
+    #[test]
+    fn escapes_user_patterns() {
+        let blocklist = blocklist(&[("word", "c++"), ("word", "a.b"), ("word", "$$$")]);
+        assert!(blocklist.is_match("I write c++ for fun"));
+        assert!(blocklist.is_match("a.b"));
+        assert!(!blocklist.is_match("axb"));
+        assert!(!blocklist.is_match("cc"));
+        // no word boundary around symbols, so they match inside words too
+        assert!(blocklist.is_match("win$$$now"));
+    }
+
+    #[test]
+    fn matches_phrases_across_whitespace() {
+        let blocklist = blocklist(&[("phrase", "buy  now")]);
+        assert!(blocklist.is_match("Buy now!"));
+        assert!(blocklist.is_match("buy\n\tnow"));
+        assert!(!blocklist.is_match("buynow"));
+        assert!(!blocklist.is_match("buy it now"));
+    }
+
+    #[test]
+    fn matches_regexes_as_written() {
+        let blocklist = blocklist(&[("regex", r"^free\b")]);
+        assert!(blocklist.is_match("Free stuff"));
+        assert!(!blocklist.is_match("stuff for free"));
+        assert!(!blocklist.is_match("freedom"));
+    }
+
+    #[test]
+    fn skips_blank_entries() {
+        assert!(!blocklist(&[("word", "  "), ("phrase", "")]).is_match("anything"));
+        assert!(!blocklist(&[]).is_match("anything"));
+    }
+
+    #[test]
+    fn normalizes_patterns() {
+        assert_eq!(normalize_pattern("word", "  spam ").unwrap(), "spam");
+        assert_eq!(normalize_pattern("regex", " a+ ").unwrap(), " a+ ");
+        assert!(normalize_pattern("phrase", "   ").is_err());
+        assert!(normalize_pattern("regex", "(unclosed").is_err());
+        assert!(normalize_pattern("word", "(unclosed").is_ok());
+    }
+}
@@ -0,0 +1,188 @@
+use crate::{
+    core::models::{ApiError, Claims},
+    modules::blocklist::{
+        models::{BlocklistEntry, BlocklistPattern, CreateEntryBody, UpdateEntryBody},
+        service::{normalize_pattern, validate_blocklist},
+    },
+    ApiContext,
+};
+use axum::{body::Body, http::Response, http::StatusCode, response::IntoResponse};
+use axum::{Extension, Json};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+const MAX_ENTRIES: usize = 200;
+
+/// Locks the user's blocklist until the transaction ends, so entries saved at
+/// the same time are checked against each other, and returns the entries
+/// other than `except`.
+async fn lock_entries(
+    tx: &mut PgConnection,
+    user_id: Uuid,
+    except: Option<Uuid>,
+) -> Result<Vec<BlocklistPattern>, ApiError> {
+    sqlx::query!(
+        r#"select user_id from "message_settings" where user_id = $1 for update"#,
+        user_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let entries = sqlx::query_as!(
+        BlocklistPattern,
+        r#"select kind, pattern from "blocklist_entries"
+        where user_id = $1 and id is distinct from $2"#,
+        user_id,
+        except
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+    Ok(entries)
+}
+
+pub async fn list_entries(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+) -> Result<Json<Vec<BlocklistEntry>>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let result = sqlx::query_as!(
+        BlocklistEntry,
+        r#"select id::text as "entry_id!", kind, pattern, created_at from "blocklist_entries"
+        where user_id = $1 order by created_at"#,
+        user_id
+    )
+    .fetch_all(&ctx.db)
+    .await;
+
+    match result {
+        Ok(entries) => Ok(Json(entries)),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+pub async fn create_entry(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    Json(body): Json<CreateEntryBody>,
+) -> Result<Json<BlocklistEntry>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+    let pattern = normalize_pattern(&body.kind, &body.pattern).map_err(|e| e.into_response())?;
+
+    let mut tx = ctx
+        .db
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e).into_response())?;
+    let others = lock_entries(&mut tx, user_id, None)
+        .await
+        .map_err(|e| e.into_response())?;
+    if others.len() >= MAX_ENTRIES {
+        return Err(ApiError::BadRequest {
+            errors: vec![format!(
+                "blocklist: at most {MAX_ENTRIES} entries are allowed."
+            )],
+        }
+        .into_response());
+    }
+    validate_blocklist(&others, &body.kind, &pattern).map_err(|e| e.into_response())?;
+
+    let result = sqlx::query_as!(
+        BlocklistEntry,
+        r#"insert into "blocklist_entries" (user_id, kind, pattern) values ($1, $2, $3)
+        returning id::text as "entry_id!", kind, pattern, created_at"#,
+        user_id,
+        body.kind,
+        pattern
+    )
+    .fetch_one(&mut *tx)
+    .await;
+
+    match result {
+        Ok(entry) => match tx.commit().await {
+            Ok(_) => Ok(Json(entry)),
+            Err(e) => Err(ApiError::Database(e).into_response()),
+        },
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+pub async fn update_entry(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    entry_id: Uuid,
+    Json(body): Json<UpdateEntryBody>,
+) -> Result<Json<BlocklistEntry>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let mut tx = ctx
+        .db
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e).into_response())?;
+    let others = lock_entries(&mut tx, user_id, Some(entry_id))
+        .await
+        .map_err(|e| e.into_response())?;
+
+    let current = sqlx::query_as!(
+        BlocklistEntry,
+        r#"select id::text as "entry_id!", kind, pattern, created_at from "blocklist_entries"
+        where id = $1 and user_id = $2"#,
+        entry_id,
+        user_id
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| ApiError::Database(e).into_response())?
+    .ok_or_else(|| ApiError::NotFound("Blocklist entry not found".to_string()).into_response())?;
+
+    let kind = body.kind.unwrap_or(current.kind);
+    let pattern = normalize_pattern(&kind, &body.pattern.unwrap_or(current.pattern))
+        .map_err(|e| e.into_response())?;
+    validate_blocklist(&others, &kind, &pattern).map_err(|e| e.into_response())?;
+
+    let result = sqlx::query_as!(
+        BlocklistEntry,
+        r#"update "blocklist_entries" set kind = $3, pattern = $4
+        where id = $1 and user_id = $2
+        returning id::text as "entry_id!", kind, pattern, created_at"#,
+        entry_id,
+        user_id,
+        kind,
+        pattern
+    )
+    .fetch_one(&mut *tx)
+    .await;
+
+    match result {
+        Ok(entry) => match tx.commit().await {
+            Ok(_) => Ok(Json(entry)),
+            Err(e) => Err(ApiError::Database(e).into_response()),
+        },
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+pub async fn delete_entry(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    entry_id: Uuid,
+) -> Result<StatusCode, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let result = sqlx::query!(
+        r#"delete from "blocklist_entries" where id = $1 and user_id = $2"#,
+        entry_id,
+        user_id
+    )
+    .execute(&ctx.db)
+    .await;
+
+    match result {
+        Ok(done) if done.rows_affected() == 0 => {
+            Err(ApiError::NotFound("Blocklist entry not found".to_string()).into_response())
+        }
+        Ok(_) => Ok(StatusCode::NO_CONTENT),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
@@ -0,0 +1,5 @@
+mod blocklist_matcher;
+mod blocklist_service;
+
+pub use blocklist_matcher::*;
+pub use blocklist_service::*;
@@ -0,0 +1,22 @@
+use crate::core::{error_transformer::error_transformer, traits::TransformValidationErrors};
+use validator::ValidationErrors;
+
+pub struct CreateEntryValidationError;
+impl TransformValidationErrors for CreateEntryValidationError {
+    fn new() -> Self {
+        CreateEntryValidationError
+    }
+    fn transform_errors(&self, errors: ValidationErrors) -> Vec<String> {
+        error_transformer(errors)
+    }
+}
+
+pub struct UpdateEntryValidationError;
+impl TransformValidationErrors for UpdateEntryValidationError {
+    fn new() -> Self {
+        UpdateEntryValidationError
+    }
+    fn transform_errors(&self, errors: ValidationErrors) -> Vec<String> {
+        error_transformer(errors)
+    }
+}
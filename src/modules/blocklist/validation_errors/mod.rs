@@ -0,0 +1,3 @@
+mod entry_error;
+
+pub use entry_error::*;
@@ -0,0 +1,50 @@
+use crate::{
+    core::{
+        extractors::{Authorized, CustomQuery, ValidatedBody},
+        models::Claims,
+    },
+    modules::digest::{
+        models::{DigestSettings, UnsubscribeQuery, UpdateDigestBody},
+        service::{find_digest_settings, unsubscribe, unsubscribe_page, update_digest_settings},
+        validation_errors::UpdateDigestValidationError,
+    },
+    ApiContext,
+};
+use axum::{
+    body::Body,
+    response::{Html, Json, Response},
+    Extension,
+};
+
+pub async fn get_digest_settings(
+    ctx: Extension<ApiContext>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<Json<DigestSettings>, Response<Body>> {
+    let settings = find_digest_settings(ctx, claims).await?;
+    Ok(settings)
+}
+
+pub async fn patch_digest_settings(
+    ctx: Extension<ApiContext>,
+    Authorized(claims): Authorized<Claims>,
+    ValidatedBody(body, _): ValidatedBody<UpdateDigestBody, UpdateDigestValidationError>,
+) -> Result<Json<DigestSettings>, Response<Body>> {
+    let settings = update_digest_settings(ctx, claims, Json(body)).await?;
+    Ok(settings)
+}
+
+pub async fn get_unsubscribe_page(
+    ctx: Extension<ApiContext>,
+    CustomQuery(query): CustomQuery<UnsubscribeQuery>,
+) -> Result<Html<String>, Response<Body>> {
+    let page = unsubscribe_page(ctx, query.token).await?;
+    Ok(page)
+}
+
+pub async fn handle_unsubscribe(
+    ctx: Extension<ApiContext>,
+    CustomQuery(query): CustomQuery<UnsubscribeQuery>,
+) -> Result<Html<String>, Response<Body>> {
+    let page = unsubscribe(ctx, query.token).await?;
+    Ok(page)
+}
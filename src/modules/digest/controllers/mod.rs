@@ -0,0 +1,3 @@
+mod digest_api;
+
+pub use digest_api::*;
@@ -0,0 +1,16 @@
+use axum::{routing::get, Router};
+
+use super::controllers::{
+    get_digest_settings, get_unsubscribe_page, handle_unsubscribe, patch_digest_settings,
+};
+
+pub fn digest_routes() -> Router {
+    Router::new().route("/", get(get_digest_settings).patch(patch_digest_settings))
+}
+
+pub fn unsubscribe_routes() -> Router {
+    Router::new().route(
+        "/unsubscribe",
+        get(get_unsubscribe_page).post(handle_unsubscribe),
+    )
+}
@@ -0,0 +1,7 @@
+mod digest_route;
+pub use digest_route::*;
+
+pub mod controllers;
+pub mod models;
+pub mod service;
+pub mod validation_errors;
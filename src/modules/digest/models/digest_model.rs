@@ -0,0 +1,59 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+/// How often a user gets a digest of their unread messages. Weekly digests go
+/// out on Mondays.
+pub const DIGEST_FREQUENCIES: [&str; 3] = ["off", "daily", "weekly"];
+
+/// Digest preferences. `send_hour` is read in `timezone`, the timezone of the
+/// message settings.
+#[derive(Serialize)]
+pub struct DigestSettings {
+    pub frequency: String,
+    pub send_hour: i16,
+    pub timezone: String,
+    pub last_digest_at: Option<NaiveDateTime>,
+}
+
+fn validate_frequency(frequency: &str) -> Result<(), ValidationError> {
+    if !DIGEST_FREQUENCIES.contains(&frequency) {
+        return Err(ValidationError::new("frequency"));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateDigestBody {
+    #[validate(custom(
+        function = "validate_frequency",
+        message = "must be one of off, daily or weekly"
+    ))]
+    pub frequency: Option<String>,
+
+    #[validate(range(min = 0, max = 23))]
+    pub send_hour: Option<i16>,
+}
+
+#[derive(Deserialize)]
+pub struct UnsubscribeQuery {
+    pub token: String,
+}
+
+/// Payload of the job sending one user's digest, covering messages received
+/// after `since`, or over the last period for a first digest.
+#[derive(Serialize, Deserialize)]
+pub struct DigestJob {
+    pub user_id: Uuid,
+    pub since: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize)]
+pub struct DigestPreview {
+    /// `None` for an end-to-end encrypted message.
+    pub body: Option<String>,
+    pub link_name: Option<String>,
+    pub created_at: NaiveDateTime,
+}
@@ -0,0 +1,3 @@
+mod digest_model;
+
+pub use digest_model::*;
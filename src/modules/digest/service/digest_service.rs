@@ -0,0 +1,160 @@
+use crate::{
+    core::{
+        models::{ApiError, Claims},
+        utils::render_template,
+    },
+    modules::digest::models::{DigestSettings, UpdateDigestBody},
+    ApiContext,
+};
+use axum::{
+    body::Body,
+    http::Response,
+    response::{Html, IntoResponse},
+    Extension, Json,
+};
+use sha2::{Digest, Sha256};
+
+/// Days an unsubscribe link works for after its digest went out. Later on,
+/// digests are turned off from the settings.
+const UNSUBSCRIBE_LINK_DAYS: i32 = 30;
+
+/// Digest stored in place of an unsubscribe token. Tokens are 128 random
+/// bits, so an unsalted digest is enough.
+pub fn unsubscribe_hash(token: &str) -> Vec<u8> {
+    Sha256::digest(token).to_vec()
+}
+
+pub async fn find_digest_settings(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+) -> Result<Json<DigestSettings>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let settings = sqlx::query_as!(
+        DigestSettings,
+        r#"select coalesce(d.frequency, 'off') as "frequency!", coalesce(d.send_hour, 8::smallint) as "send_hour!",
+            m.timezone, d.last_digest_at
+        from "message_settings" m left join "digest_settings" d on d.user_id = m.user_id
+        where m.user_id = $1"#,
+        user_id
+    )
+    .fetch_optional(&ctx.db)
+    .await
+    .map_err(|e| ApiError::Database(e).into_response())?
+    .ok_or_else(|| ApiError::NotFound("User not found".to_string()).into_response())?;
+
+    Ok(Json(settings))
+}
+
+pub async fn update_digest_settings(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    Json(body): Json<UpdateDigestBody>,
+) -> Result<Json<DigestSettings>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let settings = sqlx::query_as!(
+        DigestSettings,
+        r#"with updated as (
+            insert into "digest_settings" (user_id, frequency, send_hour)
+            values ($1, coalesce($2, 'off'), coalesce($3::smallint, 8))
+            on conflict (user_id) do update set
+                frequency = coalesce($2, "digest_settings".frequency),
+                send_hour = coalesce($3, "digest_settings".send_hour)
+            returning user_id, frequency, send_hour, last_digest_at
+        )
+        select u.frequency, u.send_hour, m.timezone, u.last_digest_at
+        from updated u join "message_settings" m on m.user_id = u.user_id"#,
+        user_id,
+        body.frequency,
+        body.send_hour
+    )
+    .fetch_optional(&ctx.db)
+    .await
+    .map_err(|e| ApiError::Database(e).into_response())?
+    .ok_or_else(|| ApiError::NotFound("User not found".to_string()).into_response())?;
+
+    Ok(Json(settings))
+}
+
+fn render_page(ctx: &ApiContext, name: &str, token: &str) -> Result<Html<String>, ApiError> {
+    render_template(
+        &ctx.config.template_dir,
+        name,
+        serde_json::json!({ "token": token }),
+    )
+    .map(Html)
+    .map_err(|e| ApiError::InternalServer(e.to_string()))
+}
+
+/// Whether the link was used already, or `Gone` once it expired.
+async fn find_unsubscribe_link(ctx: &ApiContext, token: &str) -> Result<bool, ApiError> {
+    let link = sqlx::query!(
+        r#"select unsubscribed_at is not null as "used!",
+            sent_at <= now() - make_interval(days => $2) as "expired!"
+        from "digest_emails" where unsubscribe_hash = $1"#,
+        unsubscribe_hash(token),
+        UNSUBSCRIBE_LINK_DAYS
+    )
+    .fetch_optional(&ctx.db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Unsubscribe link not found".to_string()))?;
+
+    if link.expired && !link.used {
+        return Err(ApiError::Gone("Unsubscribe link expired".to_string()));
+    }
+    Ok(link.used)
+}
+
+/// Asks for confirmation rather than unsubscribing, so that link scanners
+/// opening the URL do not unsubscribe anyone.
+pub async fn unsubscribe_page(
+    ctx: Extension<ApiContext>,
+    token: String,
+) -> Result<Html<String>, Response<Body>> {
+    let used = find_unsubscribe_link(&ctx, &token)
+        .await
+        .map_err(|e| e.into_response())?;
+    let page = if used {
+        "pages/unsubscribed.html"
+    } else {
+        "pages/unsubscribe.html"
+    };
+
+    render_page(&ctx, page, &token).map_err(|e| e.into_response())
+}
+
+/// Turns digests off for the recipient of the email the token came in. Also
+/// the target of one-click unsubscribe by mail clients. A link works once, so
+/// that an old email can't turn digests off again after they were turned on.
+pub async fn unsubscribe(
+    ctx: Extension<ApiContext>,
+    token: String,
+) -> Result<Html<String>, Response<Body>> {
+    let unsubscribed = sqlx::query_scalar!(
+        r#"with email as (
+            update "digest_emails" set unsubscribed_at = now()
+            where unsubscribe_hash = $1 and unsubscribed_at is null
+                and sent_at > now() - make_interval(days => $2)
+            returning user_id
+        ), settings as (
+            update "digest_settings" d set frequency = 'off'
+            from email where d.user_id = email.user_id
+        )
+        select user_id from email"#,
+        unsubscribe_hash(&token),
+        UNSUBSCRIBE_LINK_DAYS
+    )
+    .fetch_optional(&ctx.db)
+    .await
+    .map_err(|e| ApiError::Database(e).into_response())?;
+
+    // used already, expired or unknown
+    if unsubscribed.is_none() {
+        find_unsubscribe_link(&ctx, &token)
+            .await
+            .map_err(|e| e.into_response())?;
+    }
+
+    render_page(&ctx, "pages/unsubscribed.html", &token).map_err(|e| e.into_response())
+}
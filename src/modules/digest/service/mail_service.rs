@@ -0,0 +1,183 @@
+use anyhow::Error;
+use axum::async_trait;
+use chrono::{Duration, Utc};
+
+use crate::{
+    core::{
+        jobs::enqueue,
+        models::Email,
+        traits::JobHandler,
+        utils::{excerpt, render_template},
+    },
+    modules::digest::{
+        models::{DigestJob, DigestPreview},
+        service::unsubscribe_hash,
+    },
+    ApiContext,
+};
+
+/// Messages previewed in a digest, the newest first.
+const MAX_PREVIEWS: i64 = 5;
+
+/// Characters of a message shown in its preview.
+const PREVIEW_LENGTH: usize = 140;
+
+/// Queues a digest for every user whose send hour it is in their timezone.
+/// Running a few times an hour keeps a short outage from skipping a day,
+/// `last_digest_at` keeps anyone from getting two.
+pub struct QueueDigests;
+
+#[async_trait]
+impl JobHandler for QueueDigests {
+    const KIND: &'static str = "digests.queue";
+
+    const MAX_ATTEMPTS: i32 = 1;
+
+    type Payload = ();
+
+    async fn run(&self, ctx: &ApiContext, _: ()) -> Result<(), Error> {
+        let mut tx = ctx.db.begin().await?;
+        let due = sqlx::query!(
+            r#"with due as (
+                select d.user_id, d.last_digest_at
+                from "digest_settings" d join "message_settings" m on m.user_id = d.user_id
+                where d.frequency <> 'off'
+                    and extract(hour from now() at time zone m.timezone) = d.send_hour
+                    and (d.frequency = 'daily' or extract(isodow from now() at time zone m.timezone) = 1)
+                    and (d.last_digest_at is null or d.last_digest_at < now() - case d.frequency
+                        when 'daily' then interval '20 hours' else interval '6 days' end)
+                for update of d skip locked
+            )
+            update "digest_settings" d set last_digest_at = now()
+            from due where d.user_id = due.user_id
+            returning d.user_id, due.last_digest_at as previous"#
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for digest in due {
+            let job = DigestJob {
+                user_id: digest.user_id,
+                since: digest.previous,
+            };
+            enqueue::<SendDigest>(&mut *tx, &job, None).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+/// Emails one user how many unread messages arrived since their last digest,
+/// with previews of the newest. Nothing is sent when there are none, or when
+/// digests were turned off since the job was queued.
+pub struct SendDigest;
+
+#[async_trait]
+impl JobHandler for SendDigest {
+    const KIND: &'static str = "digests.send";
+
+    const MAX_ATTEMPTS: i32 = 3;
+
+    type Payload = DigestJob;
+
+    async fn run(&self, ctx: &ApiContext, job: DigestJob) -> Result<(), Error> {
+        let Some(user) = sqlx::query!(
+            r#"select u.email, u.name, d.frequency
+            from "users" u join "digest_settings" d on d.user_id = u.id
+            where u.id = $1"#,
+            job.user_id
+        )
+        .fetch_optional(&ctx.db)
+        .await?
+        else {
+            return Ok(());
+        };
+        if user.frequency == "off" {
+            return Ok(());
+        }
+
+        let period = match user.frequency.as_str() {
+            "weekly" => Duration::days(7),
+            _ => Duration::days(1),
+        };
+        let since = job.since.unwrap_or_else(|| Utc::now().naive_utc() - period);
+
+        let counts = sqlx::query!(
+            r#"select count(*) filter (where created_at > $2) as "new_count!", count(*) as "unread_count!"
+            from "messages"
+            where recipient_id = $1 and folder = 'inbox' and read_at is null"#,
+            job.user_id,
+            since
+        )
+        .fetch_one(&ctx.db)
+        .await?;
+        if counts.new_count == 0 {
+            return Ok(());
+        }
+
+        let messages = sqlx::query!(
+            r#"select m.body, m.sealed_body, m.encryption_key_id is not null as "encrypted!", l.name as "link_name?", m.created_at
+            from "messages" m left join "links" l on l.id = m.link_id
+            where m.recipient_id = $1 and m.folder = 'inbox' and m.read_at is null and m.created_at > $2
+            order by m.created_at desc
+            limit $3"#,
+            job.user_id,
+            since,
+            MAX_PREVIEWS
+        )
+        .fetch_all(&ctx.db)
+        .await?;
+        let mut previews = Vec::with_capacity(messages.len());
+        for message in messages {
+            let body = match (message.body, message.sealed_body) {
+                _ if message.encrypted => None,
+                (_, Some(sealed)) => Some(ctx.keyring.open(&ctx.db, job.user_id, &sealed).await?),
+                (body, None) => body,
+            };
+            previews.push(DigestPreview {
+                body: body.map(|body| excerpt(&body, PREVIEW_LENGTH)),
+                link_name: message.link_name,
+                created_at: message.created_at,
+            });
+        }
+
+        let token = hex::encode(rand::random::<[u8; 16]>());
+        let public_url = ctx.config.public_url.trim_end_matches('/');
+        let unsubscribe_url = format!("{public_url}/digest/unsubscribe?token={token}");
+        let context = serde_json::json!({
+            "name": user.name,
+            "frequency": user.frequency,
+            "new_count": counts.new_count,
+            "unread_count": counts.unread_count,
+            "more_count": counts.new_count - previews.len() as i64,
+            "previews": previews,
+            "inbox_url": public_url,
+            "unsubscribe_url": unsubscribe_url,
+        });
+        let dir = &ctx.config.template_dir;
+        let email = Email {
+            to: user.email,
+            subject: render_template(dir, "email/digest_subject.txt", &context)?
+                .trim()
+                .to_string(),
+            html: render_template(dir, "email/digest.html", &context)?,
+            text: render_template(dir, "email/digest.txt", &context)?,
+            unsubscribe_url: Some(unsubscribe_url),
+        };
+
+        // the token only works once the email is out
+        let mut tx = ctx.db.begin().await?;
+        sqlx::query!(
+            r#"insert into "digest_emails" (user_id, unsubscribe_hash, message_count)
+            values ($1, $2, $3)"#,
+            job.user_id,
+            unsubscribe_hash(&token),
+            counts.new_count as i32
+        )
+        .execute(&mut *tx)
+        .await?;
+        ctx.mailer.send(email).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+mod digest_service;
+mod mail_service;
+
+pub use digest_service::*;
+pub use mail_service::*;
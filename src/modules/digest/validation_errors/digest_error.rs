@@ -0,0 +1,12 @@
+use crate::core::{error_transformer::error_transformer, traits::TransformValidationErrors};
+use validator::ValidationErrors;
+
+pub struct UpdateDigestValidationError;
+impl TransformValidationErrors for UpdateDigestValidationError {
+    fn new() -> Self {
+        UpdateDigestValidationError
+    }
+    fn transform_errors(&self, errors: ValidationErrors) -> Vec<String> {
+        error_transformer(errors)
+    }
+}
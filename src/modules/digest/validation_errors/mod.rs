@@ -0,0 +1,3 @@
+mod digest_error;
+
+pub use digest_error::*;
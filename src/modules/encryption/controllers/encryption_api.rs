@@ -0,0 +1,44 @@
+use crate::{
+    core::{
+        extractors::{Authorized, CustomPath, ValidatedBody},
+        models::Claims,
+    },
+    modules::encryption::{
+        models::{EncryptionKey, RegisterKeyBody},
+        service::{list_keys, register_key, revoke_key},
+        validation_errors::RegisterKeyValidationError,
+    },
+    ApiContext,
+};
+use axum::{
+    body::Body,
+    http::StatusCode,
+    response::{Json, Response},
+    Extension,
+};
+use uuid::Uuid;
+
+pub async fn find_keys(
+    ctx: Extension<ApiContext>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<Json<Vec<EncryptionKey>>, Response<Body>> {
+    let keys = list_keys(ctx, claims).await?;
+    Ok(keys)
+}
+
+pub async fn handle_register_key(
+    ctx: Extension<ApiContext>,
+    Authorized(claims): Authorized<Claims>,
+    ValidatedBody(body, _): ValidatedBody<RegisterKeyBody, RegisterKeyValidationError>,
+) -> Result<(StatusCode, Json<EncryptionKey>), Response<Body>> {
+    let key = register_key(ctx, claims, Json(body)).await?;
+    Ok((StatusCode::CREATED, key))
+}
+
+pub async fn handle_revoke_key(
+    ctx: Extension<ApiContext>,
+    CustomPath(key_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<StatusCode, Response<Body>> {
+    revoke_key(ctx, claims, key_id).await
+}
@@ -0,0 +1,3 @@
+mod encryption_api;
+
+pub use encryption_api::*;
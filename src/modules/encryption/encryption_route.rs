@@ -0,0 +1,12 @@
+use axum::{
+    routing::{delete, get},
+    Router,
+};
+
+use super::controllers::{find_keys, handle_register_key, handle_revoke_key};
+
+pub fn encryption_routes() -> Router {
+    Router::new()
+        .route("/", get(find_keys).post(handle_register_key))
+        .route("/:key_id", delete(handle_revoke_key))
+}
@@ -0,0 +1,7 @@
+mod encryption_route;
+pub use encryption_route::*;
+
+pub mod controllers;
+pub mod models;
+pub mod service;
+pub mod validation_errors;
@@ -0,0 +1,36 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+use crate::core::utils::decode_e2e_key;
+
+/// A public key messages were or are encrypted to. Only the key that is not
+/// revoked is offered to senders.
+#[derive(Serialize)]
+pub struct EncryptionKey {
+    pub key_id: String,
+    /// The base64url X25519 public key.
+    pub public_key: String,
+    pub algorithm: &'static str,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+fn validate_public_key(public_key: &str) -> Result<(), ValidationError> {
+    match decode_e2e_key(public_key) {
+        Ok(key) if key != [0; 32] => Ok(()),
+        _ => Err(ValidationError::new("public_key")),
+    }
+}
+
+/// A new key pair made on the recipient's device. Its secret key never
+/// reaches the server.
+#[derive(Debug, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct RegisterKeyBody {
+    #[validate(custom(
+        function = "validate_public_key",
+        message = "must be a base64url X25519 public key"
+    ))]
+    pub public_key: String,
+}
@@ -0,0 +1,3 @@
+mod encryption_model;
+
+pub use encryption_model::*;
@@ -0,0 +1,182 @@
+use crate::{
+    core::{
+        models::{ApiError, Claims},
+        utils::{decode_e2e_key, E2E_ALGORITHM},
+    },
+    modules::encryption::models::{EncryptionKey, RegisterKeyBody},
+    ApiContext,
+};
+use axum::{body::Body, http::Response, http::StatusCode, response::IntoResponse};
+use axum::{Extension, Json};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::NaiveDateTime;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+fn encryption_key(
+    id: Uuid,
+    public_key: &[u8],
+    revoked_at: Option<NaiveDateTime>,
+    created_at: NaiveDateTime,
+) -> EncryptionKey {
+    EncryptionKey {
+        key_id: id.to_string(),
+        public_key: URL_SAFE_NO_PAD.encode(public_key),
+        algorithm: E2E_ALGORITHM,
+        revoked_at,
+        created_at,
+    }
+}
+
+/// Whether the profile link or any named link of the user refuses plaintext.
+pub async fn requires_encryption(db: &PgPool, user_id: Uuid) -> Result<bool, ApiError> {
+    let required = sqlx::query_scalar!(
+        r#"select exists(select 1 from "message_settings" where user_id = $1 and require_encryption)
+            or exists(select 1 from "links" where user_id = $1 and require_encryption) as "required!""#,
+        user_id
+    )
+    .fetch_one(db)
+    .await?;
+    Ok(required)
+}
+
+/// Whether the user has a key senders can encrypt to.
+pub async fn has_active_key(db: &PgPool, user_id: Uuid) -> Result<bool, ApiError> {
+    let active = sqlx::query_scalar!(
+        r#"select exists(select 1 from "encryption_keys" where user_id = $1 and revoked_at is null) as "active!""#,
+        user_id
+    )
+    .fetch_one(db)
+    .await?;
+    Ok(active)
+}
+
+/// Every key of the user, the active one first. Clients look up the secret
+/// key of a message by its `encryption_key_id`.
+pub async fn list_keys(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+) -> Result<Json<Vec<EncryptionKey>>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let result = sqlx::query!(
+        r#"select id, public_key, revoked_at, created_at from "encryption_keys"
+        where user_id = $1
+        order by revoked_at desc nulls first, created_at desc"#,
+        user_id
+    )
+    .fetch_all(&ctx.db)
+    .await;
+
+    match result {
+        Ok(keys) => Ok(Json(
+            keys.into_iter()
+                .map(|key| encryption_key(key.id, &key.public_key, key.revoked_at, key.created_at))
+                .collect(),
+        )),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+/// Makes a new key the one senders encrypt to, revoking the previous one.
+/// Messages already encrypted to it keep naming it.
+pub async fn register_key(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    Json(body): Json<RegisterKeyBody>,
+) -> Result<Json<EncryptionKey>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+    let public_key = decode_e2e_key(&body.public_key).map_err(|_| {
+        ApiError::BadRequest {
+            errors: vec!["public_key: must be a base64url X25519 public key.".to_string()],
+        }
+        .into_response()
+    })?;
+
+    let mut tx = ctx
+        .db
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e).into_response())?;
+    sqlx::query!(
+        r#"update "encryption_keys" set revoked_at = now() where user_id = $1 and revoked_at is null"#,
+        user_id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| ApiError::Database(e).into_response())?;
+
+    let result = sqlx::query!(
+        r#"insert into "encryption_keys" (user_id, public_key) values ($1, $2)
+        returning id, public_key, revoked_at, created_at"#,
+        user_id,
+        public_key.as_slice()
+    )
+    .fetch_one(&mut *tx)
+    .await;
+
+    match result {
+        Ok(key) => match tx.commit().await {
+            Ok(_) => Ok(Json(encryption_key(
+                key.id,
+                &key.public_key,
+                key.revoked_at,
+                key.created_at,
+            ))),
+            Err(e) => Err(ApiError::Database(e).into_response()),
+        },
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(ApiError::Conflict(
+            "Another key was registered at the same time".to_string(),
+        )
+        .into_response()),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+/// Stops senders from encrypting to a key. The active key cannot be revoked
+/// while encryption is required, or every message would be refused.
+pub async fn revoke_key(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    key_id: Uuid,
+) -> Result<StatusCode, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let revoked_at = sqlx::query_scalar!(
+        r#"select revoked_at from "encryption_keys" where id = $1 and user_id = $2"#,
+        key_id,
+        user_id
+    )
+    .fetch_optional(&ctx.db)
+    .await
+    .map_err(|e| ApiError::Database(e).into_response())?
+    .ok_or_else(|| ApiError::NotFound("Key not found".to_string()).into_response())?;
+    if revoked_at.is_some() {
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
+    if requires_encryption(&ctx.db, user_id)
+        .await
+        .map_err(|e| e.into_response())?
+    {
+        return Err(ApiError::Conflict(
+            "Turn off require_encryption in your settings and links before revoking your key"
+                .to_string(),
+        )
+        .into_response());
+    }
+
+    let result = sqlx::query!(
+        r#"update "encryption_keys" set revoked_at = coalesce(revoked_at, now())
+        where id = $1 and user_id = $2"#,
+        key_id,
+        user_id
+    )
+    .execute(&ctx.db)
+    .await;
+
+    match result {
+        Ok(_) => Ok(StatusCode::NO_CONTENT),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
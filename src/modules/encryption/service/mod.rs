@@ -0,0 +1,3 @@
+mod encryption_service;
+
+pub use encryption_service::*;
@@ -0,0 +1,3 @@
+mod encryption_error;
+
+pub use encryption_error::*;
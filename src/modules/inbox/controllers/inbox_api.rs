@@ -0,0 +1,193 @@
+use crate::{
+    core::{
+        extractors::{Authorized, CustomPath, CustomQuery, ValidatedBody},
+        models::{ApiError, Claims},
+    },
+    modules::{
+        inbox::{
+            models::{
+                AnswerBody, CardQuery, InboxMessage, InboxQuery, ReactionSettingsBody, ReplyBody,
+                SearchQuery, SearchResults,
+            },
+            service::{
+                answer_message, block_sender, delete_message, delete_reply, list_messages,
+                message_card, publish_message, read_message, reply_to_message, search_messages,
+                stream_inbox, unblock_sender, update_reactions,
+            },
+            validation_errors::{
+                AnswerValidationError, ReactionSettingsValidationError, ReplyValidationError,
+            },
+        },
+        moderation::{
+            models::{Report, ReportMessageBody},
+            service::report_message,
+            validation_errors::ReportMessageValidationError,
+        },
+    },
+    ApiContext,
+};
+use axum::{
+    body::Body,
+    http::{HeaderMap, StatusCode},
+    response::{
+        sse::{Event, Sse},
+        IntoResponse, Json, Response,
+    },
+    Extension,
+};
+use std::convert::Infallible;
+use tokio_stream::Stream;
+use uuid::Uuid;
+
+pub async fn find_messages(
+    ctx: Extension<ApiContext>,
+    Authorized(claims): Authorized<Claims>,
+    CustomQuery(query): CustomQuery<InboxQuery>,
+) -> Result<Json<Vec<InboxMessage>>, Response<Body>> {
+    let messages = list_messages(ctx, claims, query).await?;
+    Ok(messages)
+}
+
+/// Full-text search of the caller's messages. Refused with 409 on servers
+/// with `MASTER_KEY` set, as sealed bodies are not indexed.
+pub async fn search_inbox(
+    ctx: Extension<ApiContext>,
+    Authorized(claims): Authorized<Claims>,
+    CustomQuery(query): CustomQuery<SearchQuery>,
+) -> Result<Json<SearchResults>, Response<Body>> {
+    let results = search_messages(ctx, claims, query).await?;
+    Ok(results)
+}
+
+/// Opens the caller's inbox event stream, resuming after the event id a
+/// reconnecting `EventSource` sends back in `Last-Event-ID`.
+pub async fn get_inbox_stream(
+    ctx: Extension<ApiContext>,
+    Authorized(claims): Authorized<Claims>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, Response<Body>> {
+    let last_event_id = match headers.get("last-event-id") {
+        Some(value) => Some(
+            value
+                .to_str()
+                .ok()
+                .and_then(|value| value.trim().parse::<i64>().ok())
+                .ok_or_else(|| {
+                    ApiError::BadRequest {
+                        errors: vec!["Last-Event-ID: must be an event id".to_string()],
+                    }
+                    .into_response()
+                })?,
+        ),
+        None => None,
+    };
+    stream_inbox(ctx, claims, last_event_id).await
+}
+
+pub async fn get_message(
+    ctx: Extension<ApiContext>,
+    CustomPath(message_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<Json<InboxMessage>, Response<Body>> {
+    let message = read_message(ctx, claims, message_id).await?;
+    Ok(message)
+}
+
+pub async fn handle_delete_message(
+    ctx: Extension<ApiContext>,
+    CustomPath(message_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<StatusCode, Response<Body>> {
+    delete_message(ctx, claims, message_id).await
+}
+
+pub async fn handle_block_sender(
+    ctx: Extension<ApiContext>,
+    CustomPath(message_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<StatusCode, Response<Body>> {
+    block_sender(ctx, claims, message_id).await
+}
+
+pub async fn handle_unblock_sender(
+    ctx: Extension<ApiContext>,
+    CustomPath(message_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<StatusCode, Response<Body>> {
+    unblock_sender(ctx, claims, message_id).await
+}
+
+pub async fn handle_report_message(
+    ctx: Extension<ApiContext>,
+    CustomPath(message_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+    ValidatedBody(body, _): ValidatedBody<ReportMessageBody, ReportMessageValidationError>,
+) -> Result<(StatusCode, Json<Report>), Response<Body>> {
+    let report = report_message(ctx, claims, message_id, Json(body)).await?;
+    Ok((StatusCode::CREATED, report))
+}
+
+pub async fn handle_reply(
+    ctx: Extension<ApiContext>,
+    CustomPath(message_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+    ValidatedBody(body, _): ValidatedBody<ReplyBody, ReplyValidationError>,
+) -> Result<Json<InboxMessage>, Response<Body>> {
+    let message = reply_to_message(ctx, claims, message_id, Json(body)).await?;
+    Ok(message)
+}
+
+pub async fn handle_delete_reply(
+    ctx: Extension<ApiContext>,
+    CustomPath(message_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<StatusCode, Response<Body>> {
+    delete_reply(ctx, claims, message_id).await
+}
+
+pub async fn handle_answer(
+    ctx: Extension<ApiContext>,
+    CustomPath(message_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+    ValidatedBody(body, _): ValidatedBody<AnswerBody, AnswerValidationError>,
+) -> Result<Json<InboxMessage>, Response<Body>> {
+    let message = answer_message(ctx, claims, message_id, Json(body)).await?;
+    Ok(message)
+}
+
+pub async fn handle_publish(
+    ctx: Extension<ApiContext>,
+    CustomPath(message_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<Json<InboxMessage>, Response<Body>> {
+    let message = publish_message(ctx, claims, message_id, true).await?;
+    Ok(message)
+}
+
+pub async fn handle_unpublish(
+    ctx: Extension<ApiContext>,
+    CustomPath(message_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<Json<InboxMessage>, Response<Body>> {
+    let message = publish_message(ctx, claims, message_id, false).await?;
+    Ok(message)
+}
+
+pub async fn handle_update_reactions(
+    ctx: Extension<ApiContext>,
+    CustomPath(message_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+    ValidatedBody(body, _): ValidatedBody<ReactionSettingsBody, ReactionSettingsValidationError>,
+) -> Result<Json<InboxMessage>, Response<Body>> {
+    let message = update_reactions(ctx, claims, message_id, Json(body)).await?;
+    Ok(message)
+}
+
+pub async fn get_message_card(
+    ctx: Extension<ApiContext>,
+    CustomPath(message_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+    CustomQuery(query): CustomQuery<CardQuery>,
+) -> Result<Response<Body>, Response<Body>> {
+    message_card(ctx, claims, message_id, query).await
+}
@@ -0,0 +1,3 @@
+mod inbox_api;
+
+pub use inbox_api::*;
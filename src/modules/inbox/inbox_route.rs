@@ -0,0 +1,36 @@
+use axum::{
+    routing::{get, post, put},
+    Router,
+};
+
+use super::controllers::{
+    find_messages, get_inbox_stream, get_message, get_message_card, handle_answer,
+    handle_block_sender, handle_delete_message, handle_delete_reply, handle_publish, handle_reply,
+    handle_report_message, handle_unblock_sender, handle_unpublish, handle_update_reactions,
+    search_inbox,
+};
+
+pub fn inbox_routes() -> Router {
+    Router::new()
+        .route("/", get(find_messages))
+        .route("/search", get(search_inbox))
+        .route("/stream", get(get_inbox_stream))
+        .route(
+            "/:message_id",
+            get(get_message).delete(handle_delete_message),
+        )
+        .route(
+            "/:message_id/block",
+            post(handle_block_sender).delete(handle_unblock_sender),
+        )
+        .route(
+            "/:message_id/reply",
+            put(handle_reply).delete(handle_delete_reply),
+        )
+        .route("/:message_id/answer", put(handle_answer))
+        .route("/:message_id/publish", post(handle_publish))
+        .route("/:message_id/unpublish", post(handle_unpublish))
+        .route("/:message_id/reactions", put(handle_update_reactions))
+        .route("/:message_id/report", post(handle_report_message))
+        .route("/:message_id/card", get(get_message_card))
+}
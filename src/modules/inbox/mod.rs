@@ -0,0 +1,7 @@
+mod inbox_route;
+pub use inbox_route::*;
+
+pub mod controllers;
+pub mod models;
+pub mod service;
+pub mod validation_errors;
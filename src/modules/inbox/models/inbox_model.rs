@@ -0,0 +1,144 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::core::card::{CardRatio, CardTheme};
+use validator::{Validate, ValidationError};
+
+#[derive(Serialize)]
+pub struct InboxMessage {
+    pub message_id: String,
+    /// An envelope to decrypt with the secret key of `encryption_key_id` when set.
+    pub body: String,
+    /// Set while `body` is still sealed at rest, see `open_messages`.
+    #[serde(skip_serializing)]
+    pub sealed_body: Option<Vec<u8>>,
+    pub encryption_key_id: Option<String>,
+    pub folder: String,
+    /// The named link the message came through, `None` for the profile link.
+    pub link_id: Option<String>,
+    /// Highest of `scores`.
+    pub score: f32,
+    /// Score of each classifier by name, eg: `spam`, `toxicity`.
+    pub scores: serde_json::Value,
+    pub read_at: Option<NaiveDateTime>,
+    pub reply: Option<String>,
+    pub reply_visibility: Option<String>,
+    pub replied_at: Option<NaiveDateTime>,
+    /// Set while the message and its answer are on the public profile.
+    pub published_at: Option<NaiveDateTime>,
+    pub reactions_enabled: bool,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Deserialize)]
+pub struct InboxQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub unread: Option<bool>,
+    /// `inbox` (default) or `filtered`.
+    pub folder: Option<String>,
+    /// Only messages received through this named link.
+    pub link_id: Option<Uuid>,
+}
+
+/// A search of the plaintext bodies, refused once `MASTER_KEY` is set, see
+/// `search_messages`.
+#[derive(Deserialize)]
+pub struct SearchQuery {
+    /// Words to find, all of them. `"quoted words"` match as a phrase and a
+    /// word ending in `*` matches as a prefix.
+    pub q: String,
+    pub limit: Option<i64>,
+    /// `next_cursor` of the previous page.
+    pub cursor: Option<String>,
+    /// Only read messages when true, only unread ones when false.
+    pub read: Option<bool>,
+    /// `inbox` (default) or `filtered`.
+    pub folder: Option<String>,
+    /// Only messages received through this named link.
+    pub link_id: Option<Uuid>,
+    /// Only messages received on or after this day.
+    pub from: Option<NaiveDate>,
+    /// Only messages received on or before this day.
+    pub to: Option<NaiveDate>,
+}
+
+/// A message matching a search, newest first.
+#[derive(Serialize)]
+pub struct SearchHit {
+    pub message_id: String,
+    pub body: String,
+    /// Fragments of `body` around the matches as HTML, each match in `<mark>`.
+    pub headline: String,
+    pub folder: String,
+    pub link_id: Option<String>,
+    pub read_at: Option<NaiveDateTime>,
+    pub reply: Option<String>,
+    pub replied_at: Option<NaiveDateTime>,
+    pub published_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Serialize)]
+pub struct SearchResults {
+    pub messages: Vec<SearchHit>,
+    /// Passed back as `cursor` for the next page, `None` on the last one.
+    pub next_cursor: Option<String>,
+}
+
+fn validate_visibility(visibility: &str) -> Result<(), ValidationError> {
+    match visibility {
+        "private" | "public" => Ok(()),
+        _ => Err(ValidationError::new("visibility")),
+    }
+}
+
+/// A reply the sender can read through their claim code.
+#[derive(Debug, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct ReplyBody {
+    #[validate(length(min = 1, max = 1000))]
+    pub body: String,
+
+    /// `private` (default) or `public`.
+    #[validate(custom(
+        function = "validate_visibility",
+        message = "must be one of private or public"
+    ))]
+    pub visibility: Option<String>,
+}
+
+/// A public reply, see `ReplyBody`.
+#[derive(Debug, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct AnswerBody {
+    #[validate(length(min = 1, max = 1000))]
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct ReactionSettingsBody {
+    pub enabled: bool,
+}
+
+#[derive(Deserialize)]
+pub struct CardQuery {
+    /// `light` (default), `dark`, `sunset` or `ocean`.
+    pub theme: Option<CardTheme>,
+    /// `square`, `post` (default), `story` or `landscape`.
+    pub ratio: Option<CardRatio>,
+    /// Whether to show the reply under the message, when there is one.
+    pub answer: Option<bool>,
+}
+
+/// A change to a user's inbox, as recorded by the database and announced on
+/// the `inbox_events` channel. `kind` is `message` or `read`.
+#[derive(Deserialize, Clone, Debug)]
+pub struct InboxEvent {
+    pub id: i64,
+    pub user_id: Uuid,
+    pub kind: String,
+    pub payload: serde_json::Value,
+}
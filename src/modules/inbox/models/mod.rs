@@ -0,0 +1,3 @@
+mod inbox_model;
+
+pub use inbox_model::*;
@@ -0,0 +1,93 @@
+use crate::{
+    core::{
+        card::{render_card, CardContent},
+        models::{ApiError, Claims},
+    },
+    modules::inbox::models::CardQuery,
+    ApiContext,
+};
+use axum::{
+    body::Body,
+    http::{header, Response},
+    response::IntoResponse,
+    Extension,
+};
+use uuid::Uuid;
+
+/// Renders one of the caller's messages, and by default its reply, as a PNG
+/// card to share, signed with the caller's profile address.
+pub async fn message_card(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    message_id: Uuid,
+    query: CardQuery,
+) -> Result<Response<Body>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let message = sqlx::query!(
+        r#"select m.body, m.sealed_body, m.reply, m.encryption_key_id is not null as "encrypted!", u.profile_link
+        from "messages" m
+        join "users" u on u.id = m.recipient_id
+        where m.id = $1 and m.recipient_id = $2"#,
+        message_id,
+        user_id
+    )
+    .fetch_optional(&ctx.db)
+    .await
+    .map_err(|e| ApiError::Database(e).into_response())?
+    .ok_or_else(|| ApiError::NotFound("Message not found".to_string()).into_response())?;
+    if message.encrypted {
+        return Err(ApiError::BadRequest {
+            errors: vec!["Encrypted messages cannot be shared as a card".to_string()],
+        }
+        .into_response());
+    }
+
+    let question = match (message.body, message.sealed_body) {
+        (_, Some(sealed)) => ctx
+            .keyring
+            .open(&ctx.db, user_id, &sealed)
+            .await
+            .map_err(|e| e.into_response())?,
+        (body, None) => body.unwrap_or_default(),
+    };
+
+    let host = ctx
+        .config
+        .public_url
+        .split_once("://")
+        .map_or(ctx.config.public_url.as_str(), |(_, host)| host)
+        .trim_end_matches('/')
+        .to_string();
+    let footer = match message.profile_link {
+        Some(profile_link) => format!("Ask me anything at {host}/u/{profile_link}"),
+        None => host,
+    };
+    let answer = message.reply.filter(|_| query.answer.unwrap_or(true));
+    let theme = query.theme.unwrap_or_default();
+    let ratio = query.ratio.unwrap_or_default();
+
+    let card = tokio::task::spawn_blocking(move || {
+        render_card(
+            &CardContent {
+                question: &question,
+                answer: answer.as_deref(),
+                footer: &footer,
+            },
+            theme,
+            ratio,
+        )
+    })
+    .await
+    .map_err(|e| ApiError::InternalServer(e.to_string()).into_response())?
+    .map_err(|e| e.into_response())?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "image/png"),
+            (header::CACHE_CONTROL, "private, no-cache"),
+        ],
+        card,
+    )
+        .into_response())
+}
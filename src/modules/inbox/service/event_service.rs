@@ -0,0 +1,323 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    convert::Infallible,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::{
+    core::{
+        models::{ApiError, Claims},
+        traits::JobHandler,
+    },
+    modules::inbox::models::InboxEvent,
+    ApiContext,
+};
+use anyhow::Error;
+use axum::{
+    async_trait,
+    body::Body,
+    http::Response,
+    response::{
+        sse::{Event, KeepAlive},
+        IntoResponse, Sse,
+    },
+    Extension,
+};
+use sqlx::{postgres::PgListener, PgPool};
+use tokio::sync::{broadcast, mpsc};
+use tokio_stream::{wrappers::ReceiverStream, Stream};
+use uuid::Uuid;
+
+const CHANNEL: &str = "inbox_events";
+
+/// Events a stream replays at once, from the database after a reconnect or
+/// when it fell behind the live feed.
+const REPLAY_LIMIT: i64 = 500;
+
+/// Ids are taken when an event is inserted but it only shows once committed,
+/// so a lower id can appear after a higher one was sent. Replays look this
+/// many seconds back from the last event sent to pick those up.
+const LOOK_BACK_SECONDS: f64 = 10.0;
+
+/// Ids a stream remembers having sent, so what the look-back finds again is
+/// not sent twice.
+const SENT_MEMORY: usize = 1000;
+
+/// Fans the events this instance hears on the `inbox_events` channel out to
+/// the open streams, and counts streams per user. The count is per instance,
+/// so `SSE_MAX_CONNECTIONS` applies on each instance separately.
+pub struct InboxEvents {
+    sender: broadcast::Sender<Arc<InboxEvent>>,
+    connections: Mutex<HashMap<Uuid, u32>>,
+    max_connections: u32,
+}
+
+/// Holds one of a user's stream slots until dropped with the stream.
+struct StreamSlot {
+    hub: Arc<InboxEvents>,
+    user_id: Uuid,
+}
+
+impl Drop for StreamSlot {
+    fn drop(&mut self) {
+        let mut connections = self.hub.connections.lock().unwrap();
+        if let Some(count) = connections.get_mut(&self.user_id) {
+            *count -= 1;
+            if *count == 0 {
+                connections.remove(&self.user_id);
+            }
+        }
+    }
+}
+
+impl InboxEvents {
+    pub fn new(max_connections: u32) -> Self {
+        InboxEvents {
+            sender: broadcast::channel(1024).0,
+            connections: Mutex::default(),
+            max_connections,
+        }
+    }
+
+    fn connect(self: &Arc<Self>, user_id: Uuid) -> Result<StreamSlot, ApiError> {
+        let mut connections = self.connections.lock().unwrap();
+        let count = connections.entry(user_id).or_default();
+        if *count >= self.max_connections {
+            return Err(ApiError::TooManyRequests(format!(
+                "At most {} inbox streams can be open at once",
+                self.max_connections
+            )));
+        }
+        *count += 1;
+        Ok(StreamSlot {
+            hub: self.clone(),
+            user_id,
+        })
+    }
+}
+
+/// Events after `after`, and those up to `LOOK_BACK_SECONDS` older than it that
+/// committed late, leaving out the ids in `sent`.
+async fn events_after(
+    db: &PgPool,
+    user_id: Option<Uuid>,
+    after: i64,
+    sent: &[i64],
+) -> Result<Vec<InboxEvent>, sqlx::Error> {
+    sqlx::query_as!(
+        InboxEvent,
+        r#"select id, user_id, kind, payload from "inbox_events"
+        where ($2::uuid is null or user_id = $2) and id <> all($4)
+            and (id > $1 or id < $1 and created_at > (
+                select created_at - make_interval(secs => $5) from "inbox_events" where id = $1
+            ))
+        order by id limit $3"#,
+        after,
+        user_id,
+        REPLAY_LIMIT,
+        sent,
+        LOOK_BACK_SECONDS
+    )
+    .fetch_all(db)
+    .await
+}
+
+/// Ids of the events a stream or catch-up sent, keeping the most recent `SENT_MEMORY`.
+#[derive(Default)]
+struct SentEvents(BTreeSet<i64>);
+
+impl SentEvents {
+    /// Records an id, false when it was sent already.
+    fn insert(&mut self, id: i64) -> bool {
+        if !self.0.insert(id) {
+            return false;
+        }
+        if self.0.len() > SENT_MEMORY {
+            self.0.pop_first();
+        }
+        true
+    }
+
+    fn ids(&self) -> Vec<i64> {
+        self.0.iter().copied().collect()
+    }
+}
+
+/// Listens on the `inbox_events` channel for as long as the server runs, so
+/// events from every instance reach the streams open on this one. Events
+/// missed while the connection was down are read back from the table.
+pub fn listen_inbox_events(database_url: String, db: PgPool, hub: Arc<InboxEvents>) {
+    tokio::spawn(async move {
+        let mut last_id = sqlx::query_scalar!(r#"select max(id) from "inbox_events""#)
+            .fetch_one(&db)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or(0);
+
+        loop {
+            let mut listener = match PgListener::connect(&database_url).await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    tracing::warn!("Failed to connect the inbox event listener: {e}");
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    continue;
+                }
+            };
+            if let Err(e) = listener.listen(CHANNEL).await {
+                tracing::warn!("Failed to listen for inbox events: {e}");
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                continue;
+            }
+
+            // catch up on what was committed while nobody was listening, the
+            // streams drop what they already sent
+            let mut caught_up = SentEvents::default();
+            loop {
+                match events_after(&db, None, last_id, &caught_up.ids()).await {
+                    Ok(events) => {
+                        let done = (events.len() as i64) < REPLAY_LIMIT;
+                        for event in events {
+                            caught_up.insert(event.id);
+                            last_id = last_id.max(event.id);
+                            let _ = hub.sender.send(Arc::new(event));
+                        }
+                        if done {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to read missed inbox events: {e}");
+                        break;
+                    }
+                }
+            }
+
+            loop {
+                match listener.try_recv().await {
+                    Ok(Some(notification)) => {
+                        match serde_json::from_str::<InboxEvent>(notification.payload()) {
+                            Ok(event) => {
+                                last_id = last_id.max(event.id);
+                                let _ = hub.sender.send(Arc::new(event));
+                            }
+                            Err(e) => tracing::warn!("Invalid inbox event: {e}"),
+                        }
+                    }
+                    Ok(None) => break,
+                    Err(e) => {
+                        tracing::warn!("Lost the inbox event listener: {e}");
+                        break;
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Forgets events older than a day, streams can no longer resume from them.
+pub struct PruneInboxEvents;
+
+#[async_trait]
+impl JobHandler for PruneInboxEvents {
+    const KIND: &'static str = "inbox_events.prune";
+
+    type Payload = ();
+
+    async fn run(&self, ctx: &ApiContext, _: ()) -> Result<(), Error> {
+        sqlx::query!(r#"delete from "inbox_events" where created_at < now() - interval '1 day'"#)
+            .execute(&ctx.db)
+            .await?;
+        Ok(())
+    }
+}
+
+fn sse_event(event: &InboxEvent) -> Event {
+    Event::default()
+        .id(event.id.to_string())
+        .event(&event.kind)
+        .data(event.payload.to_string())
+}
+
+/// Streams new messages and read receipts of the caller's inbox. With
+/// `last_event_id`, events after it are replayed first, so a client that
+/// reconnects misses nothing from the last day. Events from the few seconds
+/// before it are replayed as well in case they committed late, a client can
+/// tell the ones it already has by their id.
+pub async fn stream_inbox(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    last_event_id: Option<i64>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+    let slot = ctx
+        .inbox_events
+        .connect(user_id)
+        .map_err(|e| e.into_response())?;
+
+    let db = ctx.db.clone();
+    let mut last_sent = match last_event_id {
+        Some(id) => id,
+        None => sqlx::query_scalar!(
+            r#"select coalesce(max(id), 0) as "id!" from "inbox_events" where user_id = $1"#,
+            user_id
+        )
+        .fetch_one(&db)
+        .await
+        .map_err(|e| ApiError::Database(e).into_response())?,
+    };
+
+    // subscribe before replaying, so nothing committed in between is missed
+    let mut live = ctx.inbox_events.sender.subscribe();
+    let (tx, rx) = mpsc::channel(32);
+
+    tokio::spawn(async move {
+        let _slot = slot;
+        let mut sent = SentEvents::default();
+        let mut replay = true;
+
+        loop {
+            if replay {
+                let events = match events_after(&db, Some(user_id), last_sent, &sent.ids()).await {
+                    Ok(events) => events,
+                    Err(e) => {
+                        tracing::warn!("Failed to replay inbox events: {e}");
+                        return;
+                    }
+                };
+                replay = events.len() as i64 == REPLAY_LIMIT;
+                for event in events {
+                    last_sent = last_sent.max(event.id);
+                    sent.insert(event.id);
+                    if tx.send(Ok(sse_event(&event))).await.is_err() {
+                        return;
+                    }
+                }
+                continue;
+            }
+
+            let event = tokio::select! {
+                _ = tx.closed() => return,
+                event = live.recv() => event,
+            };
+            match event {
+                Ok(event) if event.user_id == user_id && sent.insert(event.id) => {
+                    last_sent = last_sent.max(event.id);
+                    if tx.send(Ok(sse_event(&event))).await.is_err() {
+                        return;
+                    }
+                }
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(_)) => replay = true,
+                Err(broadcast::error::RecvError::Closed) => return,
+            }
+        }
+    });
+
+    Ok(Sse::new(ReceiverStream::new(rx)).keep_alive(
+        KeepAlive::new()
+            .interval(Duration::from_secs(ctx.config.sse_heartbeat_seconds.into()))
+            .text("heartbeat"),
+    ))
+}
@@ -0,0 +1,359 @@
+use crate::{
+    core::models::{ApiError, Claims},
+    modules::inbox::models::{
+        AnswerBody, InboxMessage, InboxQuery, ReactionSettingsBody, ReplyBody,
+    },
+    ApiContext,
+};
+use axum::{body::Body, http::Response, http::StatusCode, response::IntoResponse};
+use axum::{Extension, Json};
+use uuid::Uuid;
+
+/// Decrypts the bodies sealed at rest, so nothing past this service ever
+/// sees them sealed.
+pub async fn open_messages(
+    ctx: &ApiContext,
+    user_id: Uuid,
+    messages: &mut [InboxMessage],
+) -> Result<(), ApiError> {
+    for message in messages {
+        if let Some(sealed) = message.sealed_body.take() {
+            message.body = ctx.keyring.open(&ctx.db, user_id, &sealed).await?;
+        }
+    }
+    Ok(())
+}
+
+pub async fn list_messages(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    query: InboxQuery,
+) -> Result<Json<Vec<InboxMessage>>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+    let limit = query.limit.unwrap_or(50).clamp(1, 100);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let result = sqlx::query_as!(
+        InboxMessage,
+        r#"select id::text as "message_id!", coalesce(body, '') as "body!", sealed_body, encryption_key_id::text, folder, link_id::text, score, scores, read_at, reply, reply_visibility, replied_at,
+            published_at, reactions_enabled,
+            created_at from "messages"
+        where recipient_id = $1 and folder = coalesce($5, 'inbox')
+            and ($2::bool is not true or read_at is null)
+            and ($6::uuid is null or link_id = $6)
+        order by created_at desc
+        limit $3 offset $4"#,
+        user_id,
+        query.unread,
+        limit,
+        offset,
+        query.folder,
+        query.link_id
+    )
+    .fetch_all(&ctx.db)
+    .await;
+
+    match result {
+        Ok(mut messages) => {
+            open_messages(&ctx, user_id, &mut messages)
+                .await
+                .map_err(|e| e.into_response())?;
+            Ok(Json(messages))
+        }
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+/// Returns a single message and marks it as read the first time it is opened.
+pub async fn read_message(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    message_id: Uuid,
+) -> Result<Json<InboxMessage>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let result = sqlx::query_as!(
+        InboxMessage,
+        r#"update "messages" set read_at = coalesce(read_at, now())
+        where id = $1 and recipient_id = $2
+        returning id::text as "message_id!", coalesce(body, '') as "body!", sealed_body, encryption_key_id::text, folder, link_id::text, score, scores, read_at, reply, reply_visibility, replied_at,
+            published_at, reactions_enabled,
+            created_at"#,
+        message_id,
+        user_id
+    )
+    .fetch_optional(&ctx.db)
+    .await;
+
+    match result {
+        Ok(Some(mut message)) => {
+            open_messages(&ctx, user_id, std::slice::from_mut(&mut message))
+                .await
+                .map_err(|e| e.into_response())?;
+            Ok(Json(message))
+        }
+        Ok(None) => Err(ApiError::NotFound("Message not found".to_string()).into_response()),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+pub async fn delete_message(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    message_id: Uuid,
+) -> Result<StatusCode, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let result = sqlx::query!(
+        r#"delete from "messages" where id = $1 and recipient_id = $2"#,
+        message_id,
+        user_id
+    )
+    .execute(&ctx.db)
+    .await;
+
+    match result {
+        Ok(done) if done.rows_affected() == 0 => {
+            Err(ApiError::NotFound("Message not found".to_string()).into_response())
+        }
+        Ok(_) => Ok(StatusCode::NO_CONTENT),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+async fn message_fingerprint(
+    ctx: &ApiContext,
+    user_id: Uuid,
+    message_id: Uuid,
+) -> Result<Vec<u8>, ApiError> {
+    sqlx::query_scalar!(
+        r#"select sender_fingerprint from "messages" where id = $1 and recipient_id = $2"#,
+        message_id,
+        user_id
+    )
+    .fetch_optional(&ctx.db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Message not found".to_string()))?
+    .ok_or_else(|| ApiError::BadRequest {
+        errors: vec!["This message has no sender fingerprint to block".to_string()],
+    })
+}
+
+/// Blocks future submissions from whoever sent `message_id`, for this recipient only.
+pub async fn block_sender(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    message_id: Uuid,
+) -> Result<StatusCode, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+    let fingerprint = message_fingerprint(&ctx, user_id, message_id)
+        .await
+        .map_err(|e| e.into_response())?;
+
+    let result = sqlx::query!(
+        r#"insert into "sender_blocks" (block_id, recipient_id, fingerprint) values ($1, $2, $3)
+        on conflict (recipient_id, fingerprint) do update set created_at = now()"#,
+        Uuid::new_v4(),
+        user_id,
+        fingerprint
+    )
+    .execute(&ctx.db)
+    .await;
+
+    match result {
+        Ok(_) => Ok(StatusCode::NO_CONTENT),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+pub async fn unblock_sender(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    message_id: Uuid,
+) -> Result<StatusCode, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+    let fingerprint = message_fingerprint(&ctx, user_id, message_id)
+        .await
+        .map_err(|e| e.into_response())?;
+
+    let result = sqlx::query!(
+        r#"delete from "sender_blocks" where recipient_id = $1 and block_id in (
+            select block_id from "sender_blocks" where recipient_id = $1 and fingerprint = $2
+        )"#,
+        user_id,
+        fingerprint
+    )
+    .execute(&ctx.db)
+    .await;
+
+    match result {
+        Ok(done) if done.rows_affected() == 0 => {
+            Err(ApiError::NotFound("Sender is not blocked".to_string()).into_response())
+        }
+        Ok(_) => Ok(StatusCode::NO_CONTENT),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+/// Sets or replaces the reply to a message. Making the reply private
+/// unpublishes it.
+pub async fn reply_to_message(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    message_id: Uuid,
+    Json(body): Json<ReplyBody>,
+) -> Result<Json<InboxMessage>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let result = sqlx::query_as!(
+        InboxMessage,
+        r#"update "messages" set reply = $3, reply_visibility = coalesce($4, 'private'), replied_at = now(),
+            published_at = case when coalesce($4, 'private') = 'public' then published_at end
+        where id = $1 and recipient_id = $2
+        returning id::text as "message_id!", coalesce(body, '') as "body!", sealed_body, encryption_key_id::text, folder, link_id::text, score, scores, read_at, reply, reply_visibility,
+            replied_at, published_at, reactions_enabled,
+            created_at"#,
+        message_id,
+        user_id,
+        body.body.trim(),
+        body.visibility
+    )
+    .fetch_optional(&ctx.db)
+    .await;
+
+    match result {
+        Ok(Some(mut message)) => {
+            open_messages(&ctx, user_id, std::slice::from_mut(&mut message))
+                .await
+                .map_err(|e| e.into_response())?;
+            Ok(Json(message))
+        }
+        Ok(None) => Err(ApiError::NotFound("Message not found".to_string()).into_response()),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+pub async fn delete_reply(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    message_id: Uuid,
+) -> Result<StatusCode, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let result = sqlx::query!(
+        r#"update "messages" set reply = null, reply_visibility = null, replied_at = null, published_at = null
+        where id = $1 and recipient_id = $2 and reply is not null"#,
+        message_id,
+        user_id
+    )
+    .execute(&ctx.db)
+    .await;
+
+    match result {
+        Ok(done) if done.rows_affected() == 0 => {
+            Err(ApiError::NotFound("Reply not found".to_string()).into_response())
+        }
+        Ok(_) => Ok(StatusCode::NO_CONTENT),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+/// A public reply, ready to be published.
+pub async fn answer_message(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    message_id: Uuid,
+    Json(body): Json<AnswerBody>,
+) -> Result<Json<InboxMessage>, Response<Body>> {
+    let reply = ReplyBody {
+        body: body.body,
+        visibility: Some("public".to_string()),
+    };
+    reply_to_message(ctx, claims, message_id, Json(reply)).await
+}
+
+/// Shows or hides the question and its answer on the public profile.
+pub async fn publish_message(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    message_id: Uuid,
+    published: bool,
+) -> Result<Json<InboxMessage>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let result = sqlx::query_as!(
+        InboxMessage,
+        r#"update "messages" set published_at = case when $3 then coalesce(published_at, now()) end
+        where id = $1 and recipient_id = $2
+        returning id::text as "message_id!", coalesce(body, '') as "body!", sealed_body, encryption_key_id::text, folder, link_id::text, score, scores, read_at, reply, reply_visibility,
+            replied_at, published_at, reactions_enabled,
+            created_at"#,
+        message_id,
+        user_id,
+        published
+    )
+    .fetch_optional(&ctx.db)
+    .await;
+
+    match result {
+        Ok(Some(mut message)) => {
+            open_messages(&ctx, user_id, std::slice::from_mut(&mut message))
+                .await
+                .map_err(|e| e.into_response())?;
+            Ok(Json(message))
+        }
+        Ok(None) => Err(ApiError::NotFound("Message not found".to_string()).into_response()),
+        Err(sqlx::Error::Database(e))
+            if e.constraint() == Some("messages_published_encrypted_check") =>
+        {
+            Err(ApiError::BadRequest {
+                errors: vec!["Encrypted messages cannot be published".to_string()],
+            }
+            .into_response())
+        }
+        Err(sqlx::Error::Database(e))
+            if e.constraint() == Some("messages_published_reply_check") =>
+        {
+            Err(ApiError::BadRequest {
+                errors: vec!["Answer the message before publishing it".to_string()],
+            }
+            .into_response())
+        }
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+/// Turns reactions on an answer on or off. Existing reactions are kept but
+/// hidden while reactions are off.
+pub async fn update_reactions(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    message_id: Uuid,
+    Json(body): Json<ReactionSettingsBody>,
+) -> Result<Json<InboxMessage>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let result = sqlx::query_as!(
+        InboxMessage,
+        r#"update "messages" set reactions_enabled = $3
+        where id = $1 and recipient_id = $2
+        returning id::text as "message_id!", coalesce(body, '') as "body!", sealed_body, encryption_key_id::text, folder, link_id::text, score, scores, read_at, reply, reply_visibility,
+            replied_at, published_at, reactions_enabled, created_at"#,
+        message_id,
+        user_id,
+        body.enabled
+    )
+    .fetch_optional(&ctx.db)
+    .await;
+
+    match result {
+        Ok(Some(mut message)) => {
+            open_messages(&ctx, user_id, std::slice::from_mut(&mut message))
+                .await
+                .map_err(|e| e.into_response())?;
+            Ok(Json(message))
+        }
+        Ok(None) => Err(ApiError::NotFound("Message not found".to_string()).into_response()),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
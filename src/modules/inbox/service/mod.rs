@@ -0,0 +1,9 @@
+mod card_service;
+mod event_service;
+mod inbox_service;
+mod search_service;
+
+pub use card_service::*;
+pub use event_service::*;
+pub use inbox_service::*;
+pub use search_service::*;
@@ -0,0 +1,227 @@
+use crate::{
+    core::models::{ApiError, Claims},
+    modules::inbox::models::{SearchHit, SearchQuery, SearchResults},
+    ApiContext,
+};
+use axum::{body::Body, http::Response, response::IntoResponse};
+use axum::{Extension, Json};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, NaiveDateTime};
+use uuid::Uuid;
+
+const MAX_QUERY_LENGTH: usize = 200;
+
+/// Splits a search into the letters and digits the `simple` configuration
+/// indexes, so nothing typed can break the query syntax.
+fn words(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Turns a search into a `to_tsquery` expression matching every term: a
+/// quoted phrase as adjacent words, a word ending in `*` as a prefix.
+fn ts_query(q: &str) -> Option<String> {
+    let mut terms = vec![];
+    for (i, part) in q.split('"').enumerate() {
+        if i % 2 == 1 {
+            let phrase = words(part);
+            if !phrase.is_empty() {
+                terms.push(format!("'{phrase}'"));
+            }
+            continue;
+        }
+        for word in part.split_whitespace() {
+            let prefix = word.ends_with('*');
+            let word = words(word);
+            if word.is_empty() {
+                continue;
+            }
+            terms.push(match prefix {
+                true => format!("'{word}':*"),
+                false => format!("'{word}'"),
+            });
+        }
+    }
+    (!terms.is_empty()).then(|| terms.join(" & "))
+}
+
+fn encode_cursor(created_at: NaiveDateTime, id: &str) -> String {
+    URL_SAFE_NO_PAD.encode(format!("{}:{id}", created_at.and_utc().timestamp_micros()))
+}
+
+/// The last message of a page, the next one starts right after it.
+fn decode_cursor(cursor: &str) -> Option<(NaiveDateTime, Uuid)> {
+    let cursor = String::from_utf8(URL_SAFE_NO_PAD.decode(cursor).ok()?).ok()?;
+    let (micros, id) = cursor.split_once(':')?;
+    let created_at = DateTime::from_timestamp_micros(micros.parse().ok()?)?.naive_utc();
+    Some((created_at, id.parse().ok()?))
+}
+
+/// Searches the bodies of the caller's messages, newest first. Sealed and
+/// end-to-end encrypted bodies are not indexed, so they never match. Once
+/// `MASTER_KEY` is set and `SealStoredBodies` sealed the older ones nothing
+/// would, so the search is refused rather than answering with no results.
+pub async fn search_messages(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    query: SearchQuery,
+) -> Result<Json<SearchResults>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+    if ctx.keyring.is_sealing() {
+        return Err(ApiError::Conflict(
+            "Search is unavailable, message bodies are encrypted at rest on this server"
+                .to_string(),
+        )
+        .into_response());
+    }
+    let limit = query.limit.unwrap_or(50).clamp(1, 100);
+
+    let mut errors = vec![];
+    let ts_query = match query.q.chars().count() > MAX_QUERY_LENGTH {
+        true => {
+            errors.push(format!("q: must be at most {MAX_QUERY_LENGTH} characters."));
+            None
+        }
+        false => ts_query(&query.q),
+    };
+    if ts_query.is_none() && errors.is_empty() {
+        errors.push("q: must have at least one word to search.".to_string());
+    }
+    let cursor = match &query.cursor {
+        Some(cursor) => match decode_cursor(cursor) {
+            Some(cursor) => Some(cursor),
+            None => {
+                errors.push("cursor: must be the next_cursor of a previous page.".to_string());
+                None
+            }
+        },
+        None => None,
+    };
+    if !errors.is_empty() {
+        return Err(ApiError::BadRequest { errors }.into_response());
+    }
+    let (cursor_created_at, cursor_id) = cursor.unzip();
+
+    // the body is escaped before highlighting so the headline is safe HTML,
+    // the `simple` parser skips the entities
+    let result = sqlx::query_as!(
+        SearchHit,
+        r#"select m.id::text as "message_id!", m.body as "body!",
+            ts_headline('simple',
+                replace(replace(replace(m.body, '&', '&amp;'), '<', '&lt;'), '>', '&gt;'),
+                q.query,
+                'StartSel=<mark>, StopSel=</mark>, MaxFragments=3, FragmentDelimiter=" … "'
+            ) as "headline!",
+            m.folder, m.link_id::text, m.read_at, m.reply, m.replied_at, m.published_at, m.created_at
+        from "messages" m, to_tsquery('simple', $2) q(query)
+        where m.recipient_id = $1 and m.search_vector @@ q.query
+            and m.folder = coalesce($3, 'inbox')
+            and ($4::uuid is null or m.link_id = $4)
+            and ($5::bool is null or (m.read_at is not null) = $5)
+            and ($6::date is null or m.created_at >= $6)
+            and ($7::date is null or m.created_at < $7::date + 1)
+            and ($8::timestamp is null or (m.created_at, m.id) < ($8, $9))
+        order by m.created_at desc, m.id desc
+        limit $10"#,
+        user_id,
+        ts_query,
+        query.folder,
+        query.link_id,
+        query.read,
+        query.from,
+        query.to,
+        cursor_created_at,
+        cursor_id,
+        limit + 1
+    )
+    .fetch_all(&ctx.db)
+    .await;
+
+    match result {
+        Ok(mut messages) => {
+            let next_cursor = match messages.len() as i64 > limit {
+                true => {
+                    messages.truncate(limit as usize);
+                    messages
+                        .last()
+                        .map(|last| encode_cursor(last.created_at, &last.message_id))
+                }
+                false => None,
+            };
+            Ok(Json(SearchResults {
+                messages,
+                next_cursor,
+            }))
+        }
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_only_letters_and_digits() {
+        assert_eq!(words("  Hello,   world!! "), "Hello world");
+        assert_eq!(words("it's a \\ b & c | !d"), "it s a b c d");
+        assert_eq!(words("façade 東京 42"), "façade 東京 42");
+        assert_eq!(words("':*<->()"), "");
+    }
+
+    #[test]
+    fn builds_queries() {
+        assert_eq!(ts_query("oak tree").as_deref(), Some("'oak' & 'tree'"));
+        assert_eq!(
+            ts_query(r#"meet "old oak" tree"#).as_deref(),
+            Some("'meet' & 'old oak' & 'tree'")
+        );
+        assert_eq!(ts_query("oak* tree").as_deref(), Some("'oak':* & 'tree'"));
+        // only a trailing star makes a prefix, and a bare one is no word
+        assert_eq!(ts_query("*oak * o*k").as_deref(), Some("'oak' & 'o k'"));
+        // an unclosed quote runs to the end
+        assert_eq!(
+            ts_query(r#"tree "old oak"#).as_deref(),
+            Some("'tree' & 'old oak'")
+        );
+        assert_eq!(
+            ts_query(r#"'); drop table "messages"; --"#).as_deref(),
+            Some("'drop' & 'table' & 'messages'")
+        );
+    }
+
+    #[test]
+    fn refuses_queries_without_words() {
+        assert_eq!(ts_query(""), None);
+        assert_eq!(ts_query("   "), None);
+        assert_eq!(ts_query(r#""" * & | ! <-> "?""#), None);
+    }
+
+    #[test]
+    fn round_trips_cursors() {
+        let created_at = DateTime::from_timestamp_micros(1_700_000_000_123_456)
+            .unwrap()
+            .naive_utc();
+        let id = Uuid::new_v4();
+        let cursor = encode_cursor(created_at, &id.to_string());
+        assert_eq!(decode_cursor(&cursor), Some((created_at, id)));
+    }
+
+    #[test]
+    fn refuses_foreign_cursors() {
+        let encode = |text: &str| URL_SAFE_NO_PAD.encode(text);
+        assert_eq!(decode_cursor(""), None);
+        assert_eq!(decode_cursor("not base64!"), None);
+        assert_eq!(decode_cursor(&encode("1700000000")), None);
+        assert_eq!(
+            decode_cursor(&encode("soon:00000000-0000-0000-0000-000000000000")),
+            None
+        );
+        assert_eq!(decode_cursor(&encode("1700000000:not-a-uuid")), None);
+        assert_eq!(decode_cursor(&URL_SAFE_NO_PAD.encode([0xff, 0xfe])), None);
+    }
+}
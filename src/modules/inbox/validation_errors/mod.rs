@@ -0,0 +1,3 @@
+mod reply_error;
+
+pub use reply_error::*;
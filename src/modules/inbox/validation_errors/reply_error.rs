@@ -0,0 +1,32 @@
+use crate::core::{error_transformer::error_transformer, traits::TransformValidationErrors};
+use validator::ValidationErrors;
+
+pub struct ReplyValidationError;
+impl TransformValidationErrors for ReplyValidationError {
+    fn new() -> Self {
+        ReplyValidationError
+    }
+    fn transform_errors(&self, errors: ValidationErrors) -> Vec<String> {
+        error_transformer(errors)
+    }
+}
+
+pub struct AnswerValidationError;
+impl TransformValidationErrors for AnswerValidationError {
+    fn new() -> Self {
+        AnswerValidationError
+    }
+    fn transform_errors(&self, errors: ValidationErrors) -> Vec<String> {
+        error_transformer(errors)
+    }
+}
+
+pub struct ReactionSettingsValidationError;
+impl TransformValidationErrors for ReactionSettingsValidationError {
+    fn new() -> Self {
+        ReactionSettingsValidationError
+    }
+    fn transform_errors(&self, errors: ValidationErrors) -> Vec<String> {
+        error_transformer(errors)
+    }
+}
@@ -0,0 +1,72 @@
+use crate::{
+    core::{
+        extractors::{Authorized, CustomPath, CustomQuery, ValidatedBody},
+        models::Claims,
+    },
+    modules::links::{
+        models::{CreateLinkBody, Link, QrQuery, UpdateLinkBody},
+        service::{create_link, delete_link, link_qr_code, list_links, single_link, update_link},
+        validation_errors::{CreateLinkValidationError, UpdateLinkValidationError},
+    },
+    ApiContext,
+};
+use axum::{
+    body::Body,
+    http::StatusCode,
+    response::{Json, Response},
+    Extension,
+};
+use uuid::Uuid;
+
+pub async fn find_links(
+    ctx: Extension<ApiContext>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<Json<Vec<Link>>, Response<Body>> {
+    let links = list_links(ctx, claims).await?;
+    Ok(links)
+}
+
+pub async fn find_link(
+    ctx: Extension<ApiContext>,
+    CustomPath(link_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<Json<Link>, Response<Body>> {
+    let link = single_link(ctx, claims, link_id).await?;
+    Ok(link)
+}
+
+pub async fn handle_create_link(
+    ctx: Extension<ApiContext>,
+    Authorized(claims): Authorized<Claims>,
+    ValidatedBody(body, _): ValidatedBody<CreateLinkBody, CreateLinkValidationError>,
+) -> Result<(StatusCode, Json<Link>), Response<Body>> {
+    let link = create_link(ctx, claims, Json(body)).await?;
+    Ok((StatusCode::CREATED, link))
+}
+
+pub async fn handle_update_link(
+    ctx: Extension<ApiContext>,
+    CustomPath(link_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+    ValidatedBody(body, _): ValidatedBody<UpdateLinkBody, UpdateLinkValidationError>,
+) -> Result<Json<Link>, Response<Body>> {
+    let link = update_link(ctx, claims, link_id, Json(body)).await?;
+    Ok(link)
+}
+
+pub async fn handle_delete_link(
+    ctx: Extension<ApiContext>,
+    CustomPath(link_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<StatusCode, Response<Body>> {
+    delete_link(ctx, claims, link_id).await
+}
+
+pub async fn get_qr_code(
+    ctx: Extension<ApiContext>,
+    CustomPath(link_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+    CustomQuery(query): CustomQuery<QrQuery>,
+) -> Result<Response<Body>, Response<Body>> {
+    link_qr_code(ctx, claims, link_id, query).await
+}
@@ -0,0 +1,3 @@
+mod links_api;
+
+pub use links_api::*;
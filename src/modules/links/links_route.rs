@@ -0,0 +1,17 @@
+use axum::{routing::get, Router};
+
+use super::controllers::{
+    find_link, find_links, get_qr_code, handle_create_link, handle_delete_link, handle_update_link,
+};
+
+pub fn links_routes() -> Router {
+    Router::new()
+        .route("/", get(find_links).post(handle_create_link))
+        .route(
+            "/:link_id",
+            get(find_link)
+                .patch(handle_update_link)
+                .delete(handle_delete_link),
+        )
+        .route("/:link_id/qr", get(get_qr_code))
+}
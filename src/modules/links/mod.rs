@@ -0,0 +1,7 @@
+mod links_route;
+pub use links_route::*;
+
+pub mod controllers;
+pub mod models;
+pub mod service;
+pub mod validation_errors;
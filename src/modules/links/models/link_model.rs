@@ -0,0 +1,140 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::{core::utils::nullable, modules::user::models::PROFILE_LINK_REGEX};
+
+/// A named link shared for one campaign. A `None` setting falls back to the
+/// owner's message settings.
+#[derive(Serialize)]
+pub struct Link {
+    pub link_id: String,
+    pub slug: String,
+    pub name: String,
+    pub prompt: Option<String>,
+    pub enabled: bool,
+    pub expires_at: Option<NaiveDateTime>,
+    /// Set once a moderator suspended the link, it takes no more messages.
+    pub suspended_at: Option<NaiveDateTime>,
+    /// Messages accepted before the link expires, 1 makes it single-use.
+    pub max_messages: Option<i32>,
+    pub message_count: i32,
+    pub min_length: Option<i32>,
+    pub max_length: Option<i32>,
+    pub closed_message: Option<String>,
+    pub filter_threshold: Option<f32>,
+    pub require_encryption: Option<bool>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct CreateLinkBody {
+    #[validate(regex(
+        path = "PROFILE_LINK_REGEX",
+        message = "must be 3 to 30 letters, digits, hyphens or underscores"
+    ))]
+    pub slug: String,
+
+    #[validate(length(min = 1, max = 60))]
+    pub name: String,
+
+    #[validate(length(max = 120))]
+    pub prompt: Option<String>,
+
+    pub enabled: Option<bool>,
+
+    pub expires_at: Option<NaiveDateTime>,
+
+    #[validate(range(min = 1))]
+    pub max_messages: Option<i32>,
+
+    #[validate(range(min = 1, max = 1000))]
+    pub min_length: Option<i32>,
+
+    #[validate(range(min = 1, max = 1000))]
+    pub max_length: Option<i32>,
+
+    #[validate(length(max = 200))]
+    pub closed_message: Option<String>,
+
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub filter_threshold: Option<f32>,
+
+    pub require_encryption: Option<bool>,
+}
+
+/// Partial update of a link. Omitted fields are left untouched, an empty
+/// `prompt` or `closed_message` clears the value and `null` removes
+/// `expires_at` or `max_messages`, or resets a setting to the owner's default.
+#[derive(Debug, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateLinkBody {
+    #[validate(regex(
+        path = "PROFILE_LINK_REGEX",
+        message = "must be 3 to 30 letters, digits, hyphens or underscores"
+    ))]
+    pub slug: Option<String>,
+
+    #[validate(length(min = 1, max = 60))]
+    pub name: Option<String>,
+
+    #[validate(length(max = 120))]
+    pub prompt: Option<String>,
+
+    pub enabled: Option<bool>,
+
+    #[serde(default, deserialize_with = "nullable")]
+    pub expires_at: Option<Option<NaiveDateTime>>,
+
+    #[serde(default, deserialize_with = "nullable")]
+    #[validate(range(min = 1))]
+    pub max_messages: Option<Option<i32>>,
+
+    #[serde(default, deserialize_with = "nullable")]
+    #[validate(range(min = 1, max = 1000))]
+    pub min_length: Option<Option<i32>>,
+
+    #[serde(default, deserialize_with = "nullable")]
+    #[validate(range(min = 1, max = 1000))]
+    pub max_length: Option<Option<i32>>,
+
+    #[validate(length(max = 200))]
+    pub closed_message: Option<String>,
+
+    #[serde(default, deserialize_with = "nullable")]
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub filter_threshold: Option<Option<f32>>,
+
+    #[serde(default, deserialize_with = "nullable")]
+    pub require_encryption: Option<Option<bool>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QrFormat {
+    Svg,
+    Png,
+}
+
+/// Share of the code that can be damaged and still scan: L 7%, M 15%, Q 25%, H 30%.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+pub enum QrErrorCorrection {
+    L,
+    M,
+    Q,
+    H,
+}
+
+#[derive(Deserialize)]
+pub struct QrQuery {
+    /// `svg` (default) or `png`.
+    pub format: Option<QrFormat>,
+    /// Minimum width in pixels, 64 to 2048.
+    pub size: Option<u32>,
+    /// `L`, `M` (default), `Q` or `H`.
+    pub ecc: Option<QrErrorCorrection>,
+    /// Quiet zone width in modules, 0 to 16, 4 by default as the standard asks.
+    pub margin: Option<u32>,
+}
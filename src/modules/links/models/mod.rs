@@ -0,0 +1,3 @@
+mod link_model;
+
+pub use link_model::*;
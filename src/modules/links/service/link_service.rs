@@ -0,0 +1,232 @@
+use crate::{
+    core::models::{ApiError, Claims},
+    modules::{
+        links::models::{CreateLinkBody, Link, UpdateLinkBody},
+        user::service::ensure_encryption_key,
+    },
+    ApiContext,
+};
+use axum::{body::Body, http::Response, http::StatusCode, response::IntoResponse};
+use axum::{Extension, Json};
+use uuid::Uuid;
+
+const MAX_LINKS: i64 = 50;
+
+fn write_error(e: sqlx::Error) -> Response<Body> {
+    match e {
+        sqlx::Error::Database(e) if e.is_unique_violation() => {
+            ApiError::Conflict("Slug is already taken".to_string()).into_response()
+        }
+        sqlx::Error::Database(e) if e.constraint() == Some("links_length_check") => {
+            ApiError::BadRequest {
+                errors: vec!["min_length: must not be greater than max_length.".to_string()],
+            }
+            .into_response()
+        }
+        e => ApiError::Database(e).into_response(),
+    }
+}
+
+pub async fn list_links(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+) -> Result<Json<Vec<Link>>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let result = sqlx::query_as!(
+        Link,
+        r#"select id::text as "link_id!", slug, name, prompt, enabled, expires_at, suspended_at, max_messages, message_count,
+            min_length, max_length, closed_message, filter_threshold, require_encryption, created_at, updated_at
+        from "links" where user_id = $1 order by created_at desc"#,
+        user_id
+    )
+    .fetch_all(&ctx.db)
+    .await;
+
+    match result {
+        Ok(links) => Ok(Json(links)),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+pub async fn single_link(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    link_id: Uuid,
+) -> Result<Json<Link>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let result = sqlx::query_as!(
+        Link,
+        r#"select id::text as "link_id!", slug, name, prompt, enabled, expires_at, suspended_at, max_messages, message_count,
+            min_length, max_length, closed_message, filter_threshold, require_encryption, created_at, updated_at
+        from "links" where id = $1 and user_id = $2"#,
+        link_id,
+        user_id
+    )
+    .fetch_optional(&ctx.db)
+    .await;
+
+    match result {
+        Ok(Some(link)) => Ok(Json(link)),
+        Ok(None) => Err(ApiError::NotFound("Link not found".to_string()).into_response()),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+pub async fn create_link(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    Json(body): Json<CreateLinkBody>,
+) -> Result<Json<Link>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    if body.require_encryption == Some(true) {
+        ensure_encryption_key(&ctx.db, user_id)
+            .await
+            .map_err(|e| e.into_response())?;
+    }
+
+    // creations of one user take turns on their row, so the count in the
+    // insert sees every link made before it
+    let mut tx = ctx
+        .db
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e).into_response())?;
+    sqlx::query!(
+        r#"select id from "users" where id = $1 for update"#,
+        user_id
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| ApiError::Database(e).into_response())?;
+    let result = sqlx::query_as!(
+        Link,
+        r#"insert into "links"
+            (user_id, slug, name, prompt, enabled, expires_at, min_length, max_length, closed_message,
+            filter_threshold, max_messages, require_encryption)
+        select $1, $2, $3, nullif($4, ''), coalesce($5, true), $6, $7, $8, nullif($9, ''), $10, $11, $12
+        where (select count(*) from "links" where user_id = $1) < $13
+        returning id::text as "link_id!", slug, name, prompt, enabled, expires_at, suspended_at, max_messages, message_count,
+            min_length, max_length, closed_message, filter_threshold, require_encryption, created_at, updated_at"#,
+        user_id,
+        body.slug,
+        body.name,
+        body.prompt,
+        body.enabled,
+        body.expires_at,
+        body.min_length,
+        body.max_length,
+        body.closed_message,
+        body.filter_threshold,
+        body.max_messages,
+        body.require_encryption,
+        MAX_LINKS
+    )
+    .fetch_optional(&mut *tx)
+    .await;
+
+    match result {
+        Ok(Some(link)) => {
+            tx.commit()
+                .await
+                .map_err(|e| ApiError::Database(e).into_response())?;
+            Ok(Json(link))
+        }
+        Ok(None) => Err(ApiError::BadRequest {
+            errors: vec![format!("links: at most {MAX_LINKS} links are allowed.")],
+        }
+        .into_response()),
+        Err(e) => Err(write_error(e)),
+    }
+}
+
+pub async fn update_link(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    link_id: Uuid,
+    Json(body): Json<UpdateLinkBody>,
+) -> Result<Json<Link>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    if body.require_encryption == Some(Some(true)) {
+        ensure_encryption_key(&ctx.db, user_id)
+            .await
+            .map_err(|e| e.into_response())?;
+    }
+
+    // fields left out keep their value, nullable ones are only touched when
+    // present, null included
+    let result = sqlx::query_as!(
+        Link,
+        r#"update "links" set
+            slug = coalesce($3, slug),
+            name = coalesce($4, name),
+            prompt = nullif(coalesce($5, prompt), ''),
+            enabled = coalesce($6, enabled),
+            expires_at = case when $7 then $8 else expires_at end,
+            min_length = case when $9 then $10 else min_length end,
+            max_length = case when $11 then $12 else max_length end,
+            closed_message = nullif(coalesce($13, closed_message), ''),
+            filter_threshold = case when $14 then $15 else filter_threshold end,
+            max_messages = case when $16 then $17 else max_messages end,
+            require_encryption = case when $18 then $19 else require_encryption end
+        where id = $1 and user_id = $2
+        returning id::text as "link_id!", slug, name, prompt, enabled, expires_at, suspended_at, max_messages, message_count,
+            min_length, max_length, closed_message, filter_threshold, require_encryption, created_at, updated_at"#,
+        link_id,
+        user_id,
+        body.slug,
+        body.name,
+        body.prompt,
+        body.enabled,
+        body.expires_at.is_some(),
+        body.expires_at.flatten(),
+        body.min_length.is_some(),
+        body.min_length.flatten(),
+        body.max_length.is_some(),
+        body.max_length.flatten(),
+        body.closed_message,
+        body.filter_threshold.is_some(),
+        body.filter_threshold.flatten(),
+        body.max_messages.is_some(),
+        body.max_messages.flatten(),
+        body.require_encryption.is_some(),
+        body.require_encryption.flatten()
+    )
+    .fetch_optional(&ctx.db)
+    .await;
+
+    match result {
+        Ok(Some(link)) => Ok(Json(link)),
+        Ok(None) => Err(ApiError::NotFound("Link not found".to_string()).into_response()),
+        Err(e) => Err(write_error(e)),
+    }
+}
+
+/// Deletes a link. Messages received through it stay in the inbox untagged.
+pub async fn delete_link(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    link_id: Uuid,
+) -> Result<StatusCode, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let result = sqlx::query_scalar!(
+        r#"delete from "links" where id = $1 and user_id = $2 returning id"#,
+        link_id,
+        user_id
+    )
+    .fetch_optional(&ctx.db)
+    .await;
+
+    match result {
+        Ok(Some(id)) => {
+            ctx.qr_codes.invalidate(id);
+            Ok(StatusCode::NO_CONTENT)
+        }
+        Ok(None) => Err(ApiError::NotFound("Link not found".to_string()).into_response()),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
@@ -0,0 +1,5 @@
+mod link_service;
+mod qr_service;
+
+pub use link_service::*;
+pub use qr_service::*;
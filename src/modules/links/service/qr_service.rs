@@ -0,0 +1,252 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    body::Body,
+    http::{header, Response},
+    response::IntoResponse,
+    Extension,
+};
+use qrcode::EcLevel;
+use uuid::Uuid;
+
+use crate::{
+    core::{
+        models::{ApiError, Claims},
+        utils::{qr_png, qr_svg},
+    },
+    modules::links::models::{QrErrorCorrection, QrFormat, QrQuery},
+    ApiContext,
+};
+
+/// Rendered codes kept per link, past which the oldest renditions make room.
+const MAX_RENDITIONS: usize = 16;
+
+/// Bytes of rendered codes kept across all links, past which the links used
+/// least recently make room.
+const MAX_CACHED_BYTES: usize = 32 * 1024 * 1024;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct QrKey {
+    format: QrFormat,
+    ecc: QrErrorCorrection,
+    size: u32,
+    margin: u32,
+}
+
+/// Renditions of one code, oldest first, for the slug they encode.
+#[derive(Default)]
+struct Renditions {
+    slug: String,
+    codes: Vec<(QrKey, Arc<Vec<u8>>)>,
+    /// Value of `Codes::clock` when the link was last looked up.
+    used: u64,
+}
+
+impl Renditions {
+    fn bytes(&self) -> usize {
+        self.codes.iter().map(|(_, code)| code.len()).sum()
+    }
+}
+
+#[derive(Default)]
+struct Codes {
+    links: HashMap<Uuid, Renditions>,
+    bytes: usize,
+    clock: u64,
+}
+
+/// Rendered QR codes by link. The code only depends on the slug, so an entry
+/// is only used while the link still has the slug it was rendered for.
+#[derive(Default)]
+pub struct QrCache {
+    codes: Mutex<Codes>,
+}
+
+impl QrCache {
+    fn get(&self, link_id: Uuid, slug: &str, key: QrKey) -> Option<Arc<Vec<u8>>> {
+        let mut codes = self.codes.lock().unwrap();
+        codes.clock += 1;
+        let clock = codes.clock;
+        let renditions = codes
+            .links
+            .get_mut(&link_id)
+            .filter(|renditions| renditions.slug == slug)?;
+        renditions.used = clock;
+        renditions
+            .codes
+            .iter()
+            .find(|(cached, _)| *cached == key)
+            .map(|(_, code)| code.clone())
+    }
+
+    fn insert(&self, link_id: Uuid, slug: &str, key: QrKey, code: Arc<Vec<u8>>) {
+        let mut codes = self.codes.lock().unwrap();
+        let codes = &mut *codes;
+        codes.clock += 1;
+        codes.bytes += code.len();
+
+        let renditions = codes.links.entry(link_id).or_default();
+        if renditions.slug != slug {
+            codes.bytes -= renditions.bytes();
+            renditions.slug = slug.to_string();
+            renditions.codes.clear();
+        }
+        if renditions.codes.len() >= MAX_RENDITIONS {
+            codes.bytes -= renditions.codes.remove(0).1.len();
+        }
+        renditions.codes.push((key, code));
+        renditions.used = codes.clock;
+
+        while codes.bytes > MAX_CACHED_BYTES && codes.links.len() > 1 {
+            let Some(oldest) = codes
+                .links
+                .iter()
+                .filter(|(id, _)| **id != link_id)
+                .min_by_key(|(_, renditions)| renditions.used)
+                .map(|(id, _)| *id)
+            else {
+                break;
+            };
+            if let Some(evicted) = codes.links.remove(&oldest) {
+                codes.bytes -= evicted.bytes();
+            }
+        }
+    }
+
+    pub fn invalidate(&self, link_id: Uuid) {
+        let mut codes = self.codes.lock().unwrap();
+        if let Some(removed) = codes.links.remove(&link_id) {
+            codes.bytes -= removed.bytes();
+        }
+    }
+}
+
+/// Public address of a link, as encoded in its QR code.
+pub fn link_url(ctx: &ApiContext, slug: &str) -> String {
+    format!("{}/l/{slug}", ctx.config.public_url.trim_end_matches('/'))
+}
+
+pub async fn link_qr_code(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    link_id: Uuid,
+    query: QrQuery,
+) -> Result<Response<Body>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let slug = sqlx::query_scalar!(
+        r#"select slug from "links" where id = $1 and user_id = $2"#,
+        link_id,
+        user_id
+    )
+    .fetch_optional(&ctx.db)
+    .await
+    .map_err(|e| ApiError::Database(e).into_response())?
+    .ok_or_else(|| ApiError::NotFound("Link not found".to_string()).into_response())?;
+
+    let key = QrKey {
+        format: query.format.unwrap_or(QrFormat::Svg),
+        ecc: query.ecc.unwrap_or(QrErrorCorrection::M),
+        size: query.size.unwrap_or(512).clamp(64, 2048),
+        margin: query.margin.unwrap_or(4).min(16),
+    };
+    let code = match ctx.qr_codes.get(link_id, &slug, key) {
+        Some(code) => code,
+        None => {
+            let url = link_url(&ctx, &slug);
+            let level = match key.ecc {
+                QrErrorCorrection::L => EcLevel::L,
+                QrErrorCorrection::M => EcLevel::M,
+                QrErrorCorrection::Q => EcLevel::Q,
+                QrErrorCorrection::H => EcLevel::H,
+            };
+            let code = match key.format {
+                QrFormat::Svg => qr_svg(&url, level, key.size, key.margin).map(String::into_bytes),
+                QrFormat::Png => qr_png(&url, level, key.size, key.margin),
+            }
+            .map_err(|e| e.into_response())?;
+            let code = Arc::new(code);
+            ctx.qr_codes.insert(link_id, &slug, key, code.clone());
+            code
+        }
+    };
+
+    let content_type = match key.format {
+        QrFormat::Svg => "image/svg+xml",
+        QrFormat::Png => "image/png",
+    };
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type),
+            (header::CACHE_CONTROL, "private, no-cache"),
+        ],
+        code.to_vec(),
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(size: u32) -> QrKey {
+        QrKey {
+            format: QrFormat::Png,
+            ecc: QrErrorCorrection::M,
+            size,
+            margin: 4,
+        }
+    }
+
+    fn code(bytes: usize) -> Arc<Vec<u8>> {
+        Arc::new(vec![0; bytes])
+    }
+
+    #[test]
+    fn forgets_codes_of_an_old_slug() {
+        let cache = QrCache::default();
+        let link = Uuid::new_v4();
+        cache.insert(link, "old", key(64), code(10));
+        assert!(cache.get(link, "old", key(64)).is_some());
+        assert!(cache.get(link, "new", key(64)).is_none());
+
+        cache.insert(link, "new", key(64), code(20));
+        assert!(cache.get(link, "old", key(64)).is_none());
+        assert_eq!(cache.codes.lock().unwrap().bytes, 20);
+    }
+
+    #[test]
+    fn keeps_the_newest_renditions_of_a_link() {
+        let cache = QrCache::default();
+        let link = Uuid::new_v4();
+        for size in 0..=MAX_RENDITIONS as u32 {
+            cache.insert(link, "slug", key(size), code(1));
+        }
+        assert!(cache.get(link, "slug", key(0)).is_none());
+        assert!(cache.get(link, "slug", key(1)).is_some());
+        assert_eq!(cache.codes.lock().unwrap().bytes, MAX_RENDITIONS);
+    }
+
+    #[test]
+    fn evicts_the_links_used_least_recently() {
+        let cache = QrCache::default();
+        let share = MAX_CACHED_BYTES / 3;
+        let (first, second, third) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        cache.insert(first, "first", key(64), code(share));
+        cache.insert(second, "second", key(64), code(share));
+        cache.insert(third, "third", key(64), code(share));
+        assert!(cache.get(first, "first", key(64)).is_some());
+
+        cache.insert(Uuid::new_v4(), "fourth", key(64), code(share));
+        assert!(cache.get(first, "first", key(64)).is_some());
+        assert!(cache.get(second, "second", key(64)).is_none());
+        assert!(cache.get(third, "third", key(64)).is_some());
+        assert!(cache.codes.lock().unwrap().bytes <= MAX_CACHED_BYTES);
+
+        cache.invalidate(first);
+        assert_eq!(cache.codes.lock().unwrap().bytes, 2 * share);
+    }
+}
@@ -0,0 +1,22 @@
+use crate::core::{error_transformer::error_transformer, traits::TransformValidationErrors};
+use validator::ValidationErrors;
+
+pub struct CreateLinkValidationError;
+impl TransformValidationErrors for CreateLinkValidationError {
+    fn new() -> Self {
+        CreateLinkValidationError
+    }
+    fn transform_errors(&self, errors: ValidationErrors) -> Vec<String> {
+        error_transformer(errors)
+    }
+}
+
+pub struct UpdateLinkValidationError;
+impl TransformValidationErrors for UpdateLinkValidationError {
+    fn new() -> Self {
+        UpdateLinkValidationError
+    }
+    fn transform_errors(&self, errors: ValidationErrors) -> Vec<String> {
+        error_transformer(errors)
+    }
+}
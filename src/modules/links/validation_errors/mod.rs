@@ -0,0 +1,3 @@
+mod link_error;
+
+pub use link_error::*;
@@ -1,2 +1,13 @@
+pub mod ama;
 pub mod auth;
+pub mod blocklist;
+pub mod digest;
+pub mod encryption;
+pub mod inbox;
+pub mod links;
+pub mod moderation;
+pub mod profile;
+pub mod push;
+pub mod replies;
 pub mod user;
+pub mod webhooks;
@@ -0,0 +1,3 @@
+mod moderation_api;
+
+pub use moderation_api::*;
@@ -0,0 +1,64 @@
+use crate::{
+    core::{
+        extractors::{Authorized, CustomPath, CustomQuery, ValidatedBody},
+        models::Claims,
+    },
+    modules::moderation::{
+        models::{LogQuery, ModerationLogEntry, ModerationReport, ReportQuery, ResolveReportBody},
+        service::{claim_report, find_report, list_log, list_reports, resolve_report},
+        validation_errors::ResolveReportValidationError,
+    },
+    ApiContext,
+};
+use axum::{
+    body::Body,
+    response::{Json, Response},
+    Extension,
+};
+use uuid::Uuid;
+
+pub async fn find_reports(
+    ctx: Extension<ApiContext>,
+    Authorized(claims): Authorized<Claims>,
+    CustomQuery(query): CustomQuery<ReportQuery>,
+) -> Result<Json<Vec<ModerationReport>>, Response<Body>> {
+    let reports = list_reports(ctx, claims, query).await?;
+    Ok(reports)
+}
+
+pub async fn get_report(
+    ctx: Extension<ApiContext>,
+    CustomPath(report_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<Json<ModerationReport>, Response<Body>> {
+    let report = find_report(ctx, claims, report_id).await?;
+    Ok(report)
+}
+
+pub async fn handle_claim_report(
+    ctx: Extension<ApiContext>,
+    CustomPath(report_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<Json<ModerationReport>, Response<Body>> {
+    let report = claim_report(ctx, claims, report_id).await?;
+    Ok(report)
+}
+
+pub async fn handle_resolve_report(
+    ctx: Extension<ApiContext>,
+    CustomPath(report_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+    ValidatedBody(body, _): ValidatedBody<ResolveReportBody, ResolveReportValidationError>,
+) -> Result<Json<ModerationReport>, Response<Body>> {
+    let report = resolve_report(ctx, claims, report_id, Json(body)).await?;
+    Ok(report)
+}
+
+pub async fn find_log(
+    ctx: Extension<ApiContext>,
+    Authorized(claims): Authorized<Claims>,
+    CustomQuery(query): CustomQuery<LogQuery>,
+) -> Result<Json<Vec<ModerationLogEntry>>, Response<Body>> {
+    let entries = list_log(ctx, claims, query).await?;
+    Ok(entries)
+}
@@ -0,0 +1,7 @@
+mod moderation_route;
+pub use moderation_route::*;
+
+pub mod controllers;
+pub mod models;
+pub mod service;
+pub mod validation_errors;
@@ -0,0 +1,3 @@
+mod report_model;
+
+pub use report_model::*;
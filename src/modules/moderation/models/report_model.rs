@@ -0,0 +1,104 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+/// A report as seen by the recipient who filed it.
+#[derive(Serialize)]
+pub struct Report {
+    pub report_id: String,
+    pub message_id: Option<String>,
+    pub category: String,
+    pub note: Option<String>,
+    pub status: String,
+    pub created_at: NaiveDateTime,
+}
+
+/// A report as seen by moderators. Nothing about the sender is included
+/// beyond the reported message itself.
+#[derive(Serialize)]
+pub struct ModerationReport {
+    pub report_id: String,
+    /// Unset once the message has been deleted.
+    pub message_id: Option<String>,
+    pub recipient_id: String,
+    pub profile_link: Option<String>,
+    pub category: String,
+    pub note: Option<String>,
+    pub message_body: String,
+    /// Set while `message_body` is still sealed at rest.
+    #[serde(skip_serializing)]
+    pub sealed_message_body: Option<Vec<u8>>,
+    /// `open`, `claimed` or `resolved`.
+    pub status: String,
+    pub claimed_by: Option<String>,
+    pub claimed_at: Option<NaiveDateTime>,
+    pub resolved_by: Option<String>,
+    pub resolved_at: Option<NaiveDateTime>,
+    pub resolution: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Serialize)]
+pub struct ModerationLogEntry {
+    pub entry_id: String,
+    pub report_id: String,
+    pub moderator_id: String,
+    pub action: String,
+    pub note: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Deserialize)]
+pub struct ReportQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    /// `open` (default), `claimed` or `resolved`.
+    pub status: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct LogQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub report_id: Option<uuid::Uuid>,
+}
+
+fn validate_category(category: &str) -> Result<(), ValidationError> {
+    match category {
+        "harassment" | "threat" | "hate" | "spam" | "self_harm" | "other" => Ok(()),
+        _ => Err(ValidationError::new("category")),
+    }
+}
+
+fn validate_action(action: &str) -> Result<(), ValidationError> {
+    match action {
+        "dismiss" | "delete_message" | "block_sender" | "suspend_link" => Ok(()),
+        _ => Err(ValidationError::new("action")),
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct ReportMessageBody {
+    #[validate(custom(
+        function = "validate_category",
+        message = "must be one of harassment, threat, hate, spam, self_harm or other"
+    ))]
+    pub category: String,
+
+    #[validate(length(max = 1000))]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct ResolveReportBody {
+    #[validate(custom(
+        function = "validate_action",
+        message = "must be one of dismiss, delete_message, block_sender or suspend_link"
+    ))]
+    pub action: String,
+
+    #[validate(length(max = 1000))]
+    pub note: Option<String>,
+}
@@ -0,0 +1,17 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use super::controllers::{
+    find_log, find_reports, get_report, handle_claim_report, handle_resolve_report,
+};
+
+pub fn moderation_routes() -> Router {
+    Router::new()
+        .route("/reports", get(find_reports))
+        .route("/reports/:report_id", get(get_report))
+        .route("/reports/:report_id/claim", post(handle_claim_report))
+        .route("/reports/:report_id/resolve", post(handle_resolve_report))
+        .route("/log", get(find_log))
+}
@@ -0,0 +1,5 @@
+mod moderation_service;
+mod report_service;
+
+pub use moderation_service::*;
+pub use report_service::*;
@@ -0,0 +1,348 @@
+use crate::{
+    core::models::{ApiError, Claims},
+    modules::moderation::models::{
+        LogQuery, ModerationLogEntry, ModerationReport, ReportQuery, ResolveReportBody,
+    },
+    ApiContext,
+};
+use axum::{body::Body, http::Response, response::IntoResponse};
+use axum::{Extension, Json};
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+/// The caller's user id, provided they hold the moderator role.
+async fn moderator_id(db: &PgPool, claims: &Claims) -> Result<Uuid, ApiError> {
+    let user_id = claims.user_id()?;
+    let role = sqlx::query_scalar!(
+        r#"select role from "users" where id = $1 and suspended_at is null"#,
+        user_id
+    )
+    .fetch_optional(db)
+    .await?;
+
+    match role.as_deref() {
+        Some("moderator") => Ok(user_id),
+        _ => Err(ApiError::Forbidden("Moderator role required".to_string())),
+    }
+}
+
+async fn moderation_report(
+    executor: impl PgExecutor<'_>,
+    report_id: Uuid,
+) -> Result<ModerationReport, ApiError> {
+    sqlx::query_as!(
+        ModerationReport,
+        r#"select r.id::text as "report_id!", r.message_id::text, r.recipient_id::text as "recipient_id!",
+            u.profile_link, r.category, r.note, coalesce(r.message_body, '') as "message_body!",
+            r.sealed_message_body, r.status, r.claimed_by::text, r.claimed_at,
+            r.resolved_by::text, r.resolved_at, r.resolution, r.created_at
+        from "reports" r join "users" u on u.id = r.recipient_id
+        where r.id = $1"#,
+        report_id
+    )
+    .fetch_optional(executor)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Report not found".to_string()))
+}
+
+/// Decrypts the copy of the message body when it is sealed at rest.
+async fn open_report(ctx: &ApiContext, report: &mut ModerationReport) -> Result<(), ApiError> {
+    if let Some(sealed) = report.sealed_message_body.take() {
+        let recipient_id = Uuid::parse_str(&report.recipient_id)
+            .map_err(|e| ApiError::InternalServer(e.to_string()))?;
+        report.message_body = ctx.keyring.open(&ctx.db, recipient_id, &sealed).await?;
+    }
+    Ok(())
+}
+
+async fn log_action(
+    executor: impl PgExecutor<'_>,
+    report_id: Uuid,
+    moderator_id: Uuid,
+    action: &str,
+    note: Option<&str>,
+) -> Result<(), ApiError> {
+    sqlx::query!(
+        r#"insert into "moderation_log" (report_id, moderator_id, action, note)
+        values ($1, $2, $3, nullif($4, ''))"#,
+        report_id,
+        moderator_id,
+        action,
+        note
+    )
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Reports oldest first, so the queue is worked through in order.
+pub async fn list_reports(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    query: ReportQuery,
+) -> Result<Json<Vec<ModerationReport>>, Response<Body>> {
+    moderator_id(&ctx.db, &claims)
+        .await
+        .map_err(|e| e.into_response())?;
+    let limit = query.limit.unwrap_or(50).clamp(1, 100);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let result = sqlx::query_as!(
+        ModerationReport,
+        r#"select r.id::text as "report_id!", r.message_id::text, r.recipient_id::text as "recipient_id!",
+            u.profile_link, r.category, r.note, coalesce(r.message_body, '') as "message_body!",
+            r.sealed_message_body, r.status, r.claimed_by::text, r.claimed_at,
+            r.resolved_by::text, r.resolved_at, r.resolution, r.created_at
+        from "reports" r join "users" u on u.id = r.recipient_id
+        where r.status = coalesce($1, 'open')
+        order by r.created_at
+        limit $2 offset $3"#,
+        query.status,
+        limit,
+        offset
+    )
+    .fetch_all(&ctx.db)
+    .await;
+
+    match result {
+        Ok(mut reports) => {
+            for report in &mut reports {
+                open_report(&ctx, report)
+                    .await
+                    .map_err(|e| e.into_response())?;
+            }
+            Ok(Json(reports))
+        }
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+pub async fn find_report(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    report_id: Uuid,
+) -> Result<Json<ModerationReport>, Response<Body>> {
+    moderator_id(&ctx.db, &claims)
+        .await
+        .map_err(|e| e.into_response())?;
+
+    match moderation_report(&ctx.db, report_id).await {
+        Ok(mut report) => {
+            open_report(&ctx, &mut report)
+                .await
+                .map_err(|e| e.into_response())?;
+            Ok(Json(report))
+        }
+        Err(e) => Err(e.into_response()),
+    }
+}
+
+async fn claim(
+    db: &PgPool,
+    moderator_id: Uuid,
+    report_id: Uuid,
+) -> Result<ModerationReport, ApiError> {
+    let mut tx = db.begin().await?;
+    let report = sqlx::query!(
+        r#"select status, claimed_by from "reports" where id = $1 for update"#,
+        report_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Report not found".to_string()))?;
+
+    match (report.status.as_str(), report.claimed_by) {
+        ("resolved", _) => {
+            return Err(ApiError::Conflict("Report is already resolved".to_string()))
+        }
+        ("claimed", Some(claimed_by)) if claimed_by == moderator_id => (),
+        ("claimed", _) => {
+            return Err(ApiError::Conflict(
+                "Report is claimed by another moderator".to_string(),
+            ))
+        }
+        _ => {
+            sqlx::query!(
+                r#"update "reports" set status = 'claimed', claimed_by = $2, claimed_at = now() where id = $1"#,
+                report_id,
+                moderator_id
+            )
+            .execute(&mut *tx)
+            .await?;
+            log_action(&mut *tx, report_id, moderator_id, "claim", None).await?;
+        }
+    }
+
+    let report = moderation_report(&mut *tx, report_id).await?;
+    tx.commit().await?;
+    Ok(report)
+}
+
+/// Assigns an open report to the caller. Claiming a report twice is a no-op.
+pub async fn claim_report(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    report_id: Uuid,
+) -> Result<Json<ModerationReport>, Response<Body>> {
+    let moderator_id = moderator_id(&ctx.db, &claims)
+        .await
+        .map_err(|e| e.into_response())?;
+
+    match claim(&ctx.db, moderator_id, report_id).await {
+        Ok(mut report) => {
+            open_report(&ctx, &mut report)
+                .await
+                .map_err(|e| e.into_response())?;
+            Ok(Json(report))
+        }
+        Err(e) => Err(e.into_response()),
+    }
+}
+
+async fn resolve(
+    db: &PgPool,
+    moderator_id: Uuid,
+    report_id: Uuid,
+    body: &ResolveReportBody,
+) -> Result<ModerationReport, ApiError> {
+    let mut tx = db.begin().await?;
+    let report = sqlx::query!(
+        r#"select status, claimed_by, message_id, recipient_id, platform_fingerprint, link_id
+        from "reports" where id = $1 for update"#,
+        report_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Report not found".to_string()))?;
+
+    if report.status == "resolved" {
+        return Err(ApiError::Conflict("Report is already resolved".to_string()));
+    }
+    if report.claimed_by != Some(moderator_id) {
+        return Err(ApiError::Conflict(
+            "Claim the report before resolving it".to_string(),
+        ));
+    }
+
+    match body.action.as_str() {
+        "delete_message" => {
+            sqlx::query!(r#"delete from "messages" where id = $1"#, report.message_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        "block_sender" => {
+            let fingerprint = report
+                .platform_fingerprint
+                .ok_or_else(|| ApiError::BadRequest {
+                    errors: vec!["The sender of this message cannot be identified".to_string()],
+                })?;
+            sqlx::query!(
+                r#"insert into "platform_blocks" (block_id, fingerprint, report_id) values ($1, $2, $3)
+                on conflict (fingerprint) do update set created_at = now()"#,
+                Uuid::new_v4(),
+                fingerprint,
+                report_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        // only the link the message came through, the recipient keeps their
+        // account and other links
+        "suspend_link" => match report.link_id {
+            Some(link_id) => {
+                sqlx::query!(
+                    r#"update "links" set suspended_at = coalesce(suspended_at, now()) where id = $1"#,
+                    link_id
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+            None => {
+                sqlx::query!(
+                    r#"update "users" set profile_link_suspended_at = coalesce(profile_link_suspended_at, now())
+                    where id = $1"#,
+                    report.recipient_id
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+        },
+        _ => (),
+    }
+
+    sqlx::query!(
+        r#"update "reports" set status = 'resolved', resolved_by = $2, resolved_at = now(), resolution = $3
+        where id = $1"#,
+        report_id,
+        moderator_id,
+        body.action
+    )
+    .execute(&mut *tx)
+    .await?;
+    log_action(
+        &mut *tx,
+        report_id,
+        moderator_id,
+        &body.action,
+        body.note.as_deref(),
+    )
+    .await?;
+
+    let report = moderation_report(&mut *tx, report_id).await?;
+    tx.commit().await?;
+    Ok(report)
+}
+
+/// Applies `action` to a report claimed by the caller and closes it.
+pub async fn resolve_report(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    report_id: Uuid,
+    Json(body): Json<ResolveReportBody>,
+) -> Result<Json<ModerationReport>, Response<Body>> {
+    let moderator_id = moderator_id(&ctx.db, &claims)
+        .await
+        .map_err(|e| e.into_response())?;
+
+    match resolve(&ctx.db, moderator_id, report_id, &body).await {
+        Ok(mut report) => {
+            open_report(&ctx, &mut report)
+                .await
+                .map_err(|e| e.into_response())?;
+            Ok(Json(report))
+        }
+        Err(e) => Err(e.into_response()),
+    }
+}
+
+pub async fn list_log(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    query: LogQuery,
+) -> Result<Json<Vec<ModerationLogEntry>>, Response<Body>> {
+    moderator_id(&ctx.db, &claims)
+        .await
+        .map_err(|e| e.into_response())?;
+    let limit = query.limit.unwrap_or(50).clamp(1, 100);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let result = sqlx::query_as!(
+        ModerationLogEntry,
+        r#"select id::text as "entry_id!", report_id::text as "report_id!", moderator_id::text as "moderator_id!",
+            action, note, created_at
+        from "moderation_log"
+        where ($1::uuid is null or report_id = $1)
+        order by created_at desc
+        limit $2 offset $3"#,
+        query.report_id,
+        limit,
+        offset
+    )
+    .fetch_all(&ctx.db)
+    .await;
+
+    match result {
+        Ok(entries) => Ok(Json(entries)),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
@@ -0,0 +1,73 @@
+use crate::{
+    core::{
+        models::{ApiError, Claims},
+        utils::{report_key, unseal},
+    },
+    modules::moderation::models::{Report, ReportMessageBody},
+    ApiContext,
+};
+use axum::{body::Body, http::Response, response::IntoResponse};
+use axum::{Extension, Json};
+use uuid::Uuid;
+
+/// Files a report on a message in the recipient's inbox. The message body and
+/// the sender's platform digest, unsealed from the message, are copied so the
+/// report outlives the message.
+pub async fn report_message(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    message_id: Uuid,
+    Json(body): Json<ReportMessageBody>,
+) -> Result<Json<Report>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let message = sqlx::query!(
+        r#"select body, sealed_body, sealed_platform_fingerprint, link_id from "messages"
+        where id = $1 and recipient_id = $2"#,
+        message_id,
+        user_id
+    )
+    .fetch_optional(&ctx.db)
+    .await
+    .map_err(|e| ApiError::Database(e).into_response())?
+    .ok_or_else(|| ApiError::NotFound("Message not found".to_string()).into_response())?;
+
+    let platform_fingerprint = message
+        .sealed_platform_fingerprint
+        .map(|sealed| {
+            unseal(
+                &report_key(&ctx.config.jwt_secret),
+                user_id.as_bytes(),
+                &sealed,
+            )
+        })
+        .transpose()
+        .map_err(|e| ApiError::InternalServer(e.to_string()).into_response())?;
+
+    let result = sqlx::query_as!(
+        Report,
+        r#"insert into "reports"
+            (message_id, recipient_id, category, note, message_body, sealed_message_body,
+            platform_fingerprint, link_id)
+        values ($1, $2, $3, nullif($4, ''), $5, $6, $7, $8)
+        returning id::text as "report_id!", message_id::text, category, note, status, created_at"#,
+        message_id,
+        user_id,
+        body.category,
+        body.note,
+        message.body,
+        message.sealed_body,
+        platform_fingerprint,
+        message.link_id
+    )
+    .fetch_one(&ctx.db)
+    .await;
+
+    match result {
+        Ok(report) => Ok(Json(report)),
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            Err(ApiError::Conflict("Message has already been reported".to_string()).into_response())
+        }
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
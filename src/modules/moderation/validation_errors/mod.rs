@@ -0,0 +1,3 @@
+mod report_error;
+
+pub use report_error::*;
@@ -0,0 +1,22 @@
+use crate::core::{error_transformer::error_transformer, traits::TransformValidationErrors};
+use validator::ValidationErrors;
+
+pub struct ReportMessageValidationError;
+impl TransformValidationErrors for ReportMessageValidationError {
+    fn new() -> Self {
+        ReportMessageValidationError
+    }
+    fn transform_errors(&self, errors: ValidationErrors) -> Vec<String> {
+        error_transformer(errors)
+    }
+}
+
+pub struct ResolveReportValidationError;
+impl TransformValidationErrors for ResolveReportValidationError {
+    fn new() -> Self {
+        ResolveReportValidationError
+    }
+    fn transform_errors(&self, errors: ValidationErrors) -> Vec<String> {
+        error_transformer(errors)
+    }
+}
@@ -0,0 +1,3 @@
+mod profile_api;
+
+pub use profile_api::*;
@@ -0,0 +1,127 @@
+use crate::{
+    core::extractors::{ClientInfo, CustomPath, CustomQuery, ValidatedBody},
+    modules::profile::{
+        models::{
+            AnswersQuery, Challenge, PublicLink, PublicProfile, PublishedAnswer, ReactionBody,
+            ReactionCounts, SubmitMessageBody, SubmittedMessage, Target,
+        },
+        service::{
+            add_reaction, find_public_link, find_public_profile, issue_challenge, list_answers,
+            remove_reaction, submit_message, POW_SOLUTION_HEADER,
+        },
+        validation_errors::{ReactionValidationError, SubmitMessageValidationError},
+    },
+    ApiContext,
+};
+use axum::{
+    body::Body,
+    http::{HeaderMap, StatusCode},
+    response::{Json, Response},
+    Extension,
+};
+use uuid::Uuid;
+
+fn pow_solution(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(POW_SOLUTION_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+pub async fn get_public_profile(
+    ctx: Extension<ApiContext>,
+    CustomPath(profile_link): CustomPath<String>,
+) -> Result<Json<PublicProfile>, Response<Body>> {
+    let profile = find_public_profile(ctx, profile_link).await?;
+    Ok(profile)
+}
+
+pub async fn handle_submit_message(
+    ctx: Extension<ApiContext>,
+    CustomPath(profile_link): CustomPath<String>,
+    client: ClientInfo,
+    headers: HeaderMap,
+    ValidatedBody(body, _): ValidatedBody<SubmitMessageBody, SubmitMessageValidationError>,
+) -> Result<(StatusCode, Json<SubmittedMessage>), Response<Body>> {
+    let message = submit_message(
+        ctx,
+        Target::Profile(profile_link),
+        client,
+        pow_solution(&headers),
+        Json(body),
+    )
+    .await?;
+    Ok((StatusCode::CREATED, message))
+}
+
+pub async fn get_challenge(
+    ctx: Extension<ApiContext>,
+    CustomPath(profile_link): CustomPath<String>,
+    client: ClientInfo,
+) -> Result<Json<Challenge>, Response<Body>> {
+    let challenge = issue_challenge(ctx, Target::Profile(profile_link), client).await?;
+    Ok(challenge)
+}
+
+pub async fn get_answers(
+    ctx: Extension<ApiContext>,
+    CustomPath(profile_link): CustomPath<String>,
+    CustomQuery(query): CustomQuery<AnswersQuery>,
+) -> Result<Json<Vec<PublishedAnswer>>, Response<Body>> {
+    let answers = list_answers(ctx, profile_link, query).await?;
+    Ok(answers)
+}
+
+pub async fn handle_add_reaction(
+    ctx: Extension<ApiContext>,
+    CustomPath((profile_link, answer_id)): CustomPath<(String, Uuid)>,
+    client: ClientInfo,
+    ValidatedBody(body, _): ValidatedBody<ReactionBody, ReactionValidationError>,
+) -> Result<Json<ReactionCounts>, Response<Body>> {
+    let counts = add_reaction(ctx, profile_link, answer_id, client, Json(body)).await?;
+    Ok(counts)
+}
+
+pub async fn handle_remove_reaction(
+    ctx: Extension<ApiContext>,
+    CustomPath((profile_link, answer_id, reaction)): CustomPath<(String, Uuid, String)>,
+    client: ClientInfo,
+) -> Result<Json<ReactionCounts>, Response<Body>> {
+    let counts = remove_reaction(ctx, profile_link, answer_id, reaction, client).await?;
+    Ok(counts)
+}
+
+pub async fn get_public_link(
+    ctx: Extension<ApiContext>,
+    CustomPath(slug): CustomPath<String>,
+) -> Result<Json<PublicLink>, Response<Body>> {
+    let link = find_public_link(ctx, slug).await?;
+    Ok(link)
+}
+
+pub async fn handle_submit_link_message(
+    ctx: Extension<ApiContext>,
+    CustomPath(slug): CustomPath<String>,
+    client: ClientInfo,
+    headers: HeaderMap,
+    ValidatedBody(body, _): ValidatedBody<SubmitMessageBody, SubmitMessageValidationError>,
+) -> Result<(StatusCode, Json<SubmittedMessage>), Response<Body>> {
+    let message = submit_message(
+        ctx,
+        Target::Link(slug),
+        client,
+        pow_solution(&headers),
+        Json(body),
+    )
+    .await?;
+    Ok((StatusCode::CREATED, message))
+}
+
+pub async fn get_link_challenge(
+    ctx: Extension<ApiContext>,
+    CustomPath(slug): CustomPath<String>,
+    client: ClientInfo,
+) -> Result<Json<Challenge>, Response<Body>> {
+    let challenge = issue_challenge(ctx, Target::Link(slug), client).await?;
+    Ok(challenge)
+}
@@ -0,0 +1,7 @@
+mod profile_route;
+pub use profile_route::*;
+
+pub mod controllers;
+pub mod models;
+pub mod service;
+pub mod validation_errors;
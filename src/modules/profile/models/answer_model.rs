@@ -0,0 +1,24 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// A published question and its answer.
+#[derive(Serialize)]
+pub struct PublishedAnswer {
+    pub answer_id: String,
+    pub question: String,
+    /// Set while `question` is still sealed at rest.
+    #[serde(skip_serializing)]
+    pub sealed_question: Option<Vec<u8>>,
+    pub answer: String,
+    pub answered_at: NaiveDateTime,
+    pub published_at: NaiveDateTime,
+    pub reactions_enabled: bool,
+    /// Count of each reaction, empty while reactions are disabled.
+    pub reactions: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+pub struct AnswersQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
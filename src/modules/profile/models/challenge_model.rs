@@ -0,0 +1,10 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct Challenge {
+    pub challenge: String,
+    pub algorithm: String,
+    pub difficulty: u32,
+    pub expires_at: NaiveDateTime,
+}
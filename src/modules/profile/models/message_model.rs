@@ -0,0 +1,31 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::core::utils::MAX_E2E_ENVELOPE;
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct SubmitMessageBody {
+    /// The message, or its envelope when `encryption_key_id` is set. The
+    /// recipient's own limits are checked after.
+    #[validate(length(min = 1, max = "MAX_E2E_ENVELOPE"))]
+    pub body: String,
+
+    /// The recipient key `body` was encrypted to with `encrypt_e2e_message`.
+    pub encryption_key_id: Option<Uuid>,
+
+    /// Ask for a claim code to follow the message with `GET /replies/:code`.
+    #[serde(default)]
+    pub claim_code: bool,
+}
+
+#[derive(Serialize)]
+pub struct SubmittedMessage {
+    pub message_id: String,
+    pub created_at: NaiveDateTime,
+    /// Only returned here, the server keeps a digest of it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claim_code: Option<String>,
+}
@@ -0,0 +1,11 @@
+mod answer_model;
+mod challenge_model;
+mod message_model;
+mod profile_model;
+mod reaction_model;
+
+pub use answer_model::*;
+pub use challenge_model::*;
+pub use message_model::*;
+pub use profile_model::*;
+pub use reaction_model::*;
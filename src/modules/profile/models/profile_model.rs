@@ -0,0 +1,131 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{core::utils::E2E_ALGORITHM, modules::user::models::MessageSettings};
+
+pub struct Recipient {
+    pub id: Uuid,
+    pub name: String,
+    /// `None` for a user only reachable through named links.
+    pub profile_link: Option<String>,
+    pub bio: Option<String>,
+    pub prompt: Option<String>,
+    pub avatar: Option<String>,
+    /// The key senders may encrypt to, `None` until the user registers one.
+    pub encryption_key_id: Option<Uuid>,
+    pub encryption_key: Option<Vec<u8>>,
+}
+
+impl Recipient {
+    pub fn public_encryption_key(&self) -> Option<PublicEncryptionKey> {
+        let (id, key) = self.encryption_key_id.zip(self.encryption_key.as_ref())?;
+        Some(PublicEncryptionKey {
+            key_id: id.to_string(),
+            public_key: URL_SAFE_NO_PAD.encode(key),
+            algorithm: E2E_ALGORITHM,
+        })
+    }
+}
+
+/// What a sender addressed: a profile link or the slug of one of its named links.
+pub enum Target {
+    Profile(String),
+    Link(String),
+}
+
+impl Target {
+    /// What challenges are bound to. Slugs get a prefix, `/` never appears in a
+    /// profile link.
+    pub fn key(&self) -> String {
+        match self {
+            Target::Profile(profile_link) => profile_link.clone(),
+            Target::Link(slug) => format!("l/{slug}"),
+        }
+    }
+}
+
+pub struct TargetLink {
+    pub id: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub prompt: Option<String>,
+    pub enabled: bool,
+    pub expires_at: Option<NaiveDateTime>,
+    pub max_messages: Option<i32>,
+    pub message_count: i32,
+}
+
+/// A resolved `Target`, with the link's overrides already applied to `settings`.
+pub struct Destination {
+    pub recipient: Recipient,
+    pub settings: MessageSettings,
+    pub link: Option<TargetLink>,
+}
+
+impl Destination {
+    /// Whether the link passed its deadline or received its last message. Unlike
+    /// a closed link, it never accepts messages again until its owner edits it.
+    pub fn is_gone(&self, now: DateTime<Utc>) -> bool {
+        self.link.as_ref().is_some_and(|link| {
+            link.expires_at
+                .is_some_and(|expires| expires <= now.naive_utc())
+                || link
+                    .max_messages
+                    .is_some_and(|max| link.message_count >= max)
+        })
+    }
+
+    pub fn is_accepting(&self, now: DateTime<Utc>) -> bool {
+        let link_open = self
+            .link
+            .as_ref()
+            .is_none_or(|link| link.enabled && !self.is_gone(now));
+        link_open && self.settings.is_accepting(now)
+    }
+}
+
+/// The recipient's key to encrypt a message to, see `core::utils::encrypt_e2e_message`.
+/// Its `key_id` is sent along with the envelope.
+#[derive(Serialize)]
+pub struct PublicEncryptionKey {
+    pub key_id: String,
+    /// The base64url X25519 public key.
+    pub public_key: String,
+    pub algorithm: &'static str,
+}
+
+#[derive(Serialize)]
+pub struct PublicProfile {
+    pub name: String,
+    pub profile_link: String,
+    pub bio: Option<String>,
+    pub prompt: Option<String>,
+    pub avatar: Option<String>,
+    pub accepting: bool,
+    pub closed_message: Option<String>,
+    pub min_length: i32,
+    pub max_length: i32,
+    pub encryption_key: Option<PublicEncryptionKey>,
+    /// Plaintext messages are refused.
+    pub encryption_required: bool,
+}
+
+/// The page of a named link. The owner's profile link is not revealed.
+#[derive(Serialize)]
+pub struct PublicLink {
+    pub slug: String,
+    pub title: String,
+    pub name: String,
+    pub avatar: Option<String>,
+    pub prompt: Option<String>,
+    pub accepting: bool,
+    pub closed_message: Option<String>,
+    pub min_length: i32,
+    pub max_length: i32,
+    pub expires_at: Option<NaiveDateTime>,
+    pub encryption_key: Option<PublicEncryptionKey>,
+    /// Plaintext messages are refused.
+    pub encryption_required: bool,
+}
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+/// The reactions visitors can leave on an answer.
+pub const REACTIONS: [&str; 6] = ["like", "love", "laugh", "wow", "sad", "fire"];
+
+fn validate_reaction(reaction: &str) -> Result<(), ValidationError> {
+    match REACTIONS.contains(&reaction) {
+        true => Ok(()),
+        false => Err(ValidationError::new("reaction")),
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct ReactionBody {
+    #[validate(custom(
+        function = "validate_reaction",
+        message = "must be one of like, love, laugh, wow, sad or fire"
+    ))]
+    pub reaction: String,
+}
+
+#[derive(Serialize)]
+pub struct ReactionCounts {
+    pub answer_id: String,
+    /// Count of each reaction left on the answer, eg: `{"like": 3}`.
+    pub reactions: serde_json::Value,
+}
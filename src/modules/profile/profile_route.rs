@@ -0,0 +1,41 @@
+use axum::{
+    routing::{delete, get, post},
+    Router,
+};
+
+use super::controllers::{
+    get_answers, get_challenge, get_link_challenge, get_public_link, get_public_profile,
+    handle_add_reaction, handle_remove_reaction, handle_submit_link_message, handle_submit_message,
+};
+
+pub fn profile_routes() -> Router {
+    Router::new()
+        .route("/:profile_link", get(get_public_profile))
+        .route("/:profile_link/answers", get(get_answers))
+        .route(
+            "/:profile_link/answers/:answer_id/reactions",
+            post(handle_add_reaction),
+        )
+        .route(
+            "/:profile_link/answers/:answer_id/reactions/:reaction",
+            delete(handle_remove_reaction),
+        )
+        .route("/:profile_link/challenge", get(get_challenge))
+}
+
+/// Message submission to a profile link, kept apart from `profile_routes` so
+/// only submissions count against the submission limits.
+pub fn profile_message_routes() -> Router {
+    Router::new().route("/:profile_link/messages", post(handle_submit_message))
+}
+
+pub fn link_routes() -> Router {
+    Router::new()
+        .route("/:slug", get(get_public_link))
+        .route("/:slug/challenge", get(get_link_challenge))
+}
+
+/// Message submission to a named link, see `profile_message_routes`.
+pub fn link_message_routes() -> Router {
+    Router::new().route("/:slug/messages", post(handle_submit_link_message))
+}
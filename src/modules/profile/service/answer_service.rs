@@ -0,0 +1,60 @@
+use crate::{
+    core::models::ApiError,
+    modules::profile::{
+        models::{AnswersQuery, PublishedAnswer},
+        service::find_recipient,
+    },
+    ApiContext,
+};
+use axum::{body::Body, http::Response, response::IntoResponse};
+use axum::{Extension, Json};
+
+/// Published answers, newest first. Messages that were never published, or
+/// have since been unpublished, are never returned here.
+pub async fn list_answers(
+    ctx: Extension<ApiContext>,
+    profile_link: String,
+    query: AnswersQuery,
+) -> Result<Json<Vec<PublishedAnswer>>, Response<Body>> {
+    let (recipient, _) = find_recipient(&ctx.db, &profile_link)
+        .await
+        .map_err(|e| e.into_response())?;
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let result = sqlx::query_as!(
+        PublishedAnswer,
+        r#"select m.id::text as "answer_id!", coalesce(m.body, '') as "question!", m.sealed_body as sealed_question, m.reply as "answer!",
+            m.replied_at as "answered_at!", m.published_at as "published_at!", m.reactions_enabled,
+            case when m.reactions_enabled then (
+                select coalesce(jsonb_object_agg(reaction, count), '{}') from (
+                    select reaction, count(*) from "reactions" where message_id = m.id group by reaction
+                ) counts
+            ) else '{}' end as "reactions!"
+        from "messages" m
+        where m.recipient_id = $1 and m.published_at is not null and m.reply_visibility = 'public'
+        order by m.published_at desc
+        limit $2 offset $3"#,
+        recipient.id,
+        limit,
+        offset
+    )
+    .fetch_all(&ctx.db)
+    .await;
+
+    match result {
+        Ok(mut answers) => {
+            for answer in &mut answers {
+                if let Some(sealed) = answer.sealed_question.take() {
+                    answer.question = ctx
+                        .keyring
+                        .open(&ctx.db, recipient.id, &sealed)
+                        .await
+                        .map_err(|e| e.into_response())?;
+                }
+            }
+            Ok(Json(answers))
+        }
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
@@ -0,0 +1,240 @@
+use std::time::Duration;
+
+use axum::{body::Body, http::Response, response::IntoResponse};
+use axum::{Extension, Json};
+use chrono::{DateTime, Utc};
+use hmac::Mac;
+use uuid::Uuid;
+
+use crate::{
+    config::RateLimitQuota,
+    core::{
+        extractors::ClientInfo,
+        models::ApiError,
+        rate_limit::too_many_requests,
+        utils::{challenge_key, challenge_mac, solves},
+    },
+    modules::profile::{
+        models::{Challenge, Target},
+        service::{closed_message, ensure_not_gone, find_destination, sender_fingerprints},
+    },
+    ApiContext,
+};
+
+/// Header carrying `{challenge}:{counter}` on message submission.
+pub const POW_SOLUTION_HEADER: &str = "x-pow-solution";
+
+/// Extra bits required from a sender refused by its rate limit within `PENALTY_WINDOW`.
+const PENALTY_BITS: u32 = 4;
+const PENALTY_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+/// Rate limit bucket of a sender for one recipient.
+pub fn sender_bucket(fingerprint: &[u8]) -> String {
+    format!("sender:{}", hex::encode(fingerprint))
+}
+
+/// Rate limit bucket of a sender's reactions to one recipient's answers.
+fn reaction_bucket(fingerprint: &[u8]) -> String {
+    format!("reaction:{}", hex::encode(fingerprint))
+}
+
+async fn acquire(
+    ctx: &ApiContext,
+    bucket: &str,
+    quota: RateLimitQuota,
+) -> Result<(), Response<Body>> {
+    match ctx
+        .rate_limit_store
+        .acquire(
+            bucket,
+            quota.capacity,
+            quota.capacity as f64 / quota.period_seconds as f64,
+        )
+        .await
+    {
+        Ok(decision) if !decision.allowed => Err(too_many_requests(&decision)),
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::warn!("Rate limit store failed for {bucket}: {e}");
+            Ok(())
+        }
+    }
+}
+
+/// Takes a token from the sender's own bucket, on top of the per-address and
+/// per-link limits applied to every submission. Called once the proof of work
+/// checked out, so an unsolved submission costs the sender nothing.
+pub async fn limit_sender(
+    ctx: &ApiContext,
+    fingerprints: &[Vec<u8>],
+) -> Result<(), Response<Body>> {
+    let bucket = sender_bucket(fingerprints.first().map(Vec::as_slice).unwrap_or_default());
+    acquire(ctx, &bucket, ctx.config.rate_limit_sender).await
+}
+
+/// Takes a token from the sender's reaction bucket, kept apart from the one
+/// for submissions so reacting never costs a sender a message or the other
+/// way round.
+pub async fn limit_reactions(
+    ctx: &ApiContext,
+    fingerprints: &[Vec<u8>],
+) -> Result<(), Response<Body>> {
+    let bucket = reaction_bucket(fingerprints.first().map(Vec::as_slice).unwrap_or_default());
+    acquire(ctx, &bucket, ctx.config.rate_limit_reaction).await
+}
+
+/// Starts from the configured difficulty, adds a bit each time the messages the
+/// link received in the last minute double past the burst threshold, and adds
+/// `PENALTY_BITS` for a sender that was rate limited recently. The profile link
+/// counts as a link of its own, with `link_id` null.
+async fn challenge_difficulty(
+    ctx: &ApiContext,
+    recipient_id: Uuid,
+    link_id: Option<Uuid>,
+    fingerprint: &[u8],
+) -> Result<u32, ApiError> {
+    let mut difficulty = ctx.config.pow_difficulty;
+
+    let recent = sqlx::query_scalar!(
+        r#"select count(*) as "count!" from "messages"
+        where recipient_id = $1 and link_id is not distinct from $2
+            and created_at > now() - interval '1 minute'"#,
+        recipient_id,
+        link_id
+    )
+    .fetch_one(&ctx.db)
+    .await? as u32;
+    let threshold = ctx.config.pow_burst_threshold;
+    if recent >= threshold {
+        difficulty += 1 + (recent / threshold).ilog2();
+    }
+
+    let limited = ctx
+        .rate_limit_store
+        .recently_limited(&sender_bucket(fingerprint), PENALTY_WINDOW)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("Rate limit store failed: {e}");
+            false
+        });
+    if limited {
+        difficulty += PENALTY_BITS;
+    }
+
+    Ok(difficulty.min(ctx.config.pow_max_difficulty))
+}
+
+pub async fn issue_challenge(
+    ctx: Extension<ApiContext>,
+    target: Target,
+    client: ClientInfo,
+) -> Result<Json<Challenge>, Response<Body>> {
+    let destination = find_destination(&ctx.db, &target)
+        .await
+        .map_err(|e| e.into_response())?;
+    let recipient = &destination.recipient;
+
+    ensure_not_gone(&destination).map_err(|e| e.into_response())?;
+    if !destination.is_accepting(Utc::now()) {
+        return Err(ApiError::Forbidden(closed_message(&destination.settings)).into_response());
+    }
+
+    let fingerprints = sender_fingerprints(&ctx.db, recipient.id, &client)
+        .await
+        .map_err(|e| e.into_response())?;
+    let today = fingerprints.first().map(Vec::as_slice).unwrap_or_default();
+    let link_id = destination.link.as_ref().map(|link| link.id);
+    let difficulty = challenge_difficulty(&ctx, recipient.id, link_id, today)
+        .await
+        .map_err(|e| e.into_response())?;
+
+    let expires = Utc::now().timestamp() + ctx.config.pow_ttl_seconds as i64;
+    let nonce: [u8; 16] = rand::random();
+    let signature = challenge_mac(
+        &challenge_key(&ctx.config.jwt_secret),
+        &target.key(),
+        expires,
+        difficulty,
+        &nonce,
+        today,
+    )
+    .finalize()
+    .into_bytes();
+
+    Ok(Json(Challenge {
+        challenge: format!(
+            "{expires}.{difficulty}.{}.{}",
+            hex::encode(nonce),
+            hex::encode(signature)
+        ),
+        algorithm: "sha256".to_string(),
+        difficulty,
+        expires_at: DateTime::from_timestamp(expires, 0)
+            .unwrap_or_default()
+            .naive_utc(),
+    }))
+}
+
+/// Checks a solution to a challenge issued for a `Target::key` to one of the
+/// sender's `fingerprints`, and spends the challenge so it cannot be replayed.
+pub async fn redeem_solution(
+    ctx: &ApiContext,
+    target: &str,
+    fingerprints: &[Vec<u8>],
+    solution: Option<&str>,
+) -> Result<(), ApiError> {
+    let invalid = || {
+        ApiError::Forbidden(
+            "A valid proof-of-work solution is required, request a new challenge".to_string(),
+        )
+    };
+
+    let (challenge, counter) = solution
+        .and_then(|solution| solution.rsplit_once(':'))
+        .ok_or_else(invalid)?;
+    let (expires, difficulty, nonce, signature) = match challenge.split('.').collect::<Vec<_>>()[..]
+    {
+        [expires, difficulty, nonce, signature] => (
+            expires.parse::<i64>().ok(),
+            difficulty.parse::<u32>().ok(),
+            hex::decode(nonce).ok(),
+            hex::decode(signature).ok(),
+        ),
+        _ => return Err(invalid()),
+    };
+    let (Some(expires), Some(difficulty), Some(nonce), Some(signature)) =
+        (expires, difficulty, nonce, signature)
+    else {
+        return Err(invalid());
+    };
+
+    let key = challenge_key(&ctx.config.jwt_secret);
+    let signed = fingerprints.iter().any(|fingerprint| {
+        challenge_mac(&key, target, expires, difficulty, &nonce, fingerprint)
+            .verify_slice(&signature)
+            .is_ok()
+    });
+    if !signed || expires < Utc::now().timestamp() || counter.len() > 32 {
+        return Err(invalid());
+    }
+    if !solves(challenge, counter, difficulty) {
+        return Err(invalid());
+    }
+
+    sqlx::query!(r#"delete from "pow_redemptions" where expires_at < now()"#)
+        .execute(&ctx.db)
+        .await?;
+    let redeemed = sqlx::query!(
+        r#"insert into "pow_redemptions" (nonce, expires_at) values ($1, to_timestamp($2))
+        on conflict (nonce) do nothing"#,
+        nonce,
+        expires as f64
+    )
+    .execute(&ctx.db)
+    .await?;
+
+    if redeemed.rows_affected() == 0 {
+        return Err(invalid());
+    }
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::core::{extractors::ClientInfo, models::ApiError, utils::sender_fingerprint};
+
+/// Salts, and the blocks that can only be matched through them, are discarded
+/// after this many days so fingerprints cannot be linked over longer periods.
+pub const FINGERPRINT_RETENTION_DAYS: i64 = 30;
+
+/// Today's salt is created on first use, which is also when expired salts and
+/// blocks get discarded.
+async fn fingerprint_salts(db: &PgPool) -> Result<Vec<Vec<u8>>, ApiError> {
+    let today = Utc::now().date_naive();
+    let salt: [u8; 32] = rand::random();
+
+    let created = sqlx::query!(
+        r#"insert into "fingerprint_salts" (day, salt) values ($1, $2) on conflict (day) do nothing"#,
+        today,
+        &salt[..]
+    )
+    .execute(db)
+    .await?;
+
+    if created.rows_affected() > 0 {
+        let expired = today - Duration::days(FINGERPRINT_RETENTION_DAYS);
+        sqlx::query!(
+            r#"delete from "fingerprint_salts" where day <= $1"#,
+            expired
+        )
+        .execute(db)
+        .await?;
+        sqlx::query!(
+            r#"delete from "sender_blocks" where created_at::date <= $1"#,
+            expired
+        )
+        .execute(db)
+        .await?;
+        sqlx::query!(
+            r#"delete from "platform_blocks" where created_at::date <= $1"#,
+            expired
+        )
+        .execute(db)
+        .await?;
+    }
+
+    let salts = sqlx::query_scalar!(r#"select salt from "fingerprint_salts" order by day desc"#)
+        .fetch_all(db)
+        .await?;
+    Ok(salts)
+}
+
+/// Digests of the client for every retained salt, today's first.
+pub async fn sender_fingerprints(
+    db: &PgPool,
+    recipient_id: Uuid,
+    client: &ClientInfo,
+) -> Result<Vec<Vec<u8>>, ApiError> {
+    Ok(fingerprint_salts(db)
+        .await?
+        .iter()
+        .map(|salt| sender_fingerprint(salt, recipient_id, client))
+        .collect())
+}
+
+/// Digests of the client for the whole platform. The nil recipient id stands
+/// for every recipient, so these only ever match platform-wide blocks.
+pub async fn platform_fingerprints(
+    db: &PgPool,
+    client: &ClientInfo,
+) -> Result<Vec<Vec<u8>>, ApiError> {
+    sender_fingerprints(db, Uuid::nil(), client).await
+}
+
+/// Whether any of the sender's digests is blocked by the recipient. A block
+/// matched through an older salt is carried over to today's digest, so it
+/// lasts for as long as the sender keeps coming back.
+pub async fn is_blocked_sender(
+    db: &PgPool,
+    recipient_id: Uuid,
+    fingerprints: &[Vec<u8>],
+) -> Result<bool, ApiError> {
+    let block_id = sqlx::query_scalar!(
+        r#"select block_id from "sender_blocks" where recipient_id = $1 and fingerprint = any($2)
+        order by created_at desc limit 1"#,
+        recipient_id,
+        fingerprints
+    )
+    .fetch_optional(db)
+    .await?;
+
+    match (block_id, fingerprints.first()) {
+        (Some(block_id), Some(today)) => {
+            sqlx::query!(
+                r#"insert into "sender_blocks" (block_id, recipient_id, fingerprint) values ($1, $2, $3)
+                on conflict (recipient_id, fingerprint) do nothing"#,
+                block_id,
+                recipient_id,
+                today
+            )
+            .execute(db)
+            .await?;
+            Ok(true)
+        }
+        (Some(_), None) => Ok(true),
+        (None, _) => Ok(false),
+    }
+}
+
+/// Whether any of the sender's platform digests was blocked by a moderator,
+/// carried over to today's digest the same way as `is_blocked_sender`.
+pub async fn is_platform_blocked(db: &PgPool, fingerprints: &[Vec<u8>]) -> Result<bool, ApiError> {
+    let block = sqlx::query!(
+        r#"select block_id, report_id from "platform_blocks" where fingerprint = any($1)
+        order by created_at desc limit 1"#,
+        fingerprints
+    )
+    .fetch_optional(db)
+    .await?;
+
+    match (block, fingerprints.first()) {
+        (Some(block), Some(today)) => {
+            sqlx::query!(
+                r#"insert into "platform_blocks" (block_id, fingerprint, report_id) values ($1, $2, $3)
+                on conflict (fingerprint) do nothing"#,
+                block.block_id,
+                today,
+                block.report_id
+            )
+            .execute(db)
+            .await?;
+            Ok(true)
+        }
+        (Some(_), None) => Ok(true),
+        (None, _) => Ok(false),
+    }
+}
+
+/// Whether the sender is blocked platform-wide or by the recipient.
+pub async fn is_blocked(
+    db: &PgPool,
+    recipient_id: Uuid,
+    fingerprints: &[Vec<u8>],
+    platform_fingerprints: &[Vec<u8>],
+) -> Result<bool, ApiError> {
+    if is_platform_blocked(db, platform_fingerprints).await? {
+        return Ok(true);
+    }
+    is_blocked_sender(db, recipient_id, fingerprints).await
+}
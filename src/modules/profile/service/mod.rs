@@ -0,0 +1,11 @@
+mod answer_service;
+mod challenge_service;
+mod fingerprint_service;
+mod profile_service;
+mod reaction_service;
+
+pub use answer_service::*;
+pub use challenge_service::*;
+pub use fingerprint_service::*;
+pub use profile_service::*;
+pub use reaction_service::*;
@@ -0,0 +1,480 @@
+use crate::{
+    core::{
+        classifier::{classify, content_hash, content_key},
+        extractors::ClientInfo,
+        models::{ApiError, IncomingMessage},
+        utils::{e2e_plaintext_length, report_key, seal},
+    },
+    modules::{
+        profile::{
+            models::{
+                Destination, PublicLink, PublicProfile, Recipient, SubmitMessageBody,
+                SubmittedMessage, Target, TargetLink,
+            },
+            service::{
+                is_blocked, limit_sender, platform_fingerprints, redeem_solution,
+                sender_fingerprints,
+            },
+        },
+        replies::service::claim_hash,
+        user::{models::MessageSettings, service::message_settings},
+    },
+    ApiContext,
+};
+use axum::{body::Body, http::Response, response::IntoResponse};
+use axum::{Extension, Json};
+use chrono::Utc;
+use sqlx::{PgConnection, PgPool};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+const DEFAULT_CLOSED_MESSAGE: &str = "This link is not accepting messages right now";
+const GONE_MESSAGE: &str = "This link has expired";
+
+pub async fn find_recipient(
+    db: &PgPool,
+    profile_link: &str,
+) -> Result<(Recipient, MessageSettings), ApiError> {
+    let recipient = sqlx::query_as!(
+        Recipient,
+        r#"select u.id, u.name, u.profile_link, u.bio, u.prompt, u.avatar,
+            k.id as "encryption_key_id?", k.public_key as "encryption_key?"
+        from "users" u left join "encryption_keys" k on k.user_id = u.id and k.revoked_at is null
+        where u.profile_link = $1 and u.suspended_at is null and u.profile_link_suspended_at is null"#,
+        profile_link
+    )
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Profile not found".to_string()))?;
+
+    let settings = message_settings(db, recipient.id).await?;
+    Ok((recipient, settings))
+}
+
+/// Resolves what a sender addressed to its recipient. A named link must belong to
+/// an active user and overrides their settings where it sets its own.
+pub async fn find_destination(db: &PgPool, target: &Target) -> Result<Destination, ApiError> {
+    let slug = match target {
+        Target::Profile(profile_link) => {
+            let (recipient, settings) = find_recipient(db, profile_link).await?;
+            return Ok(Destination {
+                recipient,
+                settings,
+                link: None,
+            });
+        }
+        Target::Link(slug) => slug,
+    };
+
+    let row = sqlx::query!(
+        r#"select u.id as user_id, u.name as user_name, u.profile_link, u.bio,
+            u.prompt as user_prompt, u.avatar, l.id, l.slug, l.name, l.prompt, l.enabled, l.expires_at,
+            l.max_messages, l.message_count, l.min_length, l.max_length, l.closed_message, l.filter_threshold,
+            l.require_encryption, k.id as "encryption_key_id?", k.public_key as "encryption_key?"
+        from "links" l join "users" u on u.id = l.user_id
+            left join "encryption_keys" k on k.user_id = u.id and k.revoked_at is null
+        where l.slug = $1 and l.suspended_at is null and u.suspended_at is null"#,
+        slug
+    )
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Link not found".to_string()))?;
+
+    let mut settings = message_settings(db, row.user_id).await?;
+    settings.min_length = row.min_length.unwrap_or(settings.min_length);
+    settings.max_length = row.max_length.unwrap_or(settings.max_length);
+    settings.closed_message = row.closed_message.or(settings.closed_message);
+    settings.filter_threshold = row.filter_threshold.unwrap_or(settings.filter_threshold);
+    settings.require_encryption = row
+        .require_encryption
+        .unwrap_or(settings.require_encryption);
+
+    Ok(Destination {
+        recipient: Recipient {
+            id: row.user_id,
+            name: row.user_name,
+            profile_link: row.profile_link,
+            bio: row.bio,
+            prompt: row.user_prompt,
+            avatar: row.avatar,
+            encryption_key_id: row.encryption_key_id,
+            encryption_key: row.encryption_key,
+        },
+        settings,
+        link: Some(TargetLink {
+            id: row.id,
+            slug: row.slug,
+            name: row.name,
+            prompt: row.prompt,
+            enabled: row.enabled,
+            expires_at: row.expires_at,
+            max_messages: row.max_messages,
+            message_count: row.message_count,
+        }),
+    })
+}
+
+/// Refuses a link that expired or received its last message with 410 Gone.
+pub fn ensure_not_gone(destination: &Destination) -> Result<(), ApiError> {
+    if destination.is_gone(Utc::now()) {
+        return Err(ApiError::Gone(GONE_MESSAGE.to_string()));
+    }
+    Ok(())
+}
+
+/// Counts a message against the limits of the link it came through, in the
+/// transaction inserting it. The row lock queues concurrent senders, so a
+/// capped link never accepts more than `max_messages`.
+async fn count_link_message(tx: &mut PgConnection, link_id: Uuid) -> Result<(), ApiError> {
+    let counted = sqlx::query!(
+        r#"update "links" set message_count = message_count + 1
+        where id = $1 and (expires_at is null or expires_at > now())
+            and (max_messages is null or message_count < max_messages)"#,
+        link_id
+    )
+    .execute(tx)
+    .await?;
+
+    if counted.rows_affected() == 0 {
+        return Err(ApiError::Gone(GONE_MESSAGE.to_string()));
+    }
+    Ok(())
+}
+
+/// Refuses a message whose length in characters, somewhere between `shortest`
+/// and `longest`, cannot be within the recipient's limits.
+fn check_length(settings: &MessageSettings, shortest: i32, longest: i32) -> Result<(), ApiError> {
+    if longest < settings.min_length {
+        return Err(ApiError::BadRequest {
+            errors: vec![format!(
+                "body: minimum length is {} characters.",
+                settings.min_length
+            )],
+        });
+    }
+    if shortest > settings.max_length {
+        return Err(ApiError::BadRequest {
+            errors: vec![format!(
+                "body: maximum length is {} characters.",
+                settings.max_length
+            )],
+        });
+    }
+    Ok(())
+}
+
+/// Checks an end-to-end encrypted body was sealed for the recipient's current
+/// key. Only its size can be held to the length limits, a character being one
+/// to four bytes of the plaintext.
+fn check_envelope(
+    recipient: &Recipient,
+    settings: &MessageSettings,
+    key_id: Uuid,
+    envelope: &str,
+) -> Result<(), ApiError> {
+    match recipient.encryption_key_id {
+        None => {
+            return Err(ApiError::Conflict(
+                "The recipient has no encryption key".to_string(),
+            ))
+        }
+        Some(current) if current != key_id => {
+            return Err(ApiError::Conflict(
+                "The recipient's encryption key changed, encrypt the message to the new one"
+                    .to_string(),
+            ))
+        }
+        Some(_) => {}
+    }
+
+    let bytes = e2e_plaintext_length(envelope).map_err(|e| ApiError::BadRequest {
+        errors: vec![format!("body: {e}.")],
+    })? as i32;
+    check_length(settings, (bytes + 3) / 4, bytes)
+}
+
+pub fn closed_message(settings: &MessageSettings) -> String {
+    settings
+        .closed_message
+        .clone()
+        .unwrap_or_else(|| DEFAULT_CLOSED_MESSAGE.to_string())
+}
+
+pub async fn find_public_profile(
+    ctx: Extension<ApiContext>,
+    profile_link: String,
+) -> Result<Json<PublicProfile>, Response<Body>> {
+    let (recipient, settings) = find_recipient(&ctx.db, &profile_link)
+        .await
+        .map_err(|e| e.into_response())?;
+
+    let accepting = settings.is_accepting(Utc::now());
+    let encryption_key = recipient.public_encryption_key();
+    Ok(Json(PublicProfile {
+        name: recipient.name,
+        profile_link: recipient.profile_link.unwrap_or(profile_link),
+        bio: recipient.bio,
+        prompt: recipient.prompt,
+        avatar: recipient.avatar,
+        accepting,
+        closed_message: (!accepting).then(|| closed_message(&settings)),
+        min_length: settings.min_length,
+        max_length: settings.max_length,
+        encryption_key,
+        encryption_required: settings.require_encryption,
+    }))
+}
+
+pub async fn find_public_link(
+    ctx: Extension<ApiContext>,
+    slug: String,
+) -> Result<Json<PublicLink>, Response<Body>> {
+    let destination = find_destination(&ctx.db, &Target::Link(slug))
+        .await
+        .map_err(|e| e.into_response())?;
+    ensure_not_gone(&destination).map_err(|e| e.into_response())?;
+
+    let accepting = destination.is_accepting(Utc::now());
+    let Destination {
+        recipient,
+        settings,
+        link: Some(link),
+    } = destination
+    else {
+        return Err(ApiError::NotFound("Link not found".to_string()).into_response());
+    };
+    let encryption_key = recipient.public_encryption_key();
+    Ok(Json(PublicLink {
+        slug: link.slug,
+        title: link.name,
+        name: recipient.name,
+        avatar: recipient.avatar,
+        prompt: link.prompt.or(recipient.prompt),
+        accepting,
+        closed_message: (!accepting).then(|| closed_message(&settings)),
+        min_length: settings.min_length,
+        max_length: settings.max_length,
+        expires_at: link.expires_at,
+        encryption_key,
+        encryption_required: settings.require_encryption,
+    }))
+}
+
+pub async fn submit_message(
+    ctx: Extension<ApiContext>,
+    target: Target,
+    client: ClientInfo,
+    solution: Option<String>,
+    Json(body): Json<SubmitMessageBody>,
+) -> Result<Json<SubmittedMessage>, Response<Body>> {
+    let destination = find_destination(&ctx.db, &target)
+        .await
+        .map_err(|e| e.into_response())?;
+    let Destination {
+        recipient,
+        settings,
+        link,
+    } = &destination;
+
+    ensure_not_gone(&destination).map_err(|e| e.into_response())?;
+    if !destination.is_accepting(Utc::now()) {
+        return Err(ApiError::Forbidden(closed_message(settings)).into_response());
+    }
+
+    let fingerprints = sender_fingerprints(&ctx.db, recipient.id, &client)
+        .await
+        .map_err(|e| e.into_response())?;
+    let platform_fingerprints = platform_fingerprints(&ctx.db, &client)
+        .await
+        .map_err(|e| e.into_response())?;
+    if is_blocked(&ctx.db, recipient.id, &fingerprints, &platform_fingerprints)
+        .await
+        .map_err(|e| e.into_response())?
+    {
+        return Err(
+            ApiError::Forbidden("You cannot send messages to this profile".to_string())
+                .into_response(),
+        );
+    }
+
+    let message = body.body.trim();
+    let encryption_key_id = match body.encryption_key_id {
+        Some(key_id) => {
+            check_envelope(recipient, settings, key_id, message).map_err(|e| e.into_response())?;
+            Some(key_id)
+        }
+        None if settings.require_encryption => {
+            return Err(ApiError::BadRequest {
+                errors: vec![
+                    "body: the recipient only accepts end-to-end encrypted messages.".to_string(),
+                ],
+            }
+            .into_response())
+        }
+        None => {
+            let length = message.chars().count() as i32;
+            check_length(settings, length, length).map_err(|e| e.into_response())?;
+            None
+        }
+    };
+
+    redeem_solution(&ctx, &target.key(), &fingerprints, solution.as_deref())
+        .await
+        .map_err(|e| e.into_response())?;
+    limit_sender(&ctx, &fingerprints).await?;
+
+    let blocklist = ctx
+        .blocklists
+        .blocklist(&ctx.db, recipient.id, settings.blocklist_version)
+        .await
+        .map_err(|e| e.into_response())?;
+    let claim_code = body
+        .claim_code
+        .then(|| hex::encode(rand::random::<[u8; 16]>()));
+    let folder = if encryption_key_id.is_none() && blocklist.is_match(message) {
+        match settings.blocklist_action.as_str() {
+            // answer exactly like a delivered message so the sender cannot tell,
+            // a claim code keeps reading as a message nobody has read yet
+            "drop" => {
+                // the link counts it too, or a capped link still taking
+                // messages would give the drop away
+                let mut tx = ctx
+                    .db
+                    .begin()
+                    .await
+                    .map_err(|e| ApiError::Database(e).into_response())?;
+                if let Some(link) = link {
+                    count_link_message(&mut tx, link.id)
+                        .await
+                        .map_err(|e| e.into_response())?;
+                }
+                let dropped = match claim_code {
+                    None => sqlx::query_as!(
+                        SubmittedMessage,
+                        r#"select uuid_generate_v1mc()::text as "message_id!", now()::timestamp as "created_at!",
+                            null::text as claim_code"#
+                    )
+                    .fetch_one(&mut *tx)
+                    .await,
+                    Some(claim_code) => sqlx::query_as!(
+                        SubmittedMessage,
+                        r#"insert into "dropped_claims" (claim_hash, recipient_id) values ($1, $2)
+                        returning uuid_generate_v1mc()::text as "message_id!", created_at, $3::text as claim_code"#,
+                        claim_hash(&claim_code),
+                        recipient.id,
+                        claim_code
+                    )
+                    .fetch_one(&mut *tx)
+                    .await,
+                }
+                .map_err(|e| ApiError::Database(e).into_response())?;
+                tx.commit()
+                    .await
+                    .map_err(|e| ApiError::Database(e).into_response())?;
+                return Ok(Json(dropped));
+            }
+            "filter" => "filtered",
+            _ => {
+                return Err(ApiError::BadRequest {
+                    errors: vec!["body: contains words the recipient does not accept.".to_string()],
+                }
+                .into_response())
+            }
+        }
+    } else {
+        "inbox"
+    };
+
+    // there is nothing to score or compare in ciphertext
+    let content_hash = match encryption_key_id {
+        Some(_) => None,
+        None => Some(content_hash(&content_key(&ctx.config.jwt_secret), message)),
+    };
+    let scores = match &content_hash {
+        None => HashMap::new(),
+        Some(content_hash) => {
+            classify(
+                &ctx.classifiers,
+                &IncomingMessage {
+                    recipient_id: recipient.id,
+                    body: message,
+                    content_hash,
+                },
+            )
+            .await
+        }
+    };
+    let score = scores.values().copied().fold(0.0, f32::max);
+    let folder = if score > settings.filter_threshold {
+        "filtered"
+    } else {
+        folder
+    };
+
+    // an envelope is already out of the server's reach
+    let sealed_body = match encryption_key_id {
+        Some(_) => None,
+        None => ctx
+            .keyring
+            .seal(&ctx.db, recipient.id, message)
+            .await
+            .map_err(|e| e.into_response())?,
+    };
+
+    // sealed under a random nonce so messages from one sender are not linked
+    // through it, it is only opened if the message gets reported
+    let sealed_platform_fingerprint = platform_fingerprints
+        .first()
+        .map(|fingerprint| {
+            seal(
+                &report_key(&ctx.config.jwt_secret),
+                recipient.id.as_bytes(),
+                fingerprint,
+            )
+        })
+        .transpose()
+        .map_err(|e| ApiError::InternalServer(e.to_string()).into_response())?;
+
+    let mut tx = ctx
+        .db
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e).into_response())?;
+    if let Some(link) = link {
+        count_link_message(&mut tx, link.id)
+            .await
+            .map_err(|e| e.into_response())?;
+    }
+
+    let result = sqlx::query_as!(
+        SubmittedMessage,
+        r#"insert into "messages"
+            (recipient_id, body, folder, sender_fingerprint, sealed_platform_fingerprint, content_hash, scores, score,
+            claim_hash, link_id, encryption_key_id, sealed_body)
+        values ($1, case when $13::bytea is null then $2 end, $3, $4, $5, $6, $7, $8, $9, $11, $12, $13)
+        returning id::text as "message_id!", created_at, $10::text as claim_code"#,
+        recipient.id,
+        message,
+        folder,
+        fingerprints.first(),
+        sealed_platform_fingerprint,
+        content_hash,
+        sqlx::types::Json(&scores) as _,
+        score,
+        claim_code.as_deref().map(claim_hash),
+        claim_code,
+        link.as_ref().map(|link| link.id),
+        encryption_key_id,
+        sealed_body
+    )
+    .fetch_one(&mut *tx)
+    .await;
+
+    match result {
+        Ok(submitted) => match tx.commit().await {
+            Ok(_) => Ok(Json(submitted)),
+            Err(e) => Err(ApiError::Database(e).into_response()),
+        },
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
@@ -0,0 +1,139 @@
+use crate::{
+    core::{extractors::ClientInfo, models::ApiError},
+    modules::profile::{
+        models::{ReactionBody, ReactionCounts},
+        service::{
+            find_recipient, is_blocked, limit_reactions, platform_fingerprints, sender_fingerprints,
+        },
+    },
+    ApiContext,
+};
+use axum::{body::Body, http::Response, response::IntoResponse};
+use axum::{Extension, Json};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Whether reactions are enabled on a published answer of the recipient.
+async fn reactions_enabled(
+    db: &PgPool,
+    recipient_id: Uuid,
+    answer_id: Uuid,
+) -> Result<bool, ApiError> {
+    sqlx::query_scalar!(
+        r#"select reactions_enabled from "messages"
+        where id = $1 and recipient_id = $2 and published_at is not null and reply_visibility = 'public'"#,
+        answer_id,
+        recipient_id
+    )
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("Answer not found".to_string()))
+}
+
+async fn reaction_counts(db: &PgPool, answer_id: Uuid) -> Result<ReactionCounts, ApiError> {
+    let reactions = sqlx::query_scalar!(
+        r#"select coalesce(jsonb_object_agg(reaction, count), '{}') as "reactions!" from (
+            select reaction, count(*) from "reactions" where message_id = $1 group by reaction
+        ) counts"#,
+        answer_id
+    )
+    .fetch_one(db)
+    .await?;
+
+    Ok(ReactionCounts {
+        answer_id: answer_id.to_string(),
+        reactions,
+    })
+}
+
+/// Adds an anonymous reaction. A sender reacting twice with the same reaction,
+/// even after their digest rotated, is counted once.
+pub async fn add_reaction(
+    ctx: Extension<ApiContext>,
+    profile_link: String,
+    answer_id: Uuid,
+    client: ClientInfo,
+    Json(body): Json<ReactionBody>,
+) -> Result<Json<ReactionCounts>, Response<Body>> {
+    let (recipient, _) = find_recipient(&ctx.db, &profile_link)
+        .await
+        .map_err(|e| e.into_response())?;
+    if !reactions_enabled(&ctx.db, recipient.id, answer_id)
+        .await
+        .map_err(|e| e.into_response())?
+    {
+        return Err(
+            ApiError::Forbidden("Reactions are disabled for this answer".to_string())
+                .into_response(),
+        );
+    }
+
+    let fingerprints = sender_fingerprints(&ctx.db, recipient.id, &client)
+        .await
+        .map_err(|e| e.into_response())?;
+    let platform_fingerprints = platform_fingerprints(&ctx.db, &client)
+        .await
+        .map_err(|e| e.into_response())?;
+    if is_blocked(&ctx.db, recipient.id, &fingerprints, &platform_fingerprints)
+        .await
+        .map_err(|e| e.into_response())?
+    {
+        return Err(
+            ApiError::Forbidden("You cannot react to this answer".to_string()).into_response(),
+        );
+    }
+    limit_reactions(&ctx, &fingerprints).await?;
+
+    sqlx::query!(
+        r#"insert into "reactions" (message_id, reaction, fingerprint)
+        select $1, $2::varchar, $3 where not exists (
+            select 1 from "reactions" where message_id = $1 and reaction = $2 and fingerprint = any($4)
+        )
+        on conflict do nothing"#,
+        answer_id,
+        body.reaction,
+        fingerprints.first(),
+        &fingerprints
+    )
+    .execute(&ctx.db)
+    .await
+    .map_err(|e| ApiError::Database(e).into_response())?;
+
+    match reaction_counts(&ctx.db, answer_id).await {
+        Ok(counts) => Ok(Json(counts)),
+        Err(e) => Err(e.into_response()),
+    }
+}
+
+pub async fn remove_reaction(
+    ctx: Extension<ApiContext>,
+    profile_link: String,
+    answer_id: Uuid,
+    reaction: String,
+    client: ClientInfo,
+) -> Result<Json<ReactionCounts>, Response<Body>> {
+    let (recipient, _) = find_recipient(&ctx.db, &profile_link)
+        .await
+        .map_err(|e| e.into_response())?;
+    reactions_enabled(&ctx.db, recipient.id, answer_id)
+        .await
+        .map_err(|e| e.into_response())?;
+
+    let fingerprints = sender_fingerprints(&ctx.db, recipient.id, &client)
+        .await
+        .map_err(|e| e.into_response())?;
+    sqlx::query!(
+        r#"delete from "reactions" where message_id = $1 and reaction = $2 and fingerprint = any($3)"#,
+        answer_id,
+        reaction,
+        &fingerprints
+    )
+    .execute(&ctx.db)
+    .await
+    .map_err(|e| ApiError::Database(e).into_response())?;
+
+    match reaction_counts(&ctx.db, answer_id).await {
+        Ok(counts) => Ok(Json(counts)),
+        Err(e) => Err(e.into_response()),
+    }
+}
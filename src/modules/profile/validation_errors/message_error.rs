@@ -0,0 +1,22 @@
+use crate::core::{error_transformer::error_transformer, traits::TransformValidationErrors};
+use validator::ValidationErrors;
+
+pub struct SubmitMessageValidationError;
+impl TransformValidationErrors for SubmitMessageValidationError {
+    fn new() -> Self {
+        SubmitMessageValidationError
+    }
+    fn transform_errors(&self, errors: ValidationErrors) -> Vec<String> {
+        error_transformer(errors)
+    }
+}
+
+pub struct ReactionValidationError;
+impl TransformValidationErrors for ReactionValidationError {
+    fn new() -> Self {
+        ReactionValidationError
+    }
+    fn transform_errors(&self, errors: ValidationErrors) -> Vec<String> {
+        error_transformer(errors)
+    }
+}
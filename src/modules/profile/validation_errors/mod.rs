@@ -0,0 +1,3 @@
+mod message_error;
+
+pub use message_error::*;
@@ -0,0 +1,3 @@
+mod push_api;
+
+pub use push_api::*;
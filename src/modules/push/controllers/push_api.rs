@@ -0,0 +1,62 @@
+use crate::{
+    core::{
+        extractors::{Authorized, CustomPath, ValidatedBody},
+        models::Claims,
+    },
+    modules::push::{
+        models::{PushSubscription, SubscribeBody, VapidKey},
+        service::{
+            delete_subscription, list_subscriptions, subscribe, test_push, vapid_public_key,
+        },
+        validation_errors::SubscribeValidationError,
+    },
+    ApiContext,
+};
+use axum::{
+    body::Body,
+    http::StatusCode,
+    response::{Json, Response},
+    Extension,
+};
+use uuid::Uuid;
+
+pub async fn find_vapid_key(
+    ctx: Extension<ApiContext>,
+    Authorized(_): Authorized<Claims>,
+) -> Result<Json<VapidKey>, Response<Body>> {
+    let key = vapid_public_key(ctx).await?;
+    Ok(key)
+}
+
+pub async fn find_subscriptions(
+    ctx: Extension<ApiContext>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<Json<Vec<PushSubscription>>, Response<Body>> {
+    let subscriptions = list_subscriptions(ctx, claims).await?;
+    Ok(subscriptions)
+}
+
+pub async fn handle_subscribe(
+    ctx: Extension<ApiContext>,
+    Authorized(claims): Authorized<Claims>,
+    ValidatedBody(body, _): ValidatedBody<SubscribeBody, SubscribeValidationError>,
+) -> Result<(StatusCode, Json<PushSubscription>), Response<Body>> {
+    let subscription = subscribe(ctx, claims, Json(body)).await?;
+    Ok((StatusCode::CREATED, subscription))
+}
+
+pub async fn handle_unsubscribe(
+    ctx: Extension<ApiContext>,
+    CustomPath(subscription_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<StatusCode, Response<Body>> {
+    delete_subscription(ctx, claims, subscription_id).await
+}
+
+pub async fn handle_test_push(
+    ctx: Extension<ApiContext>,
+    CustomPath(subscription_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<StatusCode, Response<Body>> {
+    test_push(ctx, claims, subscription_id).await
+}
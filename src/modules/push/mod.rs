@@ -0,0 +1,7 @@
+mod push_route;
+pub use push_route::*;
+
+pub mod controllers;
+pub mod models;
+pub mod service;
+pub mod validation_errors;
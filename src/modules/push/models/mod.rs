@@ -0,0 +1,3 @@
+mod push_model;
+
+pub use push_model::*;
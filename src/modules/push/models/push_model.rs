@@ -0,0 +1,73 @@
+use chrono::NaiveDateTime;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::core::utils::decode_base64_url;
+
+#[derive(Serialize)]
+pub struct VapidKey {
+    /// The base64url public key to subscribe with, as `applicationServerKey`.
+    pub public_key: String,
+}
+
+/// A device notified of new messages. Its keys are never returned.
+#[derive(Serialize)]
+pub struct PushSubscription {
+    pub subscription_id: String,
+    pub endpoint: String,
+    pub device_name: Option<String>,
+    pub last_success_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubscriptionKeys {
+    pub p256dh: String,
+    pub auth: String,
+}
+
+fn validate_endpoint(endpoint: &str) -> Result<(), ValidationError> {
+    match Url::parse(endpoint) {
+        Ok(url) if url.scheme() == "https" && url.host_str().is_some() => Ok(()),
+        _ => Err(ValidationError::new("endpoint")),
+    }
+}
+
+fn validate_keys(keys: &SubscriptionKeys) -> Result<(), ValidationError> {
+    let p256dh = decode_base64_url(&keys.p256dh).unwrap_or_default();
+    let auth = decode_base64_url(&keys.auth).unwrap_or_default();
+    if p256dh.len() != 65 || p256dh[0] != 4 || auth.len() != 16 {
+        return Err(ValidationError::new("keys"));
+    }
+    Ok(())
+}
+
+/// The `PushSubscription` JSON of the browser as it is, with an optional name
+/// to tell devices apart.
+#[derive(Debug, Deserialize, Validate)]
+pub struct SubscribeBody {
+    #[validate(
+        length(max = 2048),
+        custom(function = "validate_endpoint", message = "must be an https URL")
+    )]
+    pub endpoint: String,
+
+    #[validate(custom(
+        function = "validate_keys",
+        message = "must hold a base64url P-256 p256dh key and 16 byte auth secret"
+    ))]
+    pub keys: SubscriptionKeys,
+
+    #[validate(length(max = 100))]
+    pub device_name: Option<String>,
+}
+
+/// Payload of the job notifying one device, of a new message or, without
+/// `message_id`, with a test notification.
+#[derive(Serialize, Deserialize)]
+pub struct PushJob {
+    pub subscription_id: Uuid,
+    pub message_id: Option<Uuid>,
+}
@@ -0,0 +1,25 @@
+use axum::{
+    routing::{delete, get, post},
+    Router,
+};
+
+use super::controllers::{
+    find_subscriptions, find_vapid_key, handle_subscribe, handle_test_push, handle_unsubscribe,
+};
+
+pub fn push_routes() -> Router {
+    Router::new()
+        .route("/vapid-key", get(find_vapid_key))
+        .route(
+            "/subscriptions",
+            get(find_subscriptions).post(handle_subscribe),
+        )
+        .route(
+            "/subscriptions/:subscription_id",
+            delete(handle_unsubscribe),
+        )
+        .route(
+            "/subscriptions/:subscription_id/test",
+            post(handle_test_push),
+        )
+}
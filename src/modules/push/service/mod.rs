@@ -0,0 +1,5 @@
+mod push_service;
+mod send_service;
+
+pub use push_service::*;
+pub use send_service::*;
@@ -0,0 +1,154 @@
+use crate::{
+    core::{
+        jobs::enqueue,
+        models::{ApiError, Claims},
+    },
+    modules::push::{
+        models::{PushJob, PushSubscription, SubscribeBody, VapidKey},
+        service::{vapid_keys, SendPush},
+    },
+    ApiContext,
+};
+use axum::{body::Body, http::Response, http::StatusCode, response::IntoResponse};
+use axum::{Extension, Json};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use uuid::Uuid;
+
+const MAX_SUBSCRIPTIONS: i64 = 20;
+
+pub async fn vapid_public_key(
+    ctx: Extension<ApiContext>,
+) -> Result<Json<VapidKey>, Response<Body>> {
+    let (_, public_key) = vapid_keys(&ctx.db)
+        .await
+        .map_err(|e| ApiError::Database(e).into_response())?;
+    Ok(Json(VapidKey {
+        public_key: URL_SAFE_NO_PAD.encode(public_key),
+    }))
+}
+
+pub async fn list_subscriptions(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+) -> Result<Json<Vec<PushSubscription>>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let result = sqlx::query_as!(
+        PushSubscription,
+        r#"select id::text as "subscription_id!", endpoint, device_name, last_success_at, created_at
+        from "push_subscriptions" where user_id = $1
+        order by created_at desc"#,
+        user_id
+    )
+    .fetch_all(&ctx.db)
+    .await;
+
+    match result {
+        Ok(subscriptions) => Ok(Json(subscriptions)),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+/// Registers a device, or refreshes its keys when the browser subscribed again
+/// with the same endpoint.
+pub async fn subscribe(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    Json(body): Json<SubscribeBody>,
+) -> Result<Json<PushSubscription>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let count = sqlx::query_scalar!(
+        r#"select count(*) as "count!" from "push_subscriptions"
+        where user_id = $1 and endpoint <> $2"#,
+        user_id,
+        body.endpoint
+    )
+    .fetch_one(&ctx.db)
+    .await
+    .map_err(|e| ApiError::Database(e).into_response())?;
+    if count >= MAX_SUBSCRIPTIONS {
+        return Err(ApiError::BadRequest {
+            errors: vec![format!(
+                "subscriptions: at most {MAX_SUBSCRIPTIONS} devices can be subscribed."
+            )],
+        }
+        .into_response());
+    }
+
+    let result = sqlx::query_as!(
+        PushSubscription,
+        r#"insert into "push_subscriptions" (user_id, endpoint, p256dh, auth, device_name)
+        values ($1, $2, $3, $4, nullif($5, ''))
+        on conflict (endpoint) do update set
+            user_id = excluded.user_id,
+            p256dh = excluded.p256dh,
+            auth = excluded.auth,
+            device_name = excluded.device_name
+        returning id::text as "subscription_id!", endpoint, device_name, last_success_at, created_at"#,
+        user_id,
+        body.endpoint,
+        body.keys.p256dh,
+        body.keys.auth,
+        body.device_name
+    )
+    .fetch_one(&ctx.db)
+    .await;
+
+    match result {
+        Ok(subscription) => Ok(Json(subscription)),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+pub async fn delete_subscription(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    subscription_id: Uuid,
+) -> Result<StatusCode, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let result = sqlx::query!(
+        r#"delete from "push_subscriptions" where id = $1 and user_id = $2"#,
+        subscription_id,
+        user_id
+    )
+    .execute(&ctx.db)
+    .await;
+
+    match result {
+        Ok(result) if result.rows_affected() > 0 => Ok(StatusCode::NO_CONTENT),
+        Ok(_) => Err(ApiError::NotFound("Subscription not found".to_string()).into_response()),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+/// Queues a test notification to one device.
+pub async fn test_push(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    subscription_id: Uuid,
+) -> Result<StatusCode, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let owned = sqlx::query_scalar!(
+        r#"select exists(select 1 from "push_subscriptions" where id = $1 and user_id = $2) as "owned!""#,
+        subscription_id,
+        user_id
+    )
+    .fetch_one(&ctx.db)
+    .await
+    .map_err(|e| ApiError::Database(e).into_response())?;
+    if !owned {
+        return Err(ApiError::NotFound("Subscription not found".to_string()).into_response());
+    }
+
+    let job = PushJob {
+        subscription_id,
+        message_id: None,
+    };
+    enqueue::<SendPush>(&ctx.db, &job, None)
+        .await
+        .map_err(|e| e.into_response())?;
+    Ok(StatusCode::ACCEPTED)
+}
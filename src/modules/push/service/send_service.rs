@@ -0,0 +1,158 @@
+use anyhow::{anyhow, Error};
+use axum::async_trait;
+use reqwest::Url;
+use sqlx::PgPool;
+
+use crate::{
+    core::{
+        models::PushMessage,
+        traits::JobHandler,
+        utils::{
+            decode_base64_url, encrypt_push_payload, excerpt, generate_vapid_keys,
+            vapid_authorization,
+        },
+    },
+    modules::push::models::PushJob,
+    ApiContext,
+};
+
+/// Characters of a message shown in its notification.
+const PREVIEW_LENGTH: usize = 100;
+
+/// Seconds a push service holds a notification for a device that is offline.
+const TTL: u32 = 24 * 3600;
+
+/// The VAPID private and public key, made and stored on first use. Instances
+/// racing to make them all end up with the one stored first.
+pub async fn vapid_keys(db: &PgPool) -> Result<(Vec<u8>, Vec<u8>), sqlx::Error> {
+    if let Some(keys) = sqlx::query!(r#"select private_key, public_key from "vapid_keys""#)
+        .fetch_optional(db)
+        .await?
+    {
+        return Ok((keys.private_key, keys.public_key));
+    }
+
+    let (private_key, public_key) = generate_vapid_keys();
+    sqlx::query!(
+        r#"insert into "vapid_keys" (private_key, public_key) values ($1, $2)
+        on conflict (id) do nothing"#,
+        private_key,
+        public_key
+    )
+    .execute(db)
+    .await?;
+    let keys = sqlx::query!(r#"select private_key, public_key from "vapid_keys""#)
+        .fetch_one(db)
+        .await?;
+    Ok((keys.private_key, keys.public_key))
+}
+
+/// Notifies one device. A subscription its push service no longer knows is
+/// deleted, other failures are retried.
+pub struct SendPush;
+
+#[async_trait]
+impl JobHandler for SendPush {
+    const KIND: &'static str = "push.send";
+
+    type Payload = PushJob;
+
+    async fn run(&self, ctx: &ApiContext, job: PushJob) -> Result<(), Error> {
+        let Some(subscription) = sqlx::query!(
+            r#"select user_id, endpoint, p256dh, auth from "push_subscriptions" where id = $1"#,
+            job.subscription_id
+        )
+        .fetch_optional(&ctx.db)
+        .await?
+        else {
+            return Ok(());
+        };
+
+        let (notification, urgency) = match job.message_id {
+            Some(message_id) => {
+                let Some(message) = sqlx::query!(
+                    r#"select m.body, m.sealed_body, m.encryption_key_id is not null as "encrypted!", l.name as "link_name?", m.created_at
+                    from "messages" m left join "links" l on l.id = m.link_id
+                    where m.id = $1 and m.recipient_id = $2"#,
+                    message_id,
+                    subscription.user_id
+                )
+                .fetch_optional(&ctx.db)
+                .await?
+                else {
+                    return Ok(());
+                };
+                let body = match (message.body, message.sealed_body) {
+                    _ if message.encrypted => "Open your inbox to decrypt it.".to_string(),
+                    (_, Some(sealed)) => excerpt(
+                        &ctx.keyring
+                            .open(&ctx.db, subscription.user_id, &sealed)
+                            .await?,
+                        PREVIEW_LENGTH,
+                    ),
+                    (body, None) => excerpt(&body.unwrap_or_default(), PREVIEW_LENGTH),
+                };
+                let notification = serde_json::json!({
+                    "type": "message.received",
+                    "title": "New anonymous message",
+                    "body": body,
+                    "encrypted": message.encrypted,
+                    "message_id": message_id,
+                    "link_name": message.link_name,
+                    "created_at": message.created_at,
+                });
+                (notification, "high")
+            }
+            None => {
+                let notification = serde_json::json!({
+                    "type": "push.test",
+                    "title": "Notifications are on",
+                    "body": "New messages will show up here.",
+                });
+                (notification, "normal")
+            }
+        };
+
+        let (private_key, public_key) = vapid_keys(&ctx.db).await?;
+        let endpoint = Url::parse(&subscription.endpoint)?;
+        let message = PushMessage {
+            authorization: vapid_authorization(
+                &private_key,
+                &public_key,
+                &endpoint,
+                &ctx.config.vapid_subject,
+            )?,
+            body: encrypt_push_payload(
+                &decode_base64_url(&subscription.p256dh)?,
+                &decode_base64_url(&subscription.auth)?,
+                notification.to_string().as_bytes(),
+            )?,
+            endpoint: subscription.endpoint,
+            ttl: TTL,
+            urgency,
+        };
+
+        match ctx.push_sender.send(message).await? {
+            200..=299 => {
+                sqlx::query!(
+                    r#"update "push_subscriptions" set last_success_at = now() where id = $1"#,
+                    job.subscription_id
+                )
+                .execute(&ctx.db)
+                .await?;
+                Ok(())
+            }
+            404 | 410 => {
+                tracing::info!("Push subscription {} expired", job.subscription_id);
+                sqlx::query!(
+                    r#"delete from "push_subscriptions" where id = $1"#,
+                    job.subscription_id
+                )
+                .execute(&ctx.db)
+                .await?;
+                Ok(())
+            }
+            status => Err(anyhow!("Push service responded with {status}")),
+        }
+    }
+}
@@ -0,0 +1,3 @@
+mod push_error;
+
+pub use push_error::*;
@@ -0,0 +1,12 @@
+use crate::core::{error_transformer::error_transformer, traits::TransformValidationErrors};
+use validator::ValidationErrors;
+
+pub struct SubscribeValidationError;
+impl TransformValidationErrors for SubscribeValidationError {
+    fn new() -> Self {
+        SubscribeValidationError
+    }
+    fn transform_errors(&self, errors: ValidationErrors) -> Vec<String> {
+        error_transformer(errors)
+    }
+}
@@ -0,0 +1,3 @@
+mod replies_api;
+
+pub use replies_api::*;
@@ -0,0 +1,18 @@
+use crate::{
+    core::extractors::CustomPath,
+    modules::replies::{models::ReplyStatus, service::find_reply},
+    ApiContext,
+};
+use axum::{
+    body::Body,
+    response::{Json, Response},
+    Extension,
+};
+
+pub async fn get_reply(
+    ctx: Extension<ApiContext>,
+    CustomPath(code): CustomPath<String>,
+) -> Result<Json<ReplyStatus>, Response<Body>> {
+    let status = find_reply(ctx, code).await?;
+    Ok(status)
+}
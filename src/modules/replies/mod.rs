@@ -0,0 +1,6 @@
+mod replies_route;
+pub use replies_route::*;
+
+pub mod controllers;
+pub mod models;
+pub mod service;
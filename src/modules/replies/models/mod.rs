@@ -0,0 +1,3 @@
+mod reply_model;
+
+pub use reply_model::*;
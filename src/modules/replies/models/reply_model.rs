@@ -0,0 +1,21 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct Reply {
+    pub body: String,
+    /// `private` replies are only shown here, `public` ones may also be published.
+    pub visibility: String,
+    pub replied_at: NaiveDateTime,
+}
+
+/// What the holder of a claim code may learn about their message.
+#[derive(Serialize)]
+pub struct ReplyStatus {
+    pub body: String,
+    /// Whether `body` is the envelope that was sent, only the recipient can open it.
+    pub encrypted: bool,
+    pub created_at: NaiveDateTime,
+    pub read: bool,
+    pub reply: Option<Reply>,
+}
@@ -0,0 +1,7 @@
+use axum::{routing::get, Router};
+
+use super::controllers::get_reply;
+
+pub fn replies_routes() -> Router {
+    Router::new().route("/:code", get(get_reply))
+}
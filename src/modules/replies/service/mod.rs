@@ -0,0 +1,3 @@
+mod replies_service;
+
+pub use replies_service::*;
@@ -0,0 +1,103 @@
+use crate::{
+    core::{models::ApiError, traits::JobHandler},
+    modules::replies::models::{Reply, ReplyStatus},
+    ApiContext,
+};
+use anyhow::Error;
+use axum::{async_trait, body::Body, http::Response, response::IntoResponse};
+use axum::{Extension, Json};
+use sha2::{Digest, Sha256};
+
+/// Digest stored in place of a claim code. Codes are 128 random bits, so an
+/// unsalted digest is enough.
+pub fn claim_hash(code: &str) -> Vec<u8> {
+    Sha256::digest(code).to_vec()
+}
+
+/// A claim on a message the recipient's blocklist dropped. It reads like a
+/// delivered message that is never read or replied to, minus the body, which
+/// is not kept.
+async fn find_dropped_claim(
+    ctx: &ApiContext,
+    code: &str,
+) -> Result<Json<ReplyStatus>, Response<Body>> {
+    let claim = sqlx::query!(
+        r#"select created_at from "dropped_claims" where claim_hash = $1"#,
+        claim_hash(code)
+    )
+    .fetch_optional(&ctx.db)
+    .await
+    .map_err(|e| ApiError::Database(e).into_response())?
+    .ok_or_else(|| ApiError::NotFound("Claim code not found".to_string()).into_response())?;
+
+    Ok(Json(ReplyStatus {
+        body: String::new(),
+        encrypted: false,
+        created_at: claim.created_at,
+        read: false,
+        reply: None,
+    }))
+}
+
+pub async fn find_reply(
+    ctx: Extension<ApiContext>,
+    code: String,
+) -> Result<Json<ReplyStatus>, Response<Body>> {
+    let result = sqlx::query!(
+        r#"select recipient_id, body, sealed_body, encryption_key_id is not null as "encrypted!", created_at, read_at, reply, reply_visibility, replied_at from "messages"
+        where claim_hash = $1"#,
+        claim_hash(&code)
+    )
+    .fetch_optional(&ctx.db)
+    .await;
+
+    let message = match result {
+        Ok(Some(message)) => message,
+        Ok(None) => return find_dropped_claim(&ctx, &code).await,
+        Err(e) => return Err(ApiError::Database(e).into_response()),
+    };
+
+    let reply = match (message.reply, message.reply_visibility, message.replied_at) {
+        (Some(body), Some(visibility), Some(replied_at)) => Some(Reply {
+            body,
+            visibility,
+            replied_at,
+        }),
+        _ => None,
+    };
+    let body = match (message.body, message.sealed_body) {
+        (_, Some(sealed)) => ctx
+            .keyring
+            .open(&ctx.db, message.recipient_id, &sealed)
+            .await
+            .map_err(|e| e.into_response())?,
+        (body, None) => body.unwrap_or_default(),
+    };
+    Ok(Json(ReplyStatus {
+        body,
+        encrypted: message.encrypted,
+        created_at: message.created_at,
+        read: message.read_at.is_some(),
+        reply,
+    }))
+}
+
+/// Forgets claims on dropped messages after 30 days, their codes then read as
+/// not found.
+pub struct PruneDroppedClaims;
+
+#[async_trait]
+impl JobHandler for PruneDroppedClaims {
+    const KIND: &'static str = "dropped_claims.prune";
+
+    type Payload = ();
+
+    async fn run(&self, ctx: &ApiContext, _: ()) -> Result<(), Error> {
+        sqlx::query!(
+            r#"delete from "dropped_claims" where created_at < now() - interval '30 days'"#
+        )
+        .execute(&ctx.db)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{
+    core::{
+        extractors::{Authorized, CustomPath},
+        models::{ApiError, Claims},
+    },
+    modules::user::{
+        models::AvatarResponse,
+        service::{delete_avatar, find_avatar, upload_avatar},
+    },
+    ApiContext,
+};
+use axum::{
+    body::Body,
+    extract::{multipart::MultipartRejection, Multipart},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Json, Response},
+    Extension,
+};
+use uuid::Uuid;
+
+pub async fn handle_avatar_upload(
+    ctx: Extension<ApiContext>,
+    Authorized(claims): Authorized<Claims>,
+    multipart: Result<Multipart, MultipartRejection>,
+) -> Result<Json<AvatarResponse>, Response<Body>> {
+    let multipart = multipart.map_err(|rejection| {
+        ApiError::BadRequest {
+            errors: vec![rejection.body_text()],
+        }
+        .into_response()
+    })?;
+    let avatar = upload_avatar(ctx, claims, multipart).await?;
+    Ok(avatar)
+}
+
+pub async fn handle_avatar_delete(
+    ctx: Extension<ApiContext>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<StatusCode, Response<Body>> {
+    delete_avatar(ctx, claims).await
+}
+
+pub async fn get_avatar(
+    ctx: Extension<ApiContext>,
+    CustomPath(params): CustomPath<(Uuid, Uuid, u32)>,
+    headers: HeaderMap,
+) -> Result<Response<Body>, Response<Body>> {
+    find_avatar(ctx, params, headers).await
+}
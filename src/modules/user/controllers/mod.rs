@@ -0,0 +1,7 @@
+mod avatar_api;
+mod settings_api;
+mod user_api;
+
+pub use avatar_api::*;
+pub use settings_api::*;
+pub use user_api::*;
@@ -0,0 +1,34 @@
+use crate::{
+    core::{
+        extractors::{Authorized, ValidatedBody},
+        models::Claims,
+    },
+    modules::user::{
+        models::{MessageSettings, UpdateSettingsBody},
+        service::{find_settings, update_settings},
+        validation_errors::UpdateSettingsValidationError,
+    },
+    ApiContext,
+};
+use axum::{
+    body::Body,
+    response::{Json, Response},
+    Extension,
+};
+
+pub async fn get_settings(
+    ctx: Extension<ApiContext>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<Json<MessageSettings>, Response<Body>> {
+    let settings = find_settings(ctx, claims).await?;
+    Ok(settings)
+}
+
+pub async fn patch_settings(
+    ctx: Extension<ApiContext>,
+    Authorized(claims): Authorized<Claims>,
+    ValidatedBody(body, _): ValidatedBody<UpdateSettingsBody, UpdateSettingsValidationError>,
+) -> Result<Json<MessageSettings>, Response<Body>> {
+    let settings = update_settings(ctx, claims, Json(body)).await?;
+    Ok(settings)
+}
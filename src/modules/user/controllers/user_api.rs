@@ -0,0 +1,34 @@
+use crate::{
+    core::{
+        extractors::{Authorized, ValidatedBody},
+        models::Claims,
+    },
+    modules::user::{
+        models::{ProfileResponse, UpdateProfileBody},
+        service::{find_profile, update_profile as update_user_profile},
+        validation_errors::UpdateProfileValidationError,
+    },
+    ApiContext,
+};
+use axum::{
+    body::Body,
+    response::{Json, Response},
+    Extension,
+};
+
+pub async fn get_profile(
+    ctx: Extension<ApiContext>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<Json<ProfileResponse>, Response<Body>> {
+    let profile = find_profile(ctx, claims).await?;
+    Ok(profile)
+}
+
+pub async fn update_profile(
+    ctx: Extension<ApiContext>,
+    Authorized(claims): Authorized<Claims>,
+    ValidatedBody(body, _): ValidatedBody<UpdateProfileBody, UpdateProfileValidationError>,
+) -> Result<Json<ProfileResponse>, Response<Body>> {
+    let profile = update_user_profile(ctx, claims, Json(body)).await?;
+    Ok(profile)
+}
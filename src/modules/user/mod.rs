@@ -0,0 +1,7 @@
+mod user_route;
+pub use user_route::*;
+
+pub mod controllers;
+pub mod models;
+pub mod service;
+pub mod validation_errors;
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize)]
+pub struct AvatarResponse {
+    pub avatar: String,
+    pub sizes: Vec<String>,
+}
+
+/// The renditions of a replaced or removed avatar, left for a job to delete.
+#[derive(Serialize, Deserialize)]
+pub struct AvatarBlobs {
+    pub user_id: Uuid,
+    pub avatar_id: Uuid,
+}
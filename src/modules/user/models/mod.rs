@@ -0,0 +1,7 @@
+mod avatar_model;
+mod profile_model;
+mod settings_model;
+
+pub use avatar_model::*;
+pub use profile_model::*;
+pub use settings_model::*;
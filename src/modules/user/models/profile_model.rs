@@ -0,0 +1,72 @@
+use std::sync::LazyLock;
+
+use chrono::NaiveDateTime;
+use regex::Regex;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+pub static PROFILE_LINK_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[a-zA-Z0-9_-]{3,30}$").unwrap());
+
+/// The user id and avatar id of an uploaded avatar, as `upload_avatar`
+/// stores it: `/avatars/{user_id}/{avatar_id}`.
+pub fn parse_avatar_upload(avatar: &str) -> Option<(Uuid, Uuid)> {
+    let (user_id, avatar_id) = avatar.strip_prefix("/avatars/")?.split_once('/')?;
+    Some((user_id.parse().ok()?, avatar_id.parse().ok()?))
+}
+
+fn validate_avatar(avatar: &str) -> Result<(), ValidationError> {
+    if avatar.is_empty() || parse_avatar_upload(avatar).is_some() {
+        return Ok(());
+    }
+    match Url::parse(avatar) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") && url.host_str().is_some() => Ok(()),
+        _ => Err(ValidationError::new("avatar")),
+    }
+}
+
+#[derive(Serialize)]
+pub struct ProfileResponse {
+    pub user_id: String,
+    pub email: String,
+    pub name: String,
+    pub profile_link: Option<String>,
+    pub bio: Option<String>,
+    pub prompt: Option<String>,
+    pub avatar: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+/// Partial update of the authenticated user's profile. Omitted fields are left
+/// untouched, while an empty `bio`, `prompt` or `avatar` clears the value.
+#[derive(Debug, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateProfileBody {
+    #[validate(length(max = 80, min = 2))]
+    pub name: Option<String>,
+
+    #[validate(regex(
+        path = "PROFILE_LINK_REGEX",
+        message = "must be 3 to 30 letters, digits, hyphens or underscores"
+    ))]
+    pub profile_link: Option<String>,
+
+    #[validate(length(max = 160))]
+    pub bio: Option<String>,
+
+    #[validate(length(max = 120))]
+    pub prompt: Option<String>,
+
+    /// An uploaded avatar or the URL of an image hosted elsewhere.
+    #[validate(
+        length(max = 255),
+        custom(
+            function = "validate_avatar",
+            message = "must be an uploaded avatar or an http(s) URL"
+        )
+    )]
+    pub avatar: Option<String>,
+}
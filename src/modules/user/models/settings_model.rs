@@ -0,0 +1,99 @@
+use chrono::{DateTime, NaiveTime, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+#[derive(Serialize)]
+pub struct MessageSettings {
+    pub paused: bool,
+    pub schedule_enabled: bool,
+    pub accept_from: NaiveTime,
+    pub accept_until: NaiveTime,
+    pub timezone: String,
+    pub min_length: i32,
+    pub max_length: i32,
+    pub closed_message: Option<String>,
+    /// What happens to a message matching the blocklist: `reject`, `drop` or `filter`.
+    pub blocklist_action: String,
+    #[serde(skip_serializing)]
+    pub blocklist_version: i32,
+    /// Messages scoring above this go to the `filtered` folder, 1 filters nothing.
+    pub filter_threshold: f32,
+    /// Refuse messages that are not end-to-end encrypted to the user's key.
+    pub require_encryption: bool,
+}
+
+impl MessageSettings {
+    /// Whether new messages are accepted at `now`. The schedule window is read
+    /// in the user's timezone and may wrap past midnight, eg: 22:00 to 06:00.
+    pub fn is_accepting(&self, now: DateTime<Utc>) -> bool {
+        if self.paused {
+            return false;
+        }
+        if !self.schedule_enabled {
+            return true;
+        }
+
+        let timezone: Tz = self.timezone.parse().unwrap_or(Tz::UTC);
+        let local_time = now.with_timezone(&timezone).time();
+        if self.accept_from <= self.accept_until {
+            self.accept_from <= local_time && local_time < self.accept_until
+        } else {
+            local_time >= self.accept_from || local_time < self.accept_until
+        }
+    }
+}
+
+fn validate_timezone(timezone: &str) -> Result<(), ValidationError> {
+    match timezone.parse::<Tz>() {
+        Ok(_) => Ok(()),
+        Err(_) => Err(ValidationError::new("timezone")),
+    }
+}
+
+fn validate_blocklist_action(action: &str) -> Result<(), ValidationError> {
+    match action {
+        "reject" | "drop" | "filter" => Ok(()),
+        _ => Err(ValidationError::new("blocklist_action")),
+    }
+}
+
+/// Partial update of the message-receiving settings. An empty `closed_message`
+/// restores the default text shown to senders.
+#[derive(Debug, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateSettingsBody {
+    pub paused: Option<bool>,
+
+    pub schedule_enabled: Option<bool>,
+
+    pub accept_from: Option<NaiveTime>,
+
+    pub accept_until: Option<NaiveTime>,
+
+    #[validate(custom(
+        function = "validate_timezone",
+        message = "must be an IANA timezone, eg: Africa/Lagos"
+    ))]
+    pub timezone: Option<String>,
+
+    #[validate(range(min = 1, max = 1000))]
+    pub min_length: Option<i32>,
+
+    #[validate(range(min = 1, max = 1000))]
+    pub max_length: Option<i32>,
+
+    #[validate(length(max = 200))]
+    pub closed_message: Option<String>,
+
+    #[validate(custom(
+        function = "validate_blocklist_action",
+        message = "must be one of reject, drop or filter"
+    ))]
+    pub blocklist_action: Option<String>,
+
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub filter_threshold: Option<f32>,
+
+    pub require_encryption: Option<bool>,
+}
@@ -0,0 +1,225 @@
+use crate::{
+    core::{
+        jobs::enqueue,
+        models::{ApiError, Claims},
+        traits::JobHandler,
+        utils::{render_avatar, AVATAR_SIZES},
+    },
+    modules::user::models::{parse_avatar_upload, AvatarBlobs, AvatarResponse},
+    ApiContext,
+};
+use anyhow::Error;
+use axum::{
+    async_trait,
+    body::Body,
+    extract::Multipart,
+    http::{header, HeaderMap, Response, StatusCode},
+    response::IntoResponse,
+};
+use axum::{Extension, Json};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+fn avatar_key(user_id: Uuid, avatar_id: Uuid, size: u32) -> String {
+    format!("avatars/{user_id}/{avatar_id}/{size}.png")
+}
+
+/// Deletes the stored renditions of a replaced or removed avatar.
+pub struct DeleteAvatarBlobs;
+
+#[async_trait]
+impl JobHandler for DeleteAvatarBlobs {
+    const KIND: &'static str = "avatars.delete_blobs";
+
+    type Payload = AvatarBlobs;
+
+    async fn run(&self, ctx: &ApiContext, blobs: AvatarBlobs) -> Result<(), Error> {
+        for size in AVATAR_SIZES {
+            ctx.blob_store
+                .delete(&avatar_key(blobs.user_id, blobs.avatar_id, size))
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Queues the deletion of the renditions an avatar reference points at, but
+/// only when it is an upload owned by `user_id`.
+async fn delete_avatar_blobs(
+    db: &mut PgConnection,
+    user_id: Uuid,
+    avatar: Option<String>,
+) -> Result<(), ApiError> {
+    let avatar_id = avatar
+        .as_deref()
+        .and_then(parse_avatar_upload)
+        .filter(|(owner_id, _)| *owner_id == user_id)
+        .map(|(_, avatar_id)| avatar_id);
+
+    if let Some(avatar_id) = avatar_id {
+        enqueue::<DeleteAvatarBlobs>(db, &AvatarBlobs { user_id, avatar_id }, None).await?;
+    }
+    Ok(())
+}
+
+pub async fn upload_avatar(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    mut multipart: Multipart,
+) -> Result<Json<AvatarResponse>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let mut upload = None;
+    while let Some(field) = multipart.next_field().await.map_err(|e| {
+        ApiError::BadRequest {
+            errors: vec![e.body_text()],
+        }
+        .into_response()
+    })? {
+        if field.name() == Some("avatar") {
+            let bytes = field.bytes().await.map_err(|e| {
+                ApiError::BadRequest {
+                    errors: vec![e.body_text()],
+                }
+                .into_response()
+            })?;
+            upload = Some(bytes);
+            break;
+        }
+    }
+    let upload = upload.ok_or_else(|| {
+        ApiError::BadRequest {
+            errors: vec!["avatar: missing file field".to_string()],
+        }
+        .into_response()
+    })?;
+
+    let renditions = tokio::task::spawn_blocking(move || render_avatar(&upload))
+        .await
+        .map_err(|e| ApiError::InternalServer(e.to_string()).into_response())?
+        .map_err(|e| e.into_response())?;
+
+    let avatar_id = Uuid::new_v4();
+    for (size, png) in renditions {
+        ctx.blob_store
+            .put(&avatar_key(user_id, avatar_id, size), png, "image/png")
+            .await
+            .map_err(|e| ApiError::InternalServer(e.to_string()).into_response())?;
+    }
+
+    let avatar = format!("/avatars/{user_id}/{avatar_id}");
+    let mut tx = ctx
+        .db
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e).into_response())?;
+    let previous = sqlx::query_scalar!(
+        r#"update "users" u set avatar = $2
+        from (select id, avatar from "users" where id = $1 for update) previous
+        where u.id = previous.id
+        returning previous.avatar"#,
+        user_id,
+        avatar
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| ApiError::Database(e).into_response())?
+    .ok_or_else(|| ApiError::NotFound("User not found".to_string()).into_response())?;
+
+    delete_avatar_blobs(&mut tx, user_id, previous)
+        .await
+        .map_err(|e| e.into_response())?;
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::Database(e).into_response())?;
+
+    Ok(Json(AvatarResponse {
+        sizes: AVATAR_SIZES
+            .iter()
+            .map(|size| format!("{avatar}/{size}"))
+            .collect(),
+        avatar,
+    }))
+}
+
+pub async fn delete_avatar(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+) -> Result<StatusCode, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let mut tx = ctx
+        .db
+        .begin()
+        .await
+        .map_err(|e| ApiError::Database(e).into_response())?;
+    let previous = sqlx::query_scalar!(
+        r#"update "users" u set avatar = null
+        from (select id, avatar from "users" where id = $1 for update) previous
+        where u.id = previous.id
+        returning previous.avatar"#,
+        user_id
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| ApiError::Database(e).into_response())?
+    .ok_or_else(|| ApiError::NotFound("User not found".to_string()).into_response())?;
+
+    delete_avatar_blobs(&mut tx, user_id, previous)
+        .await
+        .map_err(|e| e.into_response())?;
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::Database(e).into_response())?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Avatar renditions are immutable: a new upload gets a new `avatar_id`, so
+/// responses can be cached forever and revalidated cheaply by ETag.
+pub async fn find_avatar(
+    ctx: Extension<ApiContext>,
+    (user_id, avatar_id, size): (Uuid, Uuid, u32),
+    headers: HeaderMap,
+) -> Result<Response<Body>, Response<Body>> {
+    if !AVATAR_SIZES.contains(&size) {
+        return Err(ApiError::NotFound("Avatar size not found".to_string()).into_response());
+    }
+
+    let etag = format!("\"{avatar_id}-{size}\"");
+    let cache_control = "public, max-age=31536000, immutable";
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| {
+            value
+                .split(',')
+                .any(|tag| tag.trim() == etag || tag.trim() == "*")
+        });
+    if not_modified {
+        return Ok((
+            StatusCode::NOT_MODIFIED,
+            [
+                (header::ETAG, etag),
+                (header::CACHE_CONTROL, cache_control.to_string()),
+            ],
+        )
+            .into_response());
+    }
+
+    let blob = ctx
+        .blob_store
+        .get(&avatar_key(user_id, avatar_id, size))
+        .await
+        .map_err(|e| ApiError::InternalServer(e.to_string()).into_response())?
+        .ok_or_else(|| ApiError::NotFound("Avatar not found".to_string()).into_response())?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, blob.content_type),
+            (header::ETAG, etag),
+            (header::CACHE_CONTROL, cache_control.to_string()),
+        ],
+        blob.bytes,
+    )
+        .into_response())
+}
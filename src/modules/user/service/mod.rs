@@ -0,0 +1,7 @@
+mod avatar_service;
+mod settings_service;
+mod user_service;
+
+pub use avatar_service::*;
+pub use settings_service::*;
+pub use user_service::*;
@@ -0,0 +1,109 @@
+use crate::{
+    core::models::{ApiError, Claims},
+    modules::{
+        encryption::service::has_active_key,
+        user::models::{MessageSettings, UpdateSettingsBody},
+    },
+    ApiContext,
+};
+use axum::{body::Body, http::Response, response::IntoResponse};
+use axum::{Extension, Json};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub async fn message_settings(db: &PgPool, user_id: Uuid) -> Result<MessageSettings, ApiError> {
+    sqlx::query_as!(
+        MessageSettings,
+        r#"select paused, schedule_enabled, accept_from, accept_until, timezone, min_length, max_length, closed_message,
+            blocklist_action, blocklist_version, filter_threshold, require_encryption
+        from "message_settings" where user_id = $1"#,
+        user_id
+    )
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound("User not found".to_string()))
+}
+
+/// Refuses to require encryption before the user registered a key to encrypt to.
+pub async fn ensure_encryption_key(db: &PgPool, user_id: Uuid) -> Result<(), ApiError> {
+    if !has_active_key(db, user_id).await? {
+        return Err(ApiError::BadRequest {
+            errors: vec![
+                "require_encryption: register an encryption key before requiring it.".to_string(),
+            ],
+        });
+    }
+    Ok(())
+}
+
+pub async fn find_settings(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+) -> Result<Json<MessageSettings>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    match message_settings(&ctx.db, user_id).await {
+        Ok(settings) => Ok(Json(settings)),
+        Err(e) => Err(e.into_response()),
+    }
+}
+
+pub async fn update_settings(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    Json(body): Json<UpdateSettingsBody>,
+) -> Result<Json<MessageSettings>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+    if body.require_encryption == Some(true) {
+        ensure_encryption_key(&ctx.db, user_id)
+            .await
+            .map_err(|e| e.into_response())?;
+    }
+
+    let result = sqlx::query_as!(
+        MessageSettings,
+        r#"update "message_settings" set
+            paused = coalesce($2, paused),
+            schedule_enabled = coalesce($3, schedule_enabled),
+            accept_from = coalesce($4, accept_from),
+            accept_until = coalesce($5, accept_until),
+            timezone = coalesce($6, timezone),
+            min_length = coalesce($7, min_length),
+            max_length = coalesce($8, max_length),
+            closed_message = nullif(coalesce($9, closed_message), ''),
+            blocklist_action = coalesce($10, blocklist_action),
+            filter_threshold = coalesce($11, filter_threshold),
+            require_encryption = coalesce($12, require_encryption)
+        where user_id = $1
+        returning paused, schedule_enabled, accept_from, accept_until, timezone, min_length, max_length, closed_message,
+            blocklist_action, blocklist_version, filter_threshold, require_encryption"#,
+        user_id,
+        body.paused,
+        body.schedule_enabled,
+        body.accept_from,
+        body.accept_until,
+        body.timezone,
+        body.min_length,
+        body.max_length,
+        body.closed_message,
+        body.blocklist_action,
+        body.filter_threshold,
+        body.require_encryption
+    )
+    .fetch_optional(&ctx.db)
+    .await;
+
+    match result {
+        Ok(Some(settings)) => Ok(Json(settings)),
+        Ok(None) => Err(ApiError::NotFound("User not found".to_string()).into_response()),
+        Err(sqlx::Error::Database(e))
+            if e.constraint() == Some("message_settings_length_check") =>
+        {
+            Err(ApiError::BadRequest {
+                errors: vec!["min_length: must not be greater than max_length.".to_string()],
+            }
+            .into_response())
+        }
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
@@ -0,0 +1,78 @@
+use crate::{
+    core::models::{ApiError, Claims},
+    modules::user::models::{parse_avatar_upload, ProfileResponse, UpdateProfileBody},
+    ApiContext,
+};
+use axum::{body::Body, http::Response, response::IntoResponse};
+use axum::{Extension, Json};
+
+pub async fn find_profile(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+) -> Result<Json<ProfileResponse>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let result = sqlx::query_as!(
+        ProfileResponse,
+        r#"select id::text as "user_id!", email, name, profile_link, bio, prompt, avatar, created_at, updated_at
+        from "users" where id = $1"#,
+        user_id
+    )
+    .fetch_optional(&ctx.db)
+    .await;
+
+    match result {
+        Ok(Some(profile)) => Ok(Json(profile)),
+        Ok(None) => Err(ApiError::NotFound("User not found".to_string()).into_response()),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+pub async fn update_profile(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    Json(body): Json<UpdateProfileBody>,
+) -> Result<Json<ProfileResponse>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+    let others_upload = body
+        .avatar
+        .as_deref()
+        .and_then(parse_avatar_upload)
+        .is_some_and(|(owner_id, _)| owner_id != user_id);
+    if others_upload {
+        return Err(ApiError::BadRequest {
+            errors: vec!["avatar: must be an uploaded avatar of yours.".to_string()],
+        }
+        .into_response());
+    }
+
+    // absent fields keep their current value, empty optional fields are cleared
+    let result = sqlx::query_as!(
+        ProfileResponse,
+        r#"update "users" set
+            name = coalesce($2, name),
+            profile_link = coalesce($3, profile_link),
+            bio = nullif(coalesce($4, bio), ''),
+            prompt = nullif(coalesce($5, prompt), ''),
+            avatar = nullif(coalesce($6, avatar), '')
+        where id = $1
+        returning id::text as "user_id!", email, name, profile_link, bio, prompt, avatar, created_at, updated_at"#,
+        user_id,
+        body.name,
+        body.profile_link,
+        body.bio,
+        body.prompt,
+        body.avatar
+    )
+    .fetch_optional(&ctx.db)
+    .await;
+
+    match result {
+        Ok(Some(profile)) => Ok(Json(profile)),
+        Ok(None) => Err(ApiError::NotFound("User not found".to_string()).into_response()),
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            Err(ApiError::Conflict("Profile link is already taken".to_string()).into_response())
+        }
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
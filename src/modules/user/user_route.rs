@@ -0,0 +1,28 @@
+use axum::{
+    extract::DefaultBodyLimit,
+    routing::{get, post},
+    Router,
+};
+
+use super::controllers::{
+    get_avatar, get_profile, get_settings, handle_avatar_delete, handle_avatar_upload,
+    patch_settings, update_profile,
+};
+
+const MAX_AVATAR_UPLOAD_BYTES: usize = 5 * 1024 * 1024;
+
+pub fn user_routes() -> Router {
+    Router::new()
+        .route("/", get(get_profile).patch(update_profile))
+        .route("/settings", get(get_settings).patch(patch_settings))
+        .route(
+            "/avatar",
+            post(handle_avatar_upload)
+                .delete(handle_avatar_delete)
+                .layer(DefaultBodyLimit::max(MAX_AVATAR_UPLOAD_BYTES)),
+        )
+}
+
+pub fn avatar_routes() -> Router {
+    Router::new().route("/:user_id/:avatar_id/:size", get(get_avatar))
+}
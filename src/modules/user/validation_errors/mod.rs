@@ -0,0 +1,5 @@
+mod profile_error;
+mod settings_error;
+
+pub use profile_error::*;
+pub use settings_error::*;
@@ -0,0 +1,12 @@
+use crate::core::{error_transformer::error_transformer, traits::TransformValidationErrors};
+use validator::ValidationErrors;
+
+pub struct UpdateProfileValidationError;
+impl TransformValidationErrors for UpdateProfileValidationError {
+    fn new() -> Self {
+        UpdateProfileValidationError
+    }
+    fn transform_errors(&self, errors: ValidationErrors) -> Vec<String> {
+        error_transformer(errors)
+    }
+}
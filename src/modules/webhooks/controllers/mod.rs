@@ -0,0 +1,3 @@
+mod webhooks_api;
+
+pub use webhooks_api::*;
@@ -0,0 +1,115 @@
+use crate::{
+    core::{
+        extractors::{Authorized, CustomPath, CustomQuery, ValidatedBody},
+        models::Claims,
+    },
+    modules::webhooks::{
+        models::{
+            CreateWebhookBody, DeliveryQuery, UpdateWebhookBody, Webhook, WebhookDelivery,
+            WebhookDeliveryLog,
+        },
+        service::{
+            create_webhook, delete_webhook, list_deliveries, list_webhooks, ping_webhook,
+            redeliver, rotate_webhook_secret, single_delivery, single_webhook, update_webhook,
+        },
+        validation_errors::{CreateWebhookValidationError, UpdateWebhookValidationError},
+    },
+    ApiContext,
+};
+use axum::{
+    body::Body,
+    http::StatusCode,
+    response::{Json, Response},
+    Extension,
+};
+use uuid::Uuid;
+
+pub async fn find_webhooks(
+    ctx: Extension<ApiContext>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<Json<Vec<Webhook>>, Response<Body>> {
+    let webhooks = list_webhooks(ctx, claims).await?;
+    Ok(webhooks)
+}
+
+pub async fn find_webhook(
+    ctx: Extension<ApiContext>,
+    CustomPath(webhook_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<Json<Webhook>, Response<Body>> {
+    let webhook = single_webhook(ctx, claims, webhook_id).await?;
+    Ok(webhook)
+}
+
+pub async fn handle_create_webhook(
+    ctx: Extension<ApiContext>,
+    Authorized(claims): Authorized<Claims>,
+    ValidatedBody(body, _): ValidatedBody<CreateWebhookBody, CreateWebhookValidationError>,
+) -> Result<(StatusCode, Json<Webhook>), Response<Body>> {
+    let webhook = create_webhook(ctx, claims, Json(body)).await?;
+    Ok((StatusCode::CREATED, webhook))
+}
+
+pub async fn handle_update_webhook(
+    ctx: Extension<ApiContext>,
+    CustomPath(webhook_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+    ValidatedBody(body, _): ValidatedBody<UpdateWebhookBody, UpdateWebhookValidationError>,
+) -> Result<Json<Webhook>, Response<Body>> {
+    let webhook = update_webhook(ctx, claims, webhook_id, Json(body)).await?;
+    Ok(webhook)
+}
+
+pub async fn handle_delete_webhook(
+    ctx: Extension<ApiContext>,
+    CustomPath(webhook_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<StatusCode, Response<Body>> {
+    delete_webhook(ctx, claims, webhook_id).await
+}
+
+pub async fn handle_rotate_secret(
+    ctx: Extension<ApiContext>,
+    CustomPath(webhook_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<Json<Webhook>, Response<Body>> {
+    let webhook = rotate_webhook_secret(ctx, claims, webhook_id).await?;
+    Ok(webhook)
+}
+
+pub async fn handle_ping_webhook(
+    ctx: Extension<ApiContext>,
+    CustomPath(webhook_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<(StatusCode, Json<WebhookDelivery>), Response<Body>> {
+    let delivery = ping_webhook(ctx, claims, webhook_id).await?;
+    Ok((StatusCode::ACCEPTED, delivery))
+}
+
+pub async fn find_deliveries(
+    ctx: Extension<ApiContext>,
+    CustomPath(webhook_id): CustomPath<Uuid>,
+    Authorized(claims): Authorized<Claims>,
+    CustomQuery(query): CustomQuery<DeliveryQuery>,
+) -> Result<Json<Vec<WebhookDelivery>>, Response<Body>> {
+    let deliveries = list_deliveries(ctx, claims, webhook_id, query).await?;
+    Ok(deliveries)
+}
+
+pub async fn find_delivery(
+    ctx: Extension<ApiContext>,
+    CustomPath((webhook_id, delivery_id)): CustomPath<(Uuid, Uuid)>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<Json<WebhookDeliveryLog>, Response<Body>> {
+    let delivery = single_delivery(ctx, claims, webhook_id, delivery_id).await?;
+    Ok(delivery)
+}
+
+pub async fn handle_redeliver(
+    ctx: Extension<ApiContext>,
+    CustomPath((webhook_id, delivery_id)): CustomPath<(Uuid, Uuid)>,
+    Authorized(claims): Authorized<Claims>,
+) -> Result<(StatusCode, Json<WebhookDelivery>), Response<Body>> {
+    let delivery = redeliver(ctx, claims, webhook_id, delivery_id).await?;
+    Ok((StatusCode::ACCEPTED, delivery))
+}
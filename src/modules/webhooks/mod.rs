@@ -0,0 +1,7 @@
+mod webhooks_route;
+pub use webhooks_route::*;
+
+pub mod controllers;
+pub mod models;
+pub mod service;
+pub mod validation_errors;
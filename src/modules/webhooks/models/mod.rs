@@ -0,0 +1,3 @@
+mod webhook_model;
+
+pub use webhook_model::*;
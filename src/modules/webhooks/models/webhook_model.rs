@@ -0,0 +1,148 @@
+use chrono::NaiveDateTime;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+/// Events an endpoint can subscribe to.
+pub const WEBHOOK_EVENTS: [&str; 3] = ["message.received", "message.reported", "link.expired"];
+
+/// Sent by `POST /me/webhooks/:webhook_id/ping` only, to try an endpoint out.
+pub const PING_EVENT: &str = "webhook.ping";
+
+/// An endpoint deliveries are posted to. The signing `secret` is only returned
+/// when the endpoint is created and when the secret is rotated.
+#[derive(Serialize)]
+pub struct Webhook {
+    pub webhook_id: String,
+    pub url: String,
+    pub description: Option<String>,
+    pub events: Vec<String>,
+    pub enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+fn validate_url(url: &str) -> Result<(), ValidationError> {
+    match Url::parse(url) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") && url.host_str().is_some() => Ok(()),
+        _ => Err(ValidationError::new("url")),
+    }
+}
+
+fn validate_events(events: &[String]) -> Result<(), ValidationError> {
+    if events.is_empty()
+        || events
+            .iter()
+            .any(|event| !WEBHOOK_EVENTS.contains(&event.as_str()))
+    {
+        return Err(ValidationError::new("events"));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct CreateWebhookBody {
+    #[validate(
+        length(max = 2048),
+        custom(function = "validate_url", message = "must be an http or https URL")
+    )]
+    pub url: String,
+
+    #[validate(length(max = 200))]
+    pub description: Option<String>,
+
+    #[validate(custom(
+        function = "validate_events",
+        message = "must list message.received, message.reported or link.expired"
+    ))]
+    pub events: Vec<String>,
+
+    pub enabled: Option<bool>,
+}
+
+/// Partial update of an endpoint. Omitted fields are left untouched and an
+/// empty `description` clears it.
+#[derive(Debug, Deserialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateWebhookBody {
+    #[validate(
+        length(max = 2048),
+        custom(function = "validate_url", message = "must be an http or https URL")
+    )]
+    pub url: Option<String>,
+
+    #[validate(length(max = 200))]
+    pub description: Option<String>,
+
+    #[validate(custom(
+        function = "validate_events",
+        message = "must list message.received, message.reported or link.expired"
+    ))]
+    pub events: Option<Vec<String>>,
+
+    pub enabled: Option<bool>,
+}
+
+/// One event queued for an endpoint. `pending` deliveries are retried with
+/// exponential backoff until they succeed or run out of attempts.
+#[derive(Serialize)]
+pub struct WebhookDelivery {
+    pub delivery_id: String,
+    pub event: String,
+    /// `pending`, `succeeded` or `failed`.
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: Option<NaiveDateTime>,
+    pub last_status_code: Option<i32>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+/// A request made for a delivery, `error` is set when no response came back.
+#[derive(Serialize)]
+pub struct WebhookAttempt {
+    pub status_code: Option<i32>,
+    pub error: Option<String>,
+    /// The start of the response body.
+    pub response_body: Option<String>,
+    pub duration_ms: i32,
+    pub created_at: NaiveDateTime,
+}
+
+/// A delivery with what it sends and every attempt so far.
+#[derive(Serialize)]
+pub struct WebhookDeliveryLog {
+    #[serde(flatten)]
+    pub delivery: WebhookDelivery,
+    pub payload: serde_json::Value,
+    pub log: Vec<WebhookAttempt>,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryStatus {
+    Pending,
+    Succeeded,
+    Failed,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Succeeded => "succeeded",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct DeliveryQuery {
+    pub status: Option<DeliveryStatus>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
@@ -0,0 +1,334 @@
+use std::time::{Duration, Instant};
+
+use anyhow::Error;
+use axum::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::Url;
+use sha2::Sha256;
+use sqlx::PgPool;
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+use crate::{
+    core::{
+        keyring::Keyring,
+        models::ApiError,
+        traits::JobHandler,
+        utils::{backoff_seconds, pinned_client},
+    },
+    ApiContext,
+};
+
+/// Batches one `DeliverWebhooks` run claims at most, so that it ends within
+/// the job timeout even when every endpoint times out.
+const MAX_BATCHES: usize = 4;
+
+/// Deliveries claimed at once by one instance.
+const BATCH_SIZE: i64 = 20;
+
+/// A delivery stops being retried after this many attempts, about 8 hours
+/// after the first with the backoff below.
+const MAX_ATTEMPTS: i32 = 10;
+
+const FIRST_RETRY_SECONDS: f64 = 30.0;
+
+const MAX_RETRY_SECONDS: f64 = 6.0 * 3600.0;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Kept of each response body in the delivery log.
+const MAX_RESPONSE_BODY: usize = 1000;
+
+pub const SIGNATURE_HEADER: &str = "x-webhook-signature";
+
+pub const TIMESTAMP_HEADER: &str = "x-webhook-timestamp";
+
+/// `sha256=` and the hex HMAC-SHA256 of `{timestamp}.{body}` under the
+/// endpoint's secret. Receivers recompute it, compare in constant time and
+/// reject old timestamps to stop replays.
+pub fn webhook_signature(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+struct DueDelivery {
+    delivery_id: Uuid,
+    url: String,
+    secret: String,
+    event: String,
+    payload: serde_json::Value,
+    attempts: i32,
+    created_at: NaiveDateTime,
+}
+
+/// What came back from one attempt: the status code and the start of the
+/// body, or why there was no response.
+type Outcome = Result<(u16, String), String>;
+
+async fn post(delivery: &DueDelivery, allow_private: bool) -> Outcome {
+    let url = Url::parse(&delivery.url).map_err(|e| e.to_string())?;
+    let client = pinned_client(&url, allow_private, REQUEST_TIMEOUT).await?;
+
+    let body = serde_json::json!({
+        "id": delivery.delivery_id,
+        "event": delivery.event,
+        "created_at": delivery.created_at,
+        "data": delivery.payload,
+    })
+    .to_string();
+    let timestamp = Utc::now().timestamp();
+
+    let mut response = client
+        .post(url)
+        .header("content-type", "application/json")
+        .header("user-agent", "anonymous-message-webhooks")
+        .header("x-webhook-id", delivery.delivery_id.to_string())
+        .header("x-webhook-event", &delivery.event)
+        .header(TIMESTAMP_HEADER, timestamp)
+        .header(
+            SIGNATURE_HEADER,
+            webhook_signature(&delivery.secret, timestamp, &body),
+        )
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let status = response.status().as_u16();
+    let mut excerpt = Vec::new();
+    while excerpt.len() < MAX_RESPONSE_BODY {
+        match response.chunk().await {
+            Ok(Some(chunk)) => excerpt.extend_from_slice(&chunk),
+            Ok(None) | Err(_) => break,
+        }
+    }
+    let excerpt: String = String::from_utf8_lossy(&excerpt)
+        .chars()
+        .take(MAX_RESPONSE_BODY)
+        .collect();
+    Ok((status, excerpt))
+}
+
+async fn record(db: &PgPool, delivery: &DueDelivery, outcome: Outcome, duration: Duration) {
+    let (status_code, error, response_body) = match outcome {
+        Ok((status, body)) => (Some(status as i32), None, Some(body)),
+        Err(error) => (
+            None,
+            Some(error.chars().take(500).collect::<String>()),
+            None,
+        ),
+    };
+    let succeeded = status_code.is_some_and(|status| (200..300).contains(&status));
+    let attempts = delivery.attempts + 1;
+    let status = if succeeded {
+        "succeeded"
+    } else if attempts >= MAX_ATTEMPTS {
+        "failed"
+    } else {
+        "pending"
+    };
+    let last_error = match (&error, status_code) {
+        (Some(error), _) => Some(error.clone()),
+        (None, Some(code)) if !succeeded => Some(format!("Endpoint responded with {code}")),
+        _ => None,
+    };
+
+    let recorded = async {
+        let mut tx = db.begin().await?;
+        sqlx::query!(
+            r#"insert into "webhook_attempts" (delivery_id, status_code, error, response_body, duration_ms)
+            values ($1, $2, $3, $4, $5)"#,
+            delivery.delivery_id,
+            status_code,
+            error,
+            response_body,
+            duration.as_millis().min(i32::MAX as u128) as i32
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            r#"update "webhook_deliveries" set
+                status = $2::varchar, attempts = $3, last_status_code = $4, last_error = $5,
+                delivered_at = case when $2::varchar = 'succeeded' then now() end,
+                next_attempt_at = now() + make_interval(secs => $6)
+            where id = $1"#,
+            delivery.delivery_id,
+            status,
+            attempts,
+            status_code,
+            last_error,
+            backoff_seconds(attempts, FIRST_RETRY_SECONDS, MAX_RETRY_SECONDS)
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+    };
+    if let Err(e) = recorded.await {
+        tracing::warn!(
+            "Failed to record webhook delivery {}: {e}",
+            delivery.delivery_id
+        );
+    }
+}
+
+/// Adds the body of a message sealed at rest to its `message.received`
+/// payload, which was queued without it so it is only ever decrypted to be sent.
+async fn open_payload(
+    db: &PgPool,
+    keyring: &Keyring,
+    delivery: &mut DueDelivery,
+) -> Result<(), ApiError> {
+    if delivery.event != "message.received" || !delivery.payload["body"].is_null() {
+        return Ok(());
+    }
+    let Some(message_id) = delivery.payload["message_id"]
+        .as_str()
+        .and_then(|id| id.parse::<Uuid>().ok())
+    else {
+        return Ok(());
+    };
+
+    let message = sqlx::query!(
+        r#"select recipient_id, sealed_body from "messages" where id = $1"#,
+        message_id
+    )
+    .fetch_optional(db)
+    .await?;
+    if let Some((recipient_id, Some(sealed))) = message.map(|m| (m.recipient_id, m.sealed_body)) {
+        delivery.payload["body"] = keyring.open(db, recipient_id, &sealed).await?.into();
+    }
+    Ok(())
+}
+
+/// Claims due deliveries of enabled endpoints. The claim pushes them a minute
+/// ahead, so another instance skips them meanwhile and they come back on
+/// their own should this one stop before recording the outcome.
+async fn claim_due(db: &PgPool) -> Result<Vec<DueDelivery>, sqlx::Error> {
+    sqlx::query_as!(
+        DueDelivery,
+        r#"update "webhook_deliveries" d set next_attempt_at = now() + interval '1 minute'
+        from (
+            select d.id from "webhook_deliveries" d join "webhooks" w on w.id = d.webhook_id
+            where d.status = 'pending' and d.next_attempt_at <= now() and w.enabled
+            order by d.next_attempt_at
+            limit $1
+            for update of d skip locked
+        ) due, "webhooks" w
+        where d.id = due.id and w.id = d.webhook_id
+        returning d.id as delivery_id, w.url, w.secret, d.event, d.payload, d.attempts, d.created_at"#,
+        BATCH_SIZE
+    )
+    .fetch_all(db)
+    .await
+}
+
+/// Queues `link.expired` for links that stopped accepting messages since the
+/// last sweep, and forgets the announcement of links that accept them again.
+pub struct SweepExpiredLinks;
+
+#[async_trait]
+impl JobHandler for SweepExpiredLinks {
+    const KIND: &'static str = "links.sweep_expired";
+
+    const MAX_ATTEMPTS: i32 = 1;
+
+    type Payload = ();
+
+    async fn run(&self, ctx: &ApiContext, _: ()) -> Result<(), Error> {
+        sqlx::query!(
+            r#"with expired as (
+                update "links" set expiry_notified_at = now()
+                where expiry_notified_at is null
+                    and (expires_at <= now() or message_count >= max_messages)
+                returning id, user_id, slug, name, expires_at, max_messages, message_count
+            )
+            insert into "webhook_deliveries" (webhook_id, event, payload)
+            select w.id, 'link.expired', jsonb_build_object(
+                'link_id', e.id, 'slug', e.slug, 'name', e.name,
+                'reason', case when e.expires_at <= now() then 'expired' else 'limit_reached' end,
+                'expires_at', e.expires_at, 'max_messages', e.max_messages,
+                'message_count', e.message_count)
+            from expired e join "webhooks" w on w.user_id = e.user_id
+            where w.enabled and 'link.expired' = any(w.events)"#
+        )
+        .execute(&ctx.db)
+        .await?;
+
+        sqlx::query!(
+            r#"update "links" set expiry_notified_at = null
+            where expiry_notified_at is not null
+                and not coalesce(expires_at <= now() or message_count >= max_messages, false)"#
+        )
+        .execute(&ctx.db)
+        .await?;
+        Ok(())
+    }
+}
+
+/// Posts the deliveries that are due, a batch at a time until none are left.
+/// Claims skip locked rows, so runs of any number of runners can overlap.
+pub struct DeliverWebhooks;
+
+#[async_trait]
+impl JobHandler for DeliverWebhooks {
+    const KIND: &'static str = "webhooks.deliver";
+
+    const MAX_ATTEMPTS: i32 = 1;
+
+    type Payload = ();
+
+    async fn run(&self, ctx: &ApiContext, _: ()) -> Result<(), Error> {
+        for _ in 0..MAX_BATCHES {
+            let due = claim_due(&ctx.db).await?;
+            if due.is_empty() {
+                break;
+            }
+
+            let mut attempts = JoinSet::new();
+            for mut delivery in due {
+                let db = ctx.db.clone();
+                let keyring = ctx.keyring.clone();
+                let allow_private = ctx.config.webhook_allow_private;
+                attempts.spawn(async move {
+                    let started = Instant::now();
+                    let outcome = match open_payload(&db, &keyring, &mut delivery).await {
+                        Ok(_) => post(&delivery, allow_private).await,
+                        Err(e) => Err(e.to_string()),
+                    };
+                    record(&db, &delivery, outcome, started.elapsed()).await;
+                });
+            }
+            while attempts.join_next().await.is_some() {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // computed independently with
+    // printf '1700000000.{"event":"message.created"}' | openssl dgst -sha256 -hmac whsec_test
+    #[test]
+    fn signs_timestamp_and_body() {
+        assert_eq!(
+            webhook_signature("whsec_test", 1700000000, r#"{"event":"message.created"}"#),
+            "sha256=9884eb2fcc09ffc10f00127fff0a0c5686da2fef61d0363442271c6dfa1917eb"
+        );
+    }
+
+    #[test]
+    fn binds_the_timestamp() {
+        let body = r#"{"event":"message.created"}"#;
+        assert_ne!(
+            webhook_signature("whsec_test", 1700000000, body),
+            webhook_signature("whsec_test", 1700000001, body)
+        );
+    }
+}
@@ -0,0 +1,5 @@
+mod delivery_service;
+mod webhook_service;
+
+pub use delivery_service::*;
+pub use webhook_service::*;
@@ -0,0 +1,397 @@
+use crate::{
+    core::{
+        models::{ApiError, Claims},
+        utils::is_public_address,
+    },
+    modules::webhooks::models::{
+        CreateWebhookBody, DeliveryQuery, UpdateWebhookBody, Webhook, WebhookAttempt,
+        WebhookDelivery, WebhookDeliveryLog, PING_EVENT,
+    },
+    ApiContext,
+};
+use axum::{body::Body, http::Response, http::StatusCode, response::IntoResponse};
+use axum::{Extension, Json};
+use reqwest::Url;
+use std::net::IpAddr;
+use uuid::Uuid;
+
+const MAX_WEBHOOKS: i64 = 10;
+
+fn new_secret() -> String {
+    format!("whsec_{}", hex::encode(rand::random::<[u8; 24]>()))
+}
+
+/// Refuses endpoints on this machine or a private network, unless
+/// `WEBHOOK_ALLOW_PRIVATE` is set. Names are checked again when delivering.
+fn check_url(ctx: &ApiContext, url: &str) -> Result<(), ApiError> {
+    if ctx.config.webhook_allow_private {
+        return Ok(());
+    }
+    let private = match Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+    {
+        Some(host) => match host.trim_matches(['[', ']']).parse::<IpAddr>() {
+            Ok(ip) => !is_public_address(ip),
+            Err(_) => host == "localhost" || host.ends_with(".localhost"),
+        },
+        None => true,
+    };
+    if private {
+        return Err(ApiError::BadRequest {
+            errors: vec!["url: must not point to a private address.".to_string()],
+        });
+    }
+    Ok(())
+}
+
+pub async fn list_webhooks(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+) -> Result<Json<Vec<Webhook>>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let result = sqlx::query_as!(
+        Webhook,
+        r#"select id::text as "webhook_id!", url, description, events::text[] as "events!", enabled,
+            null::text as secret, created_at, updated_at
+        from "webhooks" where user_id = $1 order by created_at desc"#,
+        user_id
+    )
+    .fetch_all(&ctx.db)
+    .await;
+
+    match result {
+        Ok(webhooks) => Ok(Json(webhooks)),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+pub async fn single_webhook(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    webhook_id: Uuid,
+) -> Result<Json<Webhook>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let result = sqlx::query_as!(
+        Webhook,
+        r#"select id::text as "webhook_id!", url, description, events::text[] as "events!", enabled,
+            null::text as secret, created_at, updated_at
+        from "webhooks" where id = $1 and user_id = $2"#,
+        webhook_id,
+        user_id
+    )
+    .fetch_optional(&ctx.db)
+    .await;
+
+    match result {
+        Ok(Some(webhook)) => Ok(Json(webhook)),
+        Ok(None) => Err(ApiError::NotFound("Webhook not found".to_string()).into_response()),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+pub async fn create_webhook(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    Json(body): Json<CreateWebhookBody>,
+) -> Result<Json<Webhook>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+    check_url(&ctx, &body.url).map_err(|e| e.into_response())?;
+
+    let count = sqlx::query_scalar!(
+        r#"select count(*) as "count!" from "webhooks" where user_id = $1"#,
+        user_id
+    )
+    .fetch_one(&ctx.db)
+    .await
+    .map_err(|e| ApiError::Database(e).into_response())?;
+    if count >= MAX_WEBHOOKS {
+        return Err(ApiError::BadRequest {
+            errors: vec![format!(
+                "webhooks: at most {MAX_WEBHOOKS} webhooks are allowed."
+            )],
+        }
+        .into_response());
+    }
+
+    let mut events = body.events;
+    events.sort();
+    events.dedup();
+
+    let result = sqlx::query_as!(
+        Webhook,
+        r#"insert into "webhooks" (user_id, url, description, secret, events, enabled)
+        values ($1, $2, nullif($3, ''), $4, $5::text[], coalesce($6, true))
+        returning id::text as "webhook_id!", url, description, events::text[] as "events!", enabled,
+            secret as "secret?", created_at, updated_at"#,
+        user_id,
+        body.url,
+        body.description,
+        new_secret(),
+        &events,
+        body.enabled
+    )
+    .fetch_one(&ctx.db)
+    .await;
+
+    match result {
+        Ok(webhook) => Ok(Json(webhook)),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+pub async fn update_webhook(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    webhook_id: Uuid,
+    Json(body): Json<UpdateWebhookBody>,
+) -> Result<Json<Webhook>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+    if let Some(url) = &body.url {
+        check_url(&ctx, url).map_err(|e| e.into_response())?;
+    }
+
+    let events = body.events.map(|mut events| {
+        events.sort();
+        events.dedup();
+        events
+    });
+
+    let result = sqlx::query_as!(
+        Webhook,
+        r#"update "webhooks" set
+            url = coalesce($3, url),
+            description = case when $4::text is null then description else nullif($4, '') end,
+            events = coalesce($5::text[], events),
+            enabled = coalesce($6, enabled)
+        where id = $1 and user_id = $2
+        returning id::text as "webhook_id!", url, description, events::text[] as "events!", enabled,
+            null::text as secret, created_at, updated_at"#,
+        webhook_id,
+        user_id,
+        body.url,
+        body.description,
+        events.as_deref(),
+        body.enabled
+    )
+    .fetch_optional(&ctx.db)
+    .await;
+
+    match result {
+        Ok(Some(webhook)) => Ok(Json(webhook)),
+        Ok(None) => Err(ApiError::NotFound("Webhook not found".to_string()).into_response()),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+/// Replaces the signing secret. Deliveries already queued are signed with the
+/// new one when next attempted.
+pub async fn rotate_webhook_secret(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    webhook_id: Uuid,
+) -> Result<Json<Webhook>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let result = sqlx::query_as!(
+        Webhook,
+        r#"update "webhooks" set secret = $3 where id = $1 and user_id = $2
+        returning id::text as "webhook_id!", url, description, events::text[] as "events!", enabled,
+            secret as "secret?", created_at, updated_at"#,
+        webhook_id,
+        user_id,
+        new_secret()
+    )
+    .fetch_optional(&ctx.db)
+    .await;
+
+    match result {
+        Ok(Some(webhook)) => Ok(Json(webhook)),
+        Ok(None) => Err(ApiError::NotFound("Webhook not found".to_string()).into_response()),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+pub async fn delete_webhook(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    webhook_id: Uuid,
+) -> Result<StatusCode, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let result = sqlx::query!(
+        r#"delete from "webhooks" where id = $1 and user_id = $2"#,
+        webhook_id,
+        user_id
+    )
+    .execute(&ctx.db)
+    .await;
+
+    match result {
+        Ok(result) if result.rows_affected() > 0 => Ok(StatusCode::NO_CONTENT),
+        Ok(_) => Err(ApiError::NotFound("Webhook not found".to_string()).into_response()),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+/// Queues a `webhook.ping` delivery, whatever events the endpoint subscribed to.
+pub async fn ping_webhook(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    webhook_id: Uuid,
+) -> Result<Json<WebhookDelivery>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let result = sqlx::query_as!(
+        WebhookDelivery,
+        r#"insert into "webhook_deliveries" (webhook_id, event, payload)
+        select id, $3, jsonb_build_object('webhook_id', id) from "webhooks"
+        where id = $1 and user_id = $2
+        returning id::text as "delivery_id!", event, status, attempts,
+            case when status = 'pending' then next_attempt_at end as next_attempt_at,
+            last_status_code, last_error, delivered_at, created_at"#,
+        webhook_id,
+        user_id,
+        PING_EVENT
+    )
+    .fetch_optional(&ctx.db)
+    .await;
+
+    match result {
+        Ok(Some(delivery)) => Ok(Json(delivery)),
+        Ok(None) => Err(ApiError::NotFound("Webhook not found".to_string()).into_response()),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+pub async fn list_deliveries(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    webhook_id: Uuid,
+    query: DeliveryQuery,
+) -> Result<Json<Vec<WebhookDelivery>>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+    let limit = query.limit.unwrap_or(50).clamp(1, 100);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    // an empty page for someone else's endpoint would tell it exists
+    let exists = sqlx::query_scalar!(
+        r#"select exists(select 1 from "webhooks" where id = $1 and user_id = $2) as "exists!""#,
+        webhook_id,
+        user_id
+    )
+    .fetch_one(&ctx.db)
+    .await
+    .map_err(|e| ApiError::Database(e).into_response())?;
+    if !exists {
+        return Err(ApiError::NotFound("Webhook not found".to_string()).into_response());
+    }
+
+    let result = sqlx::query_as!(
+        WebhookDelivery,
+        r#"select d.id::text as "delivery_id!", d.event, d.status, d.attempts,
+            case when d.status = 'pending' then d.next_attempt_at end as next_attempt_at,
+            d.last_status_code, d.last_error, d.delivered_at, d.created_at
+        from "webhook_deliveries" d join "webhooks" w on w.id = d.webhook_id
+        where d.webhook_id = $1 and w.user_id = $2 and ($3::text is null or d.status = $3)
+        order by d.created_at desc
+        limit $4 offset $5"#,
+        webhook_id,
+        user_id,
+        query.status.map(|status| status.as_str()),
+        limit,
+        offset
+    )
+    .fetch_all(&ctx.db)
+    .await;
+
+    match result {
+        Ok(deliveries) => Ok(Json(deliveries)),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
+
+pub async fn single_delivery(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    webhook_id: Uuid,
+    delivery_id: Uuid,
+) -> Result<Json<WebhookDeliveryLog>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let row = sqlx::query!(
+        r#"select d.id::text as "delivery_id!", d.event, d.status, d.attempts,
+            case when d.status = 'pending' then d.next_attempt_at end as next_attempt_at,
+            d.last_status_code, d.last_error, d.delivered_at, d.created_at, d.payload
+        from "webhook_deliveries" d join "webhooks" w on w.id = d.webhook_id
+        where d.id = $1 and d.webhook_id = $2 and w.user_id = $3"#,
+        delivery_id,
+        webhook_id,
+        user_id
+    )
+    .fetch_optional(&ctx.db)
+    .await
+    .map_err(|e| ApiError::Database(e).into_response())?
+    .ok_or_else(|| ApiError::NotFound("Delivery not found".to_string()).into_response())?;
+
+    let log = sqlx::query_as!(
+        WebhookAttempt,
+        r#"select status_code, error, response_body, duration_ms, created_at
+        from "webhook_attempts" where delivery_id = $1 order by id"#,
+        delivery_id
+    )
+    .fetch_all(&ctx.db)
+    .await
+    .map_err(|e| ApiError::Database(e).into_response())?;
+
+    Ok(Json(WebhookDeliveryLog {
+        delivery: WebhookDelivery {
+            delivery_id: row.delivery_id,
+            event: row.event,
+            status: row.status,
+            attempts: row.attempts,
+            next_attempt_at: row.next_attempt_at,
+            last_status_code: row.last_status_code,
+            last_error: row.last_error,
+            delivered_at: row.delivered_at,
+            created_at: row.created_at,
+        },
+        payload: row.payload,
+        log,
+    }))
+}
+
+/// Queues a delivery again right away with a fresh set of attempts, whatever
+/// became of it. The receiver sees the same delivery id as before.
+pub async fn redeliver(
+    ctx: Extension<ApiContext>,
+    claims: Claims,
+    webhook_id: Uuid,
+    delivery_id: Uuid,
+) -> Result<Json<WebhookDelivery>, Response<Body>> {
+    let user_id = claims.user_id().map_err(|e| e.into_response())?;
+
+    let result = sqlx::query_as!(
+        WebhookDelivery,
+        r#"update "webhook_deliveries" d
+        set status = 'pending', attempts = 0, next_attempt_at = now(), delivered_at = null
+        from "webhooks" w
+        where d.id = $1 and d.webhook_id = $2 and w.id = d.webhook_id and w.user_id = $3
+        returning d.id::text as "delivery_id!", d.event, d.status, d.attempts,
+            d.next_attempt_at as "next_attempt_at?", d.last_status_code, d.last_error,
+            d.delivered_at, d.created_at"#,
+        delivery_id,
+        webhook_id,
+        user_id
+    )
+    .fetch_optional(&ctx.db)
+    .await;
+
+    match result {
+        Ok(Some(delivery)) => Ok(Json(delivery)),
+        Ok(None) => Err(ApiError::NotFound("Delivery not found".to_string()).into_response()),
+        Err(e) => Err(ApiError::Database(e).into_response()),
+    }
+}
@@ -0,0 +1,3 @@
+mod webhook_error;
+
+pub use webhook_error::*;
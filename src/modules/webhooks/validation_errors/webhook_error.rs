@@ -0,0 +1,22 @@
+use crate::core::{error_transformer::error_transformer, traits::TransformValidationErrors};
+use validator::ValidationErrors;
+
+pub struct CreateWebhookValidationError;
+impl TransformValidationErrors for CreateWebhookValidationError {
+    fn new() -> Self {
+        CreateWebhookValidationError
+    }
+    fn transform_errors(&self, errors: ValidationErrors) -> Vec<String> {
+        error_transformer(errors)
+    }
+}
+
+pub struct UpdateWebhookValidationError;
+impl TransformValidationErrors for UpdateWebhookValidationError {
+    fn new() -> Self {
+        UpdateWebhookValidationError
+    }
+    fn transform_errors(&self, errors: ValidationErrors) -> Vec<String> {
+        error_transformer(errors)
+    }
+}
@@ -0,0 +1,29 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use super::controllers::{
+    find_deliveries, find_delivery, find_webhook, find_webhooks, handle_create_webhook,
+    handle_delete_webhook, handle_ping_webhook, handle_redeliver, handle_rotate_secret,
+    handle_update_webhook,
+};
+
+pub fn webhooks_routes() -> Router {
+    Router::new()
+        .route("/", get(find_webhooks).post(handle_create_webhook))
+        .route(
+            "/:webhook_id",
+            get(find_webhook)
+                .patch(handle_update_webhook)
+                .delete(handle_delete_webhook),
+        )
+        .route("/:webhook_id/secret", post(handle_rotate_secret))
+        .route("/:webhook_id/ping", post(handle_ping_webhook))
+        .route("/:webhook_id/deliveries", get(find_deliveries))
+        .route("/:webhook_id/deliveries/:delivery_id", get(find_delivery))
+        .route(
+            "/:webhook_id/deliveries/:delivery_id/redeliver",
+            post(handle_redeliver),
+        )
+}
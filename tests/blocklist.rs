@@ -0,0 +1,111 @@
+mod common;
+
+use axum::{
+    http::{Method, StatusCode},
+    Router,
+};
+use reminder_api::{core::traits::JobHandler, modules::replies::service::PruneDroppedClaims};
+use serde_json::json;
+use sqlx::PgPool;
+
+/// Has the recipient drop messages mentioning spam.
+async fn drop_spam(router: &Router, auth: &[(&str, &str)]) {
+    let (status, body) = common::call(
+        router,
+        Method::PATCH,
+        "/me/settings",
+        auth,
+        Some(json!({ "blocklist_action": "drop" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let (status, body) = common::call(
+        router,
+        Method::POST,
+        "/me/blocklist",
+        auth,
+        Some(json!({ "kind": "word", "pattern": "spam" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+}
+
+#[sqlx::test]
+async fn dropped_messages_use_up_capped_links(db: PgPool) {
+    let router = common::router(db);
+    let authorization = common::sign_up(&router, "drop@example.com", "dropper").await;
+    let auth = [("authorization", authorization.as_str())];
+
+    drop_spam(&router, &auth).await;
+    let (status, body) = common::call(
+        &router,
+        Method::POST,
+        "/me/links",
+        &auth,
+        Some(json!({ "slug": "one-shot", "name": "One shot", "max_messages": 1 })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+
+    let solution = common::pow_solution(&router, "/l/one-shot").await;
+    let (status, dropped) = common::call(
+        &router,
+        Method::POST,
+        "/l/one-shot/messages",
+        &[("x-pow-solution", &solution)],
+        Some(json!({ "body": "Buy my spam now" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{dropped}");
+
+    // the link is used up as if the message had been delivered
+    let (status, body) =
+        common::call(&router, Method::GET, "/l/one-shot/challenge", &[], None).await;
+    assert_eq!(status, StatusCode::GONE, "{body}");
+    let (_, inbox) = common::call(&router, Method::GET, "/inbox", &auth, None).await;
+    assert_eq!(inbox, json!([]));
+}
+
+#[sqlx::test]
+async fn claims_on_dropped_messages_read_as_unread(db: PgPool) {
+    let router = common::router(db.clone());
+    let authorization = common::sign_up(&router, "claim@example.com", "claimer").await;
+    drop_spam(&router, &[("authorization", authorization.as_str())]).await;
+
+    let solution = common::pow_solution(&router, "/u/claimer").await;
+    let (status, dropped) = common::call(
+        &router,
+        Method::POST,
+        "/u/claimer/messages",
+        &[("x-pow-solution", &solution)],
+        Some(json!({ "body": "Buy my spam now", "claim_code": true })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{dropped}");
+    let reply = format!("/replies/{}", dropped["claim_code"].as_str().unwrap());
+
+    let (status, claim) = common::call(&router, Method::GET, &reply, &[], None).await;
+    assert_eq!(status, StatusCode::OK, "{claim}");
+    assert_eq!(claim["read"], json!(false));
+    assert_eq!(claim["reply"], json!(null));
+
+    // nothing of the message is kept, and the claim goes after 30 days
+    let columns: Vec<String> = sqlx::query_scalar(
+        "select column_name::text from information_schema.columns where table_name = 'dropped_claims' order by 1",
+    )
+    .fetch_all(&db)
+    .await
+    .unwrap();
+    assert_eq!(columns, ["claim_hash", "created_at", "recipient_id"]);
+    let ctx = common::context(db.clone());
+    PruneDroppedClaims.run(&ctx, ()).await.unwrap();
+    let (status, _) = common::call(&router, Method::GET, &reply, &[], None).await;
+    assert_eq!(status, StatusCode::OK);
+    sqlx::query(r#"update "dropped_claims" set created_at = now() - interval '31 days'"#)
+        .execute(&db)
+        .await
+        .unwrap();
+    PruneDroppedClaims.run(&ctx, ()).await.unwrap();
+    let (status, _) = common::call(&router, Method::GET, &reply, &[], None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
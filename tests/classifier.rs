@@ -0,0 +1,51 @@
+mod common;
+
+use reminder_api::core::{
+    classifier::SpamRules, models::IncomingMessage, traits::MessageClassifier,
+};
+use sqlx::PgPool;
+
+#[sqlx::test]
+async fn scores_spam_rules_together(db: PgPool) {
+    let recipient_id = common::create_user(&db, "spam@example.com").await;
+    let rules = SpamRules::new(db.clone());
+    let score = |body: &'static str, content_hash: &'static [u8]| {
+        let rules = &rules;
+        async move {
+            rules
+                .score(&IncomingMessage {
+                    recipient_id,
+                    body,
+                    content_hash,
+                })
+                .await
+                .unwrap()
+        }
+    };
+
+    assert_eq!(score("what a lovely question", b"lovely").await, 0.0);
+    assert_eq!(score("https://example.com", b"link").await, 1.0);
+    assert_eq!(
+        score("read more at www.example.com right now", b"link").await,
+        0.5
+    );
+
+    // the same content sent to other recipients
+    for n in 0..5 {
+        let other = common::create_user(&db, &format!("other{n}@example.com")).await;
+        sqlx::query(
+            r#"insert into "messages" (recipient_id, body, content_hash) values ($1, 'hi', $2)"#,
+        )
+        .bind(other)
+        .bind(&b"copied"[..])
+        .execute(&db)
+        .await
+        .unwrap();
+        if n == 1 {
+            // a 0.4 chance of spam from the copies and 0.5 from the URL
+            let mixed = score("read more at www.example.com right now", b"copied").await;
+            assert!((mixed - 0.7).abs() < 1e-6, "{mixed}");
+        }
+    }
+    assert_eq!(score("hi", b"copied").await, 1.0);
+}
@@ -0,0 +1,134 @@
+#![allow(dead_code)]
+
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Once},
+};
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::ConnectInfo,
+    http::{header, Method, Request, StatusCode},
+    Router,
+};
+use reminder_api::{
+    app,
+    config::Config,
+    core::{
+        rate_limit::{MemoryRateLimitStore, RateLimits},
+        utils::solves,
+    },
+    ApiContext,
+};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+static ENV: Once = Once::new();
+
+/// A context on the database of one `sqlx::test`, configured from the
+/// environment and `.env`, with a JWT secret of its own when none is set.
+pub fn context(db: PgPool) -> ApiContext {
+    ENV.call_once(|| {
+        dotenv::dotenv().ok();
+        if std::env::var("JWT_SECRET").is_err() {
+            std::env::set_var("JWT_SECRET", "test-secret");
+        }
+    });
+    ApiContext::new(
+        Config::parse(),
+        db,
+        Arc::new(MemoryRateLimitStore::default()),
+    )
+}
+
+pub async fn create_user(db: &PgPool, email: &str) -> Uuid {
+    sqlx::query_scalar(
+        r#"insert into "users" (email, password, name) values ($1, 'password', 'Test')
+        returning id"#,
+    )
+    .bind(email)
+    .fetch_one(db)
+    .await
+    .unwrap()
+}
+
+/// Every route of the API, as `main` serves them.
+pub fn router(db: PgPool) -> Router {
+    let ctx = context(db);
+    let rate_limits = RateLimits::new(&ctx.config, ctx.rate_limit_store.clone());
+    app(ctx, &rate_limits)
+}
+
+/// Sends one request as a client at 127.0.0.1, and reads the JSON response.
+pub async fn call(
+    router: &Router,
+    method: Method,
+    uri: &str,
+    headers: &[(&str, &str)],
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let mut request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(header::USER_AGENT, "integration-test");
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    let mut request = match body {
+        Some(body) => request
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string())),
+        None => request.body(Body::empty()),
+    }
+    .unwrap();
+    request
+        .extensions_mut()
+        .insert(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 50000))));
+
+    let response = router.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+/// Signs up a user with `profile_link`, and returns their `Authorization` header.
+pub async fn sign_up(router: &Router, email: &str, profile_link: &str) -> String {
+    let (status, user) = call(
+        router,
+        Method::POST,
+        "/auth/signup",
+        &[],
+        Some(json!({ "email": email, "password": "password1", "name": "Test" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{user}");
+    let authorization = format!("Bearer {}", user["token"].as_str().unwrap());
+
+    let (status, body) = call(
+        router,
+        Method::PATCH,
+        "/me",
+        &[("authorization", &authorization)],
+        Some(json!({ "profile_link": profile_link })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    authorization
+}
+
+/// A header solving the proof-of-work challenge of a profile (`/u/:link`) or
+/// named link (`/l/:slug`).
+pub async fn pow_solution(router: &Router, page: &str) -> String {
+    let (status, challenge) =
+        call(router, Method::GET, &format!("{page}/challenge"), &[], None).await;
+    assert_eq!(status, StatusCode::OK, "{challenge}");
+    let difficulty = challenge["difficulty"].as_u64().unwrap() as u32;
+    let challenge = challenge["challenge"].as_str().unwrap();
+    let counter = (0u64..)
+        .map(|counter| counter.to_string())
+        .find(|counter| solves(challenge, counter, difficulty))
+        .unwrap();
+    format!("{challenge}:{counter}")
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use reminder_api::core::utils::{
+    decode_e2e_key, decrypt_e2e_message, encrypt_e2e_message, generate_e2e_keys,
+};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+
+#[sqlx::test]
+async fn delivers_envelopes_the_recipient_can_decrypt(db: PgPool) {
+    let router = common::router(db);
+    let authorization = common::sign_up(&router, "e2e@example.com", "e2e-recipient").await;
+    let auth = [("authorization", authorization.as_str())];
+
+    // the secret key stays here, only the public key is registered
+    let (secret_key, public_key) = generate_e2e_keys();
+    let (status, key) = common::call(
+        &router,
+        Method::POST,
+        "/me/encryption-keys",
+        &auth,
+        Some(json!({ "public_key": public_key })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{key}");
+
+    let (status, link) = common::call(
+        &router,
+        Method::POST,
+        "/me/links",
+        &auth,
+        Some(json!({ "slug": "e2e-only", "name": "E2E only", "require_encryption": true })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{link}");
+
+    // senders find the key to encrypt to on the public profile
+    let (status, profile) = common::call(&router, Method::GET, "/u/e2e-recipient", &[], None).await;
+    assert_eq!(status, StatusCode::OK, "{profile}");
+    let published = &profile["encryption_key"];
+    assert_eq!(published["key_id"], key["key_id"]);
+    assert_eq!(published["public_key"], public_key);
+    let (status, page) = common::call(&router, Method::GET, "/l/e2e-only", &[], None).await;
+    assert_eq!(status, StatusCode::OK, "{page}");
+    assert_eq!(page["encryption_required"], true);
+
+    let solution = common::pow_solution(&router, "/l/e2e-only").await;
+    let (status, refused) = common::call(
+        &router,
+        Method::POST,
+        "/l/e2e-only/messages",
+        &[("x-pow-solution", &solution)],
+        Some(json!({ "body": "Plaintext nobody should store" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{refused}");
+    assert_eq!(
+        refused["errors"][0],
+        "body: the recipient only accepts end-to-end encrypted messages."
+    );
+
+    let recipient_key = decode_e2e_key(published["public_key"].as_str().unwrap()).unwrap();
+    let envelope = encrypt_e2e_message(&recipient_key, "Only you can read this").unwrap();
+    let solution = common::pow_solution(&router, "/l/e2e-only").await;
+    let (status, submitted) = common::call(
+        &router,
+        Method::POST,
+        "/l/e2e-only/messages",
+        &[("x-pow-solution", &solution)],
+        Some(json!({ "body": envelope, "encryption_key_id": published["key_id"] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{submitted}");
+
+    let (status, inbox) = common::call(&router, Method::GET, "/inbox", &auth, None).await;
+    assert_eq!(status, StatusCode::OK, "{inbox}");
+    let messages = inbox.as_array().unwrap();
+    assert_eq!(messages.len(), 1, "{inbox}");
+    let message: &Value = &messages[0];
+    assert_eq!(message["message_id"], submitted["message_id"]);
+    assert_eq!(message["encryption_key_id"], key["key_id"]);
+    assert_eq!(message["body"], envelope);
+    assert_eq!(
+        decrypt_e2e_message(
+            &decode_e2e_key(&secret_key).unwrap(),
+            message["body"].as_str().unwrap()
+        )
+        .unwrap(),
+        "Only you can read this"
+    );
+}
@@ -0,0 +1,127 @@
+mod common;
+
+use std::sync::Arc;
+
+use axum::http::{Method, StatusCode};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use reminder_api::{
+    app,
+    config::Config,
+    core::{
+        classifier::{content_hash, content_key},
+        keyring::{Keyring, SealStoredBodies},
+        rate_limit::RateLimits,
+        traits::JobHandler,
+    },
+    ApiContext,
+};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// A context sealing bodies at rest.
+fn sealing_context(db: PgPool) -> ApiContext {
+    let mut ctx = common::context(db);
+    let config = Config {
+        master_key: STANDARD.encode([7; 32]),
+        ..Config::default()
+    };
+    ctx.keyring = Arc::new(Keyring::new(&config));
+    ctx
+}
+
+#[sqlx::test]
+async fn sealing_stored_bodies_leaves_no_plaintext(db: PgPool) {
+    let ctx = sealing_context(db.clone());
+
+    let body = "Meet me by the old oak tree";
+    let keyed_hash = content_hash(&content_key(&ctx.config.jwt_secret), body);
+    let recipient_id = common::create_user(&db, "sealed@example.com").await;
+    sqlx::query(
+        r#"insert into "webhooks" (user_id, url, secret, events)
+        values ($1, 'https://example.com/hook', 'whsec_test', array['message.received'])"#,
+    )
+    .bind(recipient_id)
+    .execute(&db)
+    .await
+    .unwrap();
+    let message_id: Uuid = sqlx::query_scalar(
+        r#"insert into "messages" (recipient_id, body, content_hash) values ($1, $2, $3)
+        returning id"#,
+    )
+    .bind(recipient_id)
+    .bind(body)
+    .bind(&keyed_hash)
+    .fetch_one(&db)
+    .await
+    .unwrap();
+    let indexed: bool =
+        sqlx::query_scalar(r#"select search_vector is not null from "messages" where id = $1"#)
+            .bind(message_id)
+            .fetch_one(&db)
+            .await
+            .unwrap();
+    assert!(indexed);
+    let queued: String = sqlx::query_scalar(r#"select payload->>'body' from "webhook_deliveries""#)
+        .fetch_one(&db)
+        .await
+        .unwrap();
+    assert_eq!(queued, body);
+
+    SealStoredBodies.run(&ctx, ()).await.unwrap();
+
+    let (stored, sealed, indexed, hash): (Option<String>, Vec<u8>, bool, Vec<u8>) = sqlx::query_as(
+        r#"select body, sealed_body, search_vector is not null, content_hash
+            from "messages" where id = $1"#,
+    )
+    .bind(message_id)
+    .fetch_one(&db)
+    .await
+    .unwrap();
+    assert_eq!(stored, None);
+    assert!(!indexed, "search_vector still holds the words of the body");
+    // keyed, so it can't be matched against guesses without the server secret
+    assert_eq!(hash, keyed_hash);
+    assert_ne!(hash, Sha256::digest(body).to_vec());
+    assert_eq!(
+        ctx.keyring.open(&db, recipient_id, &sealed).await.unwrap(),
+        body
+    );
+
+    // the delivery queued with the message gets its body back when it is sent
+    let (queued_for, queued_body): (String, serde_json::Value) = sqlx::query_as(
+        r#"select payload->>'message_id', payload->'body' from "webhook_deliveries""#,
+    )
+    .fetch_one(&db)
+    .await
+    .unwrap();
+    assert_eq!(queued_for, message_id.to_string());
+    assert_eq!(queued_body, serde_json::Value::Null);
+
+    let matches: i64 = sqlx::query_scalar(
+        r#"select count(*) from "messages"
+        where search_vector @@ to_tsquery('simple', 'oak')"#,
+    )
+    .fetch_one(&db)
+    .await
+    .unwrap();
+    assert_eq!(matches, 0);
+}
+
+#[sqlx::test]
+async fn search_is_refused_while_sealing(db: PgPool) {
+    let ctx = sealing_context(db);
+    let rate_limits = RateLimits::new(&ctx.config, ctx.rate_limit_store.clone());
+    let router = app(ctx, &rate_limits);
+    let authorization = common::sign_up(&router, "search@example.com", "searcher").await;
+
+    let (status, body) = common::call(
+        &router,
+        Method::GET,
+        "/inbox/search?q=oak",
+        &[("authorization", &authorization)],
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT, "{body}");
+}
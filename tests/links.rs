@@ -0,0 +1,114 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use serde_json::json;
+use sqlx::PgPool;
+use tokio::task::JoinSet;
+
+#[sqlx::test]
+async fn concurrent_creations_stay_within_the_cap(db: PgPool) {
+    let router = common::router(db.clone());
+    let authorization = common::sign_up(&router, "links@example.com", "linker").await;
+    sqlx::query(
+        r#"insert into "links" (user_id, slug, name)
+        select id, 'link-' || n, 'Link' from "users", generate_series(1, 49) n"#,
+    )
+    .execute(&db)
+    .await
+    .unwrap();
+
+    let mut requests = JoinSet::new();
+    for n in 0..5 {
+        let router = router.clone();
+        let authorization = authorization.clone();
+        requests.spawn(async move {
+            common::call(
+                &router,
+                Method::POST,
+                "/me/links",
+                &[("authorization", &authorization)],
+                Some(json!({ "slug": format!("racer-{n}"), "name": "Racer" })),
+            )
+            .await
+            .0
+        });
+    }
+    let mut created = 0;
+    while let Some(status) = requests.join_next().await {
+        match status.unwrap() {
+            StatusCode::CREATED => created += 1,
+            status => assert_eq!(status, StatusCode::BAD_REQUEST),
+        }
+    }
+    assert_eq!(created, 1);
+
+    let links: i64 = sqlx::query_scalar(r#"select count(*) from "links""#)
+        .fetch_one(&db)
+        .await
+        .unwrap();
+    assert_eq!(links, 50);
+}
+
+#[sqlx::test]
+async fn updates_only_the_fields_sent(db: PgPool) {
+    let router = common::router(db);
+    let authorization = common::sign_up(&router, "links@example.com", "linker").await;
+    let auth = [("authorization", authorization.as_str())];
+    let (status, link) = common::call(
+        &router,
+        Method::POST,
+        "/me/links",
+        &auth,
+        Some(json!({
+            "slug": "capped",
+            "name": "Capped",
+            "prompt": "Ask away",
+            "max_messages": 5,
+            "min_length": 3,
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{link}");
+    let uri = format!("/me/links/{}", link["link_id"].as_str().unwrap());
+
+    let (status, link) = common::call(
+        &router,
+        Method::PATCH,
+        &uri,
+        &auth,
+        Some(json!({ "name": "Renamed" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{link}");
+    assert_eq!(link["name"], json!("Renamed"));
+    assert_eq!(link["slug"], json!("capped"));
+    assert_eq!(link["prompt"], json!("Ask away"));
+    assert_eq!(link["max_messages"], json!(5));
+    assert_eq!(link["min_length"], json!(3));
+
+    // null clears a nullable field, an empty string a text one
+    let (status, link) = common::call(
+        &router,
+        Method::PATCH,
+        &uri,
+        &auth,
+        Some(json!({ "max_messages": null, "prompt": "", "slug": "uncapped" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{link}");
+    assert_eq!(link["slug"], json!("uncapped"));
+    assert_eq!(link["prompt"], json!(null));
+    assert_eq!(link["max_messages"], json!(null));
+    assert_eq!(link["min_length"], json!(3));
+    assert_eq!(link["name"], json!("Renamed"));
+
+    let (status, _) = common::call(
+        &router,
+        Method::PATCH,
+        "/me/links/00000000-0000-0000-0000-000000000000",
+        &auth,
+        Some(json!({ "name": "Nobody's" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
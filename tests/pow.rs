@@ -0,0 +1,159 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use chrono::Utc;
+use hmac::Mac;
+use reminder_api::{
+    core::{
+        models::ApiError,
+        utils::{challenge_key, challenge_mac, solves},
+    },
+    modules::profile::service::redeem_solution,
+    ApiContext,
+};
+use serde_json::json;
+use sqlx::PgPool;
+
+const SENDER: &[u8] = b"sender";
+
+/// A solved challenge for `target` and `SENDER`, signed as `issue_challenge`
+/// signs them.
+fn solution(ctx: &ApiContext, target: &str, expires: i64) -> String {
+    let nonce: [u8; 16] = rand::random();
+    let signature = challenge_mac(
+        &challenge_key(&ctx.config.jwt_secret),
+        target,
+        expires,
+        4,
+        &nonce,
+        SENDER,
+    )
+    .finalize()
+    .into_bytes();
+    let challenge = format!(
+        "{expires}.4.{}.{}",
+        hex::encode(nonce),
+        hex::encode(signature)
+    );
+    let counter = (0u64..)
+        .map(|counter| counter.to_string())
+        .find(|counter| solves(&challenge, counter, 4))
+        .unwrap();
+    format!("{challenge}:{counter}")
+}
+
+async fn redeem(ctx: &ApiContext, target: &str, solution: &str) -> Result<(), ApiError> {
+    redeem_solution(ctx, target, &[SENDER.to_vec()], Some(solution)).await
+}
+
+#[sqlx::test]
+async fn redeems_a_solution_once(db: PgPool) {
+    let ctx = common::context(db.clone());
+    let solution = solution(&ctx, "alice", Utc::now().timestamp() + 60);
+
+    redeem(&ctx, "alice", &solution).await.unwrap();
+    let redeemed: i64 = sqlx::query_scalar(r#"select count(*) from "pow_redemptions""#)
+        .fetch_one(&db)
+        .await
+        .unwrap();
+    assert_eq!(redeemed, 1);
+
+    assert!(matches!(
+        redeem(&ctx, "alice", &solution).await,
+        Err(ApiError::Forbidden(_))
+    ));
+}
+
+#[sqlx::test]
+async fn refuses_expired_and_foreign_solutions(db: PgPool) {
+    let ctx = common::context(db);
+
+    let expired = solution(&ctx, "alice", Utc::now().timestamp() - 1);
+    assert!(matches!(
+        redeem(&ctx, "alice", &expired).await,
+        Err(ApiError::Forbidden(_))
+    ));
+
+    let solution = solution(&ctx, "alice", Utc::now().timestamp() + 60);
+    assert!(matches!(
+        redeem(&ctx, "l/alice", &solution).await,
+        Err(ApiError::Forbidden(_))
+    ));
+    assert!(matches!(
+        redeem_solution(&ctx, "alice", &[b"other".to_vec()], Some(&solution)).await,
+        Err(ApiError::Forbidden(_))
+    ));
+    let (challenge, _) = solution.rsplit_once(':').unwrap();
+    assert!(matches!(
+        redeem(&ctx, "alice", &format!("{challenge}:not-a-solution")).await,
+        Err(ApiError::Forbidden(_))
+    ));
+    assert!(matches!(
+        redeem_solution(&ctx, "alice", &[SENDER.to_vec()], None).await,
+        Err(ApiError::Forbidden(_))
+    ));
+
+    // none of the refusals spent the challenge
+    redeem(&ctx, "alice", &solution).await.unwrap();
+}
+
+#[sqlx::test]
+async fn unsolved_submissions_keep_the_sender_tokens(db: PgPool) {
+    let router = common::router(db);
+    common::sign_up(&router, "pow@example.com", "powder").await;
+
+    // more than the 3 a minute a sender may send
+    for _ in 0..4 {
+        let (status, body) = common::call(
+            &router,
+            Method::POST,
+            "/u/powder/messages",
+            &[],
+            Some(json!({ "body": "Hello there" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "{body}");
+    }
+
+    let solution = common::pow_solution(&router, "/u/powder").await;
+    let (status, body) = common::call(
+        &router,
+        Method::POST,
+        "/u/powder/messages",
+        &[("x-pow-solution", &solution)],
+        Some(json!({ "body": "Hello there" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+}
+
+#[sqlx::test]
+async fn bursts_raise_the_difficulty_of_their_link_only(db: PgPool) {
+    let router = common::router(db.clone());
+    let authorization = common::sign_up(&router, "burst@example.com", "bursty").await;
+    let (status, body) = common::call(
+        &router,
+        Method::POST,
+        "/me/links",
+        &[("authorization", &authorization)],
+        Some(json!({ "slug": "quiet", "name": "Quiet" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+
+    let config = common::context(db.clone()).config;
+    let (difficulty, threshold) = (config.pow_difficulty, config.pow_burst_threshold);
+    sqlx::query(
+        r#"insert into "messages" (recipient_id, body)
+        select id, 'Hello' from "users", generate_series(1, $1)"#,
+    )
+    .bind(threshold as i32)
+    .execute(&db)
+    .await
+    .unwrap();
+
+    let (_, profile) = common::call(&router, Method::GET, "/u/bursty/challenge", &[], None).await;
+    assert_eq!(profile["difficulty"], json!(difficulty + 1));
+    let (_, link) = common::call(&router, Method::GET, "/l/quiet/challenge", &[], None).await;
+    assert_eq!(link["difficulty"], json!(difficulty));
+}
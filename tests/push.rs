@@ -0,0 +1,84 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use anyhow::Error;
+use axum::async_trait;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use p256::{elliptic_curve::sec1::ToEncodedPoint, SecretKey};
+use rand::rngs::OsRng;
+use reminder_api::{
+    core::{
+        models::PushMessage,
+        traits::{JobHandler, PushSender},
+    },
+    modules::push::{models::PushJob, service::SendPush},
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Answers every endpoint with the status in its last path segment.
+#[derive(Default)]
+struct FakePushSender {
+    sent: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl PushSender for FakePushSender {
+    async fn send(&self, message: PushMessage) -> Result<u16, Error> {
+        let status = message.endpoint.rsplit('/').next().unwrap().parse()?;
+        self.sent.lock().unwrap().push(message.endpoint);
+        Ok(status)
+    }
+}
+
+async fn subscribe(db: &PgPool, user_id: Uuid, status: u16) -> Uuid {
+    let p256dh = SecretKey::random(&mut OsRng)
+        .public_key()
+        .to_encoded_point(false);
+    sqlx::query_scalar(
+        r#"insert into "push_subscriptions" (user_id, endpoint, p256dh, auth)
+        values ($1, $2, $3, 'BTBZMqHH6r4Tts7J_aSIgg') returning id"#,
+    )
+    .bind(user_id)
+    .bind(format!("https://push.example.com/{status}"))
+    .bind(URL_SAFE_NO_PAD.encode(p256dh.as_bytes()))
+    .fetch_one(db)
+    .await
+    .unwrap()
+}
+
+#[sqlx::test]
+async fn prunes_subscriptions_the_push_service_forgot(db: PgPool) {
+    let sender = Arc::new(FakePushSender::default());
+    let mut ctx = common::context(db.clone());
+    ctx.push_sender = sender.clone();
+
+    let user_id = common::create_user(&db, "push@example.com").await;
+    let delivered = subscribe(&db, user_id, 201).await;
+    let not_found = subscribe(&db, user_id, 404).await;
+    let gone = subscribe(&db, user_id, 410).await;
+    let failing = subscribe(&db, user_id, 503).await;
+
+    for subscription_id in [delivered, not_found, gone] {
+        let job = PushJob {
+            subscription_id,
+            message_id: None,
+        };
+        SendPush.run(&ctx, job).await.unwrap();
+    }
+    let job = PushJob {
+        subscription_id: failing,
+        message_id: None,
+    };
+    assert!(SendPush.run(&ctx, job).await.is_err());
+    assert_eq!(sender.sent.lock().unwrap().len(), 4);
+
+    let left: Vec<(Uuid, bool)> = sqlx::query_as(
+        r#"select id, last_success_at is not null from "push_subscriptions" order by endpoint"#,
+    )
+    .fetch_all(&db)
+    .await
+    .unwrap();
+    assert_eq!(left, vec![(delivered, true), (failing, false)]);
+}
@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use reminder_api::core::{rate_limit::PostgresRateLimitStore, traits::RateLimitStore};
+use sqlx::PgPool;
+
+async fn take(store: &PostgresRateLimitStore, key: &str, n: usize) -> Vec<bool> {
+    let mut allowed = vec![];
+    for _ in 0..n {
+        allowed.push(store.acquire(key, 3, 20.0).await.unwrap().allowed);
+    }
+    allowed
+}
+
+#[sqlx::test]
+async fn allows_up_to_capacity_per_key(db: PgPool) {
+    let store = PostgresRateLimitStore::new(db);
+    let first = store.acquire("ip:1", 3, 0.01).await.unwrap();
+    assert!(first.allowed);
+    assert_eq!((first.limit, first.remaining), (3, 2));
+    store.acquire("ip:1", 3, 0.01).await.unwrap();
+    store.acquire("ip:1", 3, 0.01).await.unwrap();
+
+    let refused = store.acquire("ip:1", 3, 0.01).await.unwrap();
+    assert!(!refused.allowed);
+    assert_eq!(refused.remaining, 0);
+    assert!(refused.retry_after > Duration::from_secs(90));
+    assert!(store
+        .recently_limited("ip:1", Duration::from_secs(60))
+        .await
+        .unwrap());
+
+    // another key has a bucket of its own
+    assert!(store.acquire("ip:2", 3, 0.01).await.unwrap().allowed);
+    assert!(!store
+        .recently_limited("ip:2", Duration::from_secs(60))
+        .await
+        .unwrap());
+}
+
+#[sqlx::test]
+async fn refills_up_to_capacity(db: PgPool) {
+    let store = PostgresRateLimitStore::new(db);
+    assert_eq!(take(&store, "ip:1", 4).await, [true, true, true, false]);
+
+    // 20 tokens a second, 2 back after 100ms
+    tokio::time::sleep(Duration::from_millis(110)).await;
+    assert_eq!(take(&store, "ip:1", 3).await, [true, true, false]);
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert_eq!(take(&store, "ip:1", 4).await, [true, true, true, false]);
+}